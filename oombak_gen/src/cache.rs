@@ -0,0 +1,294 @@
+use std::{
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+    process::Command,
+    time::{Duration, SystemTime},
+};
+
+use sha2::{Digest, Sha256};
+use tempfile::TempDir;
+
+use crate::{error::OombakGenResult, source::SourceConfig, TempGenDir};
+
+const LIB_FILE_NAME: &str = "libdut.so";
+const ENTRY_FILE_NAME: &str = "entry";
+
+/// Persistent store of built DUT libraries, keyed by everything that goes into a build.
+pub struct BuildCache {
+    dir: PathBuf,
+}
+
+#[derive(Clone, Debug)]
+pub struct CacheEntry {
+    pub key: String,
+    pub top_module_name: String,
+    pub size: u64,
+    pub last_used: SystemTime,
+}
+
+impl BuildCache {
+    pub fn new(dir: &Path) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+        }
+    }
+
+    /// Cache under `$XDG_CACHE_HOME/oombak`, falling back to `~/.cache/oombak`.
+    pub fn open_default() -> Option<Self> {
+        let cache_home = match std::env::var_os("XDG_CACHE_HOME") {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => PathBuf::from(std::env::var_os("HOME")?).join(".cache"),
+        };
+        Some(Self::new(&cache_home.join("oombak")))
+    }
+
+    /// Hash of the design sources, top module, parameter overrides, every generated source in
+    /// `gen_dir` and the Verilator version.
+    pub fn key_of(source_config: &SourceConfig, gen_dir: &Path) -> OombakGenResult<String> {
+        let mut hasher = Sha256::new();
+        hash_field(&mut hasher, env!("CARGO_PKG_VERSION").as_bytes());
+        hash_field(&mut hasher, &verilator_version()?);
+        hash_field(&mut hasher, source_config.top_module_name.as_bytes());
+        for parameter in source_config.parameters.iter() {
+            hash_field(
+                &mut hasher,
+                format!("{}={}", parameter.name, parameter.value).as_bytes(),
+            );
+        }
+        hash_gen_dir(&mut hasher, gen_dir)?;
+        for source_path in source_config.parser_source_paths()? {
+            hash_file(&mut hasher, Path::new(&source_path))?;
+        }
+        for include_dir in source_config.include_dirs.iter() {
+            let mut include_files = vec![];
+            collect_files(include_dir, &mut include_files)?;
+            include_files.sort();
+            for include_file in include_files {
+                hash_file(&mut hasher, &include_file)?;
+            }
+        }
+        Ok(hasher
+            .finalize()
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect())
+    }
+
+    /// Copies the cached library into a fresh temporary directory. A separate copy is loaded on
+    /// every hit, since loading the same path twice would share the model's static state.
+    pub fn fetch(&self, key: &str) -> OombakGenResult<Option<TempGenDir>> {
+        let entry_dir = self.dir.join(key);
+        let cached_lib_path = entry_dir.join(LIB_FILE_NAME);
+        if !cached_lib_path.is_file() {
+            return Ok(None);
+        }
+        let tempdir = TempDir::new()?;
+        fs::copy(&cached_lib_path, tempdir.path().join(LIB_FILE_NAME))?;
+        File::options()
+            .write(true)
+            .open(entry_dir.join(ENTRY_FILE_NAME))?
+            .set_modified(SystemTime::now())?;
+        Ok(Some(TempGenDir {
+            tempdir,
+            lib_path: PathBuf::from(LIB_FILE_NAME),
+            key: key.to_string(),
+        }))
+    }
+
+    pub fn store(
+        &self,
+        key: &str,
+        source_config: &SourceConfig,
+        lib_path: &Path,
+    ) -> OombakGenResult<()> {
+        let entry_dir = self.dir.join(key);
+        fs::create_dir_all(&entry_dir)?;
+        let partial_lib_path = entry_dir.join(format!("{LIB_FILE_NAME}.partial"));
+        fs::copy(lib_path, &partial_lib_path)?;
+        let mut entry_file = File::create(entry_dir.join(ENTRY_FILE_NAME))?;
+        writeln!(entry_file, "{}", source_config.top_module_name)?;
+        fs::rename(partial_lib_path, entry_dir.join(LIB_FILE_NAME))?;
+        Ok(())
+    }
+
+    pub fn list(&self) -> OombakGenResult<Vec<CacheEntry>> {
+        if !self.dir.is_dir() {
+            return Ok(vec![]);
+        }
+        let mut entries = vec![];
+        for entry_dir in fs::read_dir(&self.dir)? {
+            let entry_dir = entry_dir?.path();
+            let lib_path = entry_dir.join(LIB_FILE_NAME);
+            let entry_path = entry_dir.join(ENTRY_FILE_NAME);
+            if !lib_path.is_file() || !entry_path.is_file() {
+                continue;
+            }
+            let top_module_name = fs::read_to_string(&entry_path)?
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .to_string();
+            entries.push(CacheEntry {
+                key: entry_dir
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                top_module_name,
+                size: fs::metadata(&lib_path)?.len(),
+                last_used: fs::metadata(&entry_path)?.modified()?,
+            });
+        }
+        entries.sort_by_key(|e| std::cmp::Reverse(e.last_used));
+        Ok(entries)
+    }
+
+    /// Removes entries which have not been used for at least `max_age`. Returns the number
+    /// removed.
+    pub fn prune(&self, max_age: Duration) -> OombakGenResult<usize> {
+        let now = SystemTime::now();
+        let mut removed = 0;
+        for entry in self.list()? {
+            let age = now.duration_since(entry.last_used).unwrap_or_default();
+            if age >= max_age {
+                fs::remove_dir_all(self.dir.join(&entry.key))?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Removes every entry. Returns the number removed.
+    pub fn clear(&self) -> OombakGenResult<usize> {
+        self.prune(Duration::ZERO)
+    }
+}
+
+fn hash_field(hasher: &mut Sha256, bytes: &[u8]) {
+    hasher.update((bytes.len() as u64).to_le_bytes());
+    hasher.update(bytes);
+}
+
+fn hash_file(hasher: &mut Sha256, path: &Path) -> OombakGenResult<()> {
+    hash_field(hasher, path.to_string_lossy().as_bytes());
+    hash_field(hasher, &fs::read(path)?);
+    Ok(())
+}
+
+/// Generated files are hashed by their path relative to `gen_dir`, which is a fresh temporary
+/// directory on every build.
+fn hash_gen_dir(hasher: &mut Sha256, gen_dir: &Path) -> OombakGenResult<()> {
+    let mut gen_files = vec![];
+    collect_files(gen_dir, &mut gen_files)?;
+    gen_files.sort();
+    for gen_file in gen_files {
+        let relative_path = gen_file.strip_prefix(gen_dir).unwrap_or(&gen_file);
+        hash_field(hasher, relative_path.to_string_lossy().as_bytes());
+        hash_field(hasher, &fs::read(&gen_file)?);
+    }
+    Ok(())
+}
+
+/// Output of `verilator --version`, taken from `$VERILATOR_ROOT` when set as the build does.
+fn verilator_version() -> OombakGenResult<Vec<u8>> {
+    let verilator = match std::env::var_os("VERILATOR_ROOT") {
+        Some(root) if !root.is_empty() => PathBuf::from(root).join("bin").join("verilator"),
+        _ => PathBuf::from("verilator"),
+    };
+    Ok(Command::new(verilator).arg("--version").output()?.stdout)
+}
+
+/// Every file under `dir`, including those in nested directories.
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> OombakGenResult<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else if path.is_file() {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fs, time::Duration};
+
+    use tempfile::TempDir;
+
+    use sha2::{Digest, Sha256};
+
+    use super::{collect_files, hash_gen_dir, BuildCache};
+    use crate::source::SourceConfig;
+
+    #[test]
+    fn test_store_fetch_clear() {
+        let cache_dir = TempDir::new().unwrap();
+        let cache = BuildCache::new(cache_dir.path());
+        let build_dir = TempDir::new().unwrap();
+        let lib_path = build_dir.path().join("libdut.so");
+        fs::write(&lib_path, "library").unwrap();
+        let source_config = SourceConfig {
+            top_module_name: "sample".to_string(),
+            ..SourceConfig::default()
+        };
+
+        assert!(cache.fetch("abc").unwrap().is_none());
+        cache.store("abc", &source_config, &lib_path).unwrap();
+        let temp_gen_dir = cache.fetch("abc").unwrap().unwrap();
+        assert_eq!(
+            fs::read_to_string(temp_gen_dir.lib_path()).unwrap(),
+            "library"
+        );
+        assert_ne!(temp_gen_dir.lib_path(), lib_path);
+        assert_eq!(temp_gen_dir.key(), "abc");
+
+        let entries = cache.list().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].key, "abc");
+        assert_eq!(entries[0].top_module_name, "sample");
+        assert_eq!(entries[0].size, 7);
+
+        assert_eq!(cache.prune(Duration::from_secs(3600)).unwrap(), 0);
+        assert_eq!(cache.clear().unwrap(), 1);
+        assert!(cache.list().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_collect_files() {
+        let include_dir = TempDir::new().unwrap();
+        fs::create_dir_all(include_dir.path().join("sub/deeper")).unwrap();
+        fs::write(include_dir.path().join("top.svh"), "").unwrap();
+        fs::write(include_dir.path().join("sub/deeper/nested.svh"), "").unwrap();
+        let mut files = vec![];
+        collect_files(include_dir.path(), &mut files).unwrap();
+        files.sort();
+        assert_eq!(
+            files,
+            vec![
+                include_dir.path().join("sub/deeper/nested.svh"),
+                include_dir.path().join("top.svh"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hash_gen_dir() {
+        let hash = |gen_dir: &TempDir| {
+            let mut hasher = Sha256::new();
+            hash_gen_dir(&mut hasher, gen_dir.path()).unwrap();
+            hasher.finalize()
+        };
+        let gen_dir = TempDir::new().unwrap();
+        fs::write(gen_dir.path().join("CMakeLists.txt"), "project(dut)").unwrap();
+        fs::write(gen_dir.path().join("dut.cpp"), "int a;").unwrap();
+        let same_gen_dir = TempDir::new().unwrap();
+        fs::write(same_gen_dir.path().join("CMakeLists.txt"), "project(dut)").unwrap();
+        fs::write(same_gen_dir.path().join("dut.cpp"), "int a;").unwrap();
+        assert_eq!(hash(&gen_dir), hash(&same_gen_dir));
+
+        fs::write(same_gen_dir.path().join("dut.cpp"), "long a;").unwrap();
+        assert_ne!(hash(&gen_dir), hash(&same_gen_dir));
+    }
+}
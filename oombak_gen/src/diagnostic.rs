@@ -0,0 +1,162 @@
+use std::{fmt::Display, path::PathBuf};
+
+pub use oombak_rs::parser::{Diagnostic, Severity};
+
+/// Failed build step, together with its output and the diagnostics found in it.
+#[derive(Debug)]
+pub struct BuildError {
+    pub command: String,
+    pub diagnostics: Vec<Diagnostic>,
+    pub log: String,
+}
+
+/// Extracts diagnostics from Verilator (`%Error: file:line:col: message`) and GCC / Clang
+/// (`file:line:col: error: message`) output. Lines without a source location are skipped.
+pub fn parse(log: &str) -> Vec<Diagnostic> {
+    log.lines()
+        .filter_map(|line| parse_verilator_line(line).or_else(|| parse_compiler_line(line)))
+        .collect()
+}
+
+fn parse_verilator_line(line: &str) -> Option<Diagnostic> {
+    let line = line.trim_start().strip_prefix('%')?;
+    let (tag, rest) = line.split_once(": ")?;
+    let severity = match tag.split('-').next()? {
+        "Error" => Severity::Error,
+        "Warning" => Severity::Warning,
+        _ => return None,
+    };
+    let (file, line, column, message) = split_location(rest)?;
+    Some(Diagnostic {
+        severity,
+        file,
+        line,
+        column,
+        message: message.trim().to_string(),
+    })
+}
+
+fn parse_compiler_line(line: &str) -> Option<Diagnostic> {
+    let (file, line, column, rest) = split_location(line)?;
+    let (severity, message) = if let Some(message) = rest.strip_prefix(" fatal error:") {
+        (Severity::Error, message)
+    } else if let Some(message) = rest.strip_prefix(" error:") {
+        (Severity::Error, message)
+    } else if let Some(message) = rest.strip_prefix(" warning:") {
+        (Severity::Warning, message)
+    } else if let Some(message) = rest.strip_prefix(" note:") {
+        (Severity::Note, message)
+    } else {
+        return None;
+    };
+    Some(Diagnostic {
+        severity,
+        file,
+        line,
+        column,
+        message: message.trim().to_string(),
+    })
+}
+
+/// Splits `file:line[:column]:rest` into its parts.
+fn split_location(text: &str) -> Option<(PathBuf, usize, Option<usize>, &str)> {
+    let (file, rest) = text.split_once(':')?;
+    if file.is_empty() || file.contains(' ') {
+        return None;
+    }
+    let (line, rest) = rest.split_once(':')?;
+    let line = line.parse().ok()?;
+    if let Some((column, message)) = rest.split_once(':') {
+        if let Ok(column) = column.parse() {
+            return Some((PathBuf::from(file), line, Some(column), message));
+        }
+    }
+    Some((PathBuf::from(file), line, None, rest))
+}
+
+impl Display for BuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} failed", self.command)?;
+        match self
+            .diagnostics
+            .iter()
+            .find(|d| d.severity == Severity::Error)
+        {
+            Some(diagnostic) => write!(f, ": {diagnostic}"),
+            None => match self.log.lines().rev().find(|l| !l.trim().is_empty()) {
+                Some(last_line) => write!(f, ": {}", last_line.trim()),
+                None => Ok(()),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use super::{parse, split_location, Diagnostic, Severity};
+
+    #[test]
+    fn test_parse_verilator() {
+        let log = concat!(
+            "%Warning-WIDTH: /src/sample.sv:12:15: Operator ASSIGN expects 8 bits\n",
+            "                : ... note: In instance 'ombak_dut.sample'\n",
+            "%Error: /src/adder.sv:3: syntax error, unexpected ')'\n",
+            "%Error: Exiting due to 1 error(s)\n",
+        );
+        assert_eq!(
+            parse(log),
+            vec![
+                Diagnostic {
+                    severity: Severity::Warning,
+                    file: PathBuf::from("/src/sample.sv"),
+                    line: 12,
+                    column: Some(15),
+                    message: "Operator ASSIGN expects 8 bits".to_string(),
+                },
+                Diagnostic {
+                    severity: Severity::Error,
+                    file: PathBuf::from("/src/adder.sv"),
+                    line: 3,
+                    column: None,
+                    message: "syntax error, unexpected ')'".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_compiler() {
+        let log = concat!(
+            "[ 20%] Building CXX object CMakeFiles/dut.dir/getters.cpp.o\n",
+            "/tmp/x/getters.cpp:10:5: error: 'out' was not declared in this scope\n",
+            "/tmp/x/dut.hpp:4:1: note: declared here\n",
+            "gmake[2]: *** [CMakeFiles/dut.dir/build.make:76: getters.cpp.o] Error 1\n",
+        );
+        let diagnostics = parse(log);
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!(diagnostics[0].file, PathBuf::from("/tmp/x/getters.cpp"));
+        assert_eq!(diagnostics[0].line, 10);
+        assert_eq!(diagnostics[0].column, Some(5));
+        assert_eq!(
+            diagnostics[0].message,
+            "'out' was not declared in this scope"
+        );
+        assert_eq!(diagnostics[1].severity, Severity::Note);
+    }
+
+    #[test]
+    fn test_split_location() {
+        assert_eq!(
+            split_location("/src/error.sv:3:error: here"),
+            Some((PathBuf::from("/src/error.sv"), 3, None, "error: here"))
+        );
+        assert_eq!(
+            split_location("/src/a.sv:3:7: error: here"),
+            Some((PathBuf::from("/src/a.sv"), 3, Some(7), " error: here"))
+        );
+        assert_eq!(split_location("no location"), None);
+    }
+}
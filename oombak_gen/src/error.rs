@@ -1,16 +1,24 @@
 use std::path::PathBuf;
 
 use oombak_rs::error::OombakError;
+
+use crate::diagnostic::BuildError;
 use thiserror::Error;
 
 pub type OombakGenResult<T> = Result<T, OombakGenError>;
 
 #[derive(Error, Debug)]
 pub enum OombakGenError {
-    #[error(".sv file path not found: ")]
+    #[error(".sv file path not found: {}", _0.to_string_lossy())]
     SvFilePathNotFound(PathBuf),
     #[error("invalid path given: {}", _0.to_string_lossy())]
     InvalidPath(PathBuf),
+    #[error("invalid filelist: {}", _0)]
+    InvalidFilelist(String),
+    #[error("no source files given")]
+    NoSourceFiles,
+    #[error("build: {}", _0)]
+    Build(BuildError),
     #[error("IO error: {}", _0)]
     Io(std::io::Error),
     #[error("oombak_rs: {}", _0)]
@@ -1,6 +1,9 @@
-use std::{fs::File, io::Write, path::Path};
+use std::{fs::File, io::Write, path::PathBuf};
 
-use crate::error::{OombakGenError, OombakGenResult};
+use crate::{
+    error::{OombakGenError, OombakGenResult},
+    source::SourceConfig,
+};
 
 use oombak_rs::probe::{Probe, ProbePoint};
 use tempfile::TempDir;
@@ -55,10 +58,10 @@ macro_rules! generate_lines_from_dot_replaced_name_name_width {
 macro_rules! single_bit_getter_template {
     () => {
         concat!(
-            "pair<vector<uint32_t>, uint64_t> Dut::get_{0}(Dut *self) {{\n",
-            "    svBit out;\n",
+            "void Dut::get_{0}(Dut *self, uint32_t *words) {{\n",
+            "    svLogic out;\n",
             "    self->vDut->v_sample_get_{0}(&out);\n",
-            "    return {{vector<uint32_t>(1, out), 1}};\n",
+            "    Dut::put_words(out, words);\n",
             "}}\n"
         )
     };
@@ -81,11 +84,11 @@ macro_rules! single_bit_setter_template {
 macro_rules! multi_bit_getter_template {
     () => {
         concat!(
-            "pair<vector<uint32_t>, uint64_t> Dut::get_{0}(Dut *self) {{\n",
+            "void Dut::get_{0}(Dut *self, uint32_t *words) {{\n",
             "  int nBits = {1};\n",
-            "  svBitVecVal out[nBits / 32 + 1];\n",
+            "  svLogicVecVal out[nBits / 32 + 1];\n",
             "  self->vDut->v_sample_get_{0}(out);\n",
-            "  return {{Dut::get_words_vec_from(out, nBits), nBits}};\n",
+            "  Dut::put_words(out, nBits, words);\n",
             "}}\n"
         )
     };
@@ -129,11 +132,122 @@ macro_rules! multi_bit_dpc_setter_template {
     };
 }
 
+/// Top-level inouts are driven through `__ombak_value_<name>` while `__ombak_enable_<name>` is
+/// set, and left to the DUT otherwise. The path `{1}` of a top-level port is its bare name.
+macro_rules! single_bit_dpc_inout_setter_template {
+    () => {
+        concat!(
+            "export \"DPI-C\" function v_sample_set_{0};\n",
+            "function automatic void v_sample_set_{0}(input bit _in);\n",
+            "  __ombak_value_{1} = _in;\n",
+            "  __ombak_enable_{1} = 1'b1;\n",
+            "endfunction\n"
+        )
+    };
+}
+
+macro_rules! multi_bit_dpc_inout_setter_template {
+    () => {
+        concat!(
+            "export \"DPI-C\" function v_sample_set_{0};\n",
+            "function automatic void v_sample_set_{0}(input bit [{2}:0] _in);\n",
+            "  __ombak_value_{1} = _in;\n",
+            "  __ombak_enable_{1} = 1'b1;\n",
+            "endfunction\n"
+        )
+    };
+}
+
+macro_rules! inout_releaser_template {
+    () => {
+        "void Dut::release_{0}(Dut *self) {{ self->vDut->v_sample_release_{0}(); }}\n"
+    };
+}
+
+macro_rules! dpc_inout_releaser_template {
+    () => {
+        concat!(
+            "export \"DPI-C\" function v_sample_release_{0};\n",
+            "function automatic void v_sample_release_{0}();\n",
+            "  __ombak_enable_{0} = 1'b0;\n",
+            "endfunction\n"
+        )
+    };
+}
+
+macro_rules! single_bit_forcer_template {
+    () => {
+        concat!(
+            "bool Dut::force_{0}(Dut *self, const vector<uint32_t> &words) {{\n",
+            "  if (words.size() > 0) {{\n",
+            "    self->vDut->v_sample_force_{0}(words[0]);\n",
+            "    return true;\n",
+            "  }}\n",
+            "  return false;\n",
+            "}}\n",
+            "void Dut::release_{0}(Dut *self) {{ self->vDut->v_sample_release_{0}(); }}\n"
+        )
+    };
+}
+
+macro_rules! multi_bit_forcer_template {
+    () => {
+        concat!(
+            "bool Dut::force_{0}(Dut *self, const vector<uint32_t> &words) {{\n",
+            "  int nBits = {1};\n",
+            "  svBitVecVal in[nBits / 32 + 1];\n",
+            "  if (Dut::set_from_words_vec(in, words, nBits)) {{\n",
+            "    self->vDut->v_sample_force_{0}(in);\n",
+            "    return true;\n",
+            "  }}\n",
+            "  return false;\n",
+            "}}\n",
+            "void Dut::release_{0}(Dut *self) {{ self->vDut->v_sample_release_{0}(); }}\n"
+        )
+    };
+}
+
+/// The forced value is kept in a variable of the wrapper, since `force` needs an operand which
+/// outlives the call.
+macro_rules! single_bit_dpc_forcer_template {
+    () => {
+        concat!(
+            "bit __ombak_force_{0};\n",
+            "export \"DPI-C\" function v_sample_force_{0};\n",
+            "function automatic void v_sample_force_{0}(input bit _in);\n",
+            "  __ombak_force_{0} = _in;\n",
+            "  force {1} = __ombak_force_{0};\n",
+            "endfunction\n",
+            "export \"DPI-C\" function v_sample_release_{0};\n",
+            "function automatic void v_sample_release_{0}();\n",
+            "  release {1};\n",
+            "endfunction\n"
+        )
+    };
+}
+
+macro_rules! multi_bit_dpc_forcer_template {
+    () => {
+        concat!(
+            "bit [{2}:0] __ombak_force_{0};\n",
+            "export \"DPI-C\" function v_sample_force_{0};\n",
+            "function automatic void v_sample_force_{0}(input bit [{2}:0] _in);\n",
+            "  __ombak_force_{0} = _in;\n",
+            "  force {1} = __ombak_force_{0};\n",
+            "endfunction\n",
+            "export \"DPI-C\" function v_sample_release_{0};\n",
+            "function automatic void v_sample_release_{0}();\n",
+            "  release {1};\n",
+            "endfunction\n"
+        )
+    };
+}
+
 macro_rules! single_bit_dpc_getter_template {
     () => {
         concat!(
             "export \"DPI-C\" function v_sample_get_{0};\n",
-            "function automatic void v_sample_get_{0}(output bit _out);\n",
+            "function automatic void v_sample_get_{0}(output logic _out);\n",
             "  _out = {1};\n",
             "endfunction\n"
         )
@@ -144,29 +258,156 @@ macro_rules! multi_bit_dpc_getter_template {
     () => {
         concat!(
             "export \"DPI-C\" function v_sample_get_{0};\n",
-            "function automatic void v_sample_get_{0}(output bit [{2}:0] _out);\n",
+            "function automatic void v_sample_get_{0}(output logic [{2}:0] _out);\n",
             "  _out = {1};\n",
             "endfunction\n"
         )
     };
 }
 
-pub fn generate(sv_path: &Path, probe: &Probe) -> OombakGenResult<TempDir> {
-    Generator::new(probe, sv_path)?.generate()
+macro_rules! element_getter_template {
+    () => {
+        concat!(
+            "pair<vector<uint32_t>, uint64_t> Dut::get_element_{0}(Dut *self, const vector<int64_t> &idx) {{\n",
+            "  int nBits = {1};\n",
+            "  svLogicVecVal out[nBits / 32 + 1];\n",
+            "  self->vDut->v_sample_get_element_{0}({2}out);\n",
+            "  return {{Dut::get_words_vec_from(out, nBits), nBits}};\n",
+            "}}\n"
+        )
+    };
+}
+
+macro_rules! element_setter_template {
+    () => {
+        concat!(
+            "bool Dut::set_element_{0}(Dut *self, const vector<int64_t> &idx, const vector<uint32_t> &words) {{\n",
+            "  int nBits = {1};\n",
+            "  svBitVecVal in[nBits / 32 + 1];\n",
+            "  if (Dut::set_from_words_vec(in, words, nBits)) {{\n",
+            "    self->vDut->v_sample_set_element_{0}({2}in);\n",
+            "    return true;\n",
+            "  }}\n",
+            "  return false;\n",
+            "}}\n"
+        )
+    };
+}
+
+/// `{2}` is the number of elements.
+macro_rules! memory_getter_template {
+    () => {
+        concat!(
+            "bool Dut::get_memory_{0}(Dut *self, uint32_t *words, uint64_t words_len) {{\n",
+            "  int nBits = {1};\n",
+            "  auto len = Dut::num_of_words(nBits);\n",
+            "  if (words_len < {2} * 2 * len) {{\n",
+            "    return false;\n",
+            "  }}\n",
+            "  static thread_local vector<svLogicVecVal> out({2} * len);\n",
+            "  self->vDut->v_sample_get_memory_{0}(out.data());\n",
+            "  for (int i = 0; i < {2}; i++) {{\n",
+            "    Dut::put_words(&out[i * len], nBits, words + i * 2 * len);\n",
+            "  }}\n",
+            "  return true;\n",
+            "}}\n"
+        )
+    };
+}
+
+macro_rules! memory_loader_template {
+    () => {
+        concat!(
+            "void Dut::load_memory_{0}(Dut *self, const char *file_path) {{\n",
+            "  self->vDut->v_sample_load_memory_{0}(file_path);\n",
+            "}}\n"
+        )
+    };
+}
+
+macro_rules! memory_dumper_template {
+    () => {
+        concat!(
+            "void Dut::dump_memory_{0}(Dut *self, const char *file_path) {{\n",
+            "  self->vDut->v_sample_dump_memory_{0}(file_path);\n",
+            "}}\n"
+        )
+    };
+}
+
+/// `{3}` declares one `int` argument per dimension and `{4}` selects the element with them.
+macro_rules! dpc_element_getter_template {
+    () => {
+        concat!(
+            "export \"DPI-C\" function v_sample_get_element_{0};\n",
+            "function automatic void v_sample_get_element_{0}({3}output logic [{2}:0] _out);\n",
+            "  _out = {1}{4};\n",
+            "endfunction\n"
+        )
+    };
+}
+
+macro_rules! dpc_element_setter_template {
+    () => {
+        concat!(
+            "export \"DPI-C\" function v_sample_set_element_{0};\n",
+            "function automatic void v_sample_set_element_{0}({3}input bit [{2}:0] _in);\n",
+            "  {1}{4} = _in;\n",
+            "endfunction\n"
+        )
+    };
+}
+
+/// `{3}` is the number of elements and `{4}` the lowest index.
+macro_rules! dpc_memory_getter_template {
+    () => {
+        concat!(
+            "export \"DPI-C\" function v_sample_get_memory_{0};\n",
+            "function automatic void v_sample_get_memory_{0}(output logic [{2}:0] _out [{3}]);\n",
+            "  for (int _i = 0; _i < {3}; _i++) _out[_i] = {1}[{4} + _i];\n",
+            "endfunction\n"
+        )
+    };
+}
+
+macro_rules! dpc_memory_loader_template {
+    () => {
+        concat!(
+            "export \"DPI-C\" function v_sample_load_memory_{0};\n",
+            "function automatic void v_sample_load_memory_{0}(input string _path);\n",
+            "  $readmemh(_path, {1});\n",
+            "endfunction\n"
+        )
+    };
+}
+
+macro_rules! dpc_memory_dumper_template {
+    () => {
+        concat!(
+            "export \"DPI-C\" function v_sample_dump_memory_{0};\n",
+            "function automatic void v_sample_dump_memory_{0}(input string _path);\n",
+            "  $writememh(_path, {1});\n",
+            "endfunction\n"
+        )
+    };
+}
+
+pub fn generate(source_config: &SourceConfig, probe: &Probe) -> OombakGenResult<TempDir> {
+    Generator::new(probe, source_config)?.generate()
 }
 
 struct Generator<'a> {
     temp_dir: TempDir,
     probe: &'a Probe,
-    sv_path: &'a Path,
+    source_config: &'a SourceConfig,
 }
 
 impl<'a> Generator<'a> {
-    fn new(probe: &'a Probe, sv_path: &'a Path) -> OombakGenResult<Self> {
+    fn new(probe: &'a Probe, source_config: &'a SourceConfig) -> OombakGenResult<Self> {
         Ok(Generator {
             temp_dir: TempDir::new()?,
             probe,
-            sv_path,
+            source_config,
         })
     }
 
@@ -177,6 +418,8 @@ impl<'a> Generator<'a> {
         self.put_dut_hpp()?;
         self.put_getters_cpp()?;
         self.put_setters_cpp()?;
+        self.put_forcers_cpp()?;
+        self.put_memories_cpp()?;
         self.put_signals_cpp()?;
         self.put_ombak_dut_sv()?;
         self.put_cmakelists_txt()?;
@@ -201,12 +444,33 @@ impl<'a> Generator<'a> {
             "signalMapping[\"{1}\"].set = set_{0};",
             self.probe.get_settable_points()
         );
-        let getters = generate_lines_from_dot_replaced_name_name!(
-            "signalMapping[\"{1}\"].get = get_{0};",
-            self.probe.get_gettable_points()
+        let getters = self
+            .probe
+            .get_gettable_points()
+            .map(|s| {
+                format!(
+                    "signalMapping[\"{1}\"].get = get_{0};\nsignalMapping[\"{1}\"].width = {2};",
+                    s.get_dot_replaced_path(),
+                    s.path(),
+                    s.bit_width()
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n");
+        let forcers = generate_lines_from_dot_replaced_name_name!(
+            "signalMapping[\"{1}\"].force = force_{0};\nsignalMapping[\"{1}\"].release = release_{0};",
+            self.probe.get_forceable_points()
+        );
+        let inout_releasers = generate_lines_from_dot_replaced_name_name!(
+            "signalMapping[\"{1}\"].release = release_{0};",
+            self.probe.get_inout_points()
         );
+        let forcers = forcers + "\n" + &inout_releasers;
         let content = content.replace("// TEMPLATED: setters", &setters);
         let content = content.replace("// TEMPLATED: getters", &getters);
+        let memories = self.generate_memory_mappings();
+        let content = content.replace("// TEMPLATED: forcers", &forcers);
+        let content = content.replace("// TEMPLATED: memories", &memories);
         self.put_file("dut.cpp", content.as_bytes())?;
         Ok(())
     }
@@ -218,11 +482,52 @@ impl<'a> Generator<'a> {
             self.probe.get_settable_points()
         );
         let getters = generate_lines_from_name_template!(
-            "static std::pair<std::vector<uint32_t>, uint64_t> get_{0}(Dut *self);",
+            "static void get_{0}(Dut *self, uint32_t *words);",
             self.probe.get_gettable_points()
         );
+        let forcers = generate_lines_from_name_template!(
+            "static bool force_{0}(Dut *self, const std::vector<uint32_t> &words);\nstatic void release_{0}(Dut *self);",
+            self.probe.get_forceable_points()
+        );
+        let inout_releasers = generate_lines_from_name_template!(
+            "static void release_{0}(Dut *self);",
+            self.probe.get_inout_points()
+        );
+        let forcers = forcers + "\n" + &inout_releasers;
         let content = content.replace("// TEMPLATED: setters", &setters);
         let content = content.replace("// TEMPLATED: getters", &getters);
+        let element_getters = generate_lines_from_name_template!(
+            "static std::pair<std::vector<uint32_t>, uint64_t> get_element_{0}(Dut *self, const std::vector<int64_t> &idx);",
+            self.probe.get_array_points()
+        );
+        let element_setters = generate_lines_from_name_template!(
+            "static bool set_element_{0}(Dut *self, const std::vector<int64_t> &idx, const std::vector<uint32_t> &words);",
+            self.probe.get_element_settable_points()
+        );
+        let memory_getters = generate_lines_from_name_template!(
+            "static bool get_memory_{0}(Dut *self, uint32_t *words, uint64_t words_len);",
+            self.probe.get_memory_points()
+        );
+        let memory_loaders = generate_lines_from_name_template!(
+            "static void load_memory_{0}(Dut *self, const char *file_path);",
+            self.probe
+                .get_memory_points()
+                .filter(|p| p.is_element_settable())
+        );
+        let memory_dumpers = generate_lines_from_name_template!(
+            "static void dump_memory_{0}(Dut *self, const char *file_path);",
+            self.probe.get_memory_points()
+        );
+        let memories = [
+            element_getters,
+            element_setters,
+            memory_getters,
+            memory_loaders,
+            memory_dumpers,
+        ]
+        .join("\n");
+        let content = content.replace("// TEMPLATED: forcers", &forcers);
+        let content = content.replace("// TEMPLATED: memories", &memories);
         self.put_file("dut.hpp", content.as_bytes())?;
         Ok(())
     }
@@ -263,6 +568,55 @@ impl<'a> Generator<'a> {
         Ok(())
     }
 
+    fn put_forcers_cpp(&self) -> OombakGenResult<()> {
+        let content = include_str!("templates/forcers.cpp.templated");
+        let single_bit_signals = self.probe.get_single_bit_forceable_points();
+        let multi_bit_signals = self.probe.get_multibit_forceable_points();
+        let single_bit_forcers =
+            generate_lines_from_name_template!(single_bit_forcer_template!(), single_bit_signals);
+        let multi_bit_forcers = generate_lines_from_name_width_template!(
+            multi_bit_forcer_template!(),
+            multi_bit_signals
+        );
+        let inout_releasers = generate_lines_from_name_template!(
+            inout_releaser_template!(),
+            self.probe.get_inout_points()
+        );
+        let content = content.replace(
+            "// TEMPLATED: forcers",
+            &(single_bit_forcers + "\n" + &multi_bit_forcers + "\n" + &inout_releasers),
+        );
+        self.put_file("forcers.cpp", content.as_bytes())?;
+        Ok(())
+    }
+
+    fn put_memories_cpp(&self) -> OombakGenResult<()> {
+        let content = include_str!("templates/memories.cpp.templated");
+        let mut memories = String::new();
+        for point in self.probe.get_array_points() {
+            let name = point.get_dot_replaced_path();
+            let width = point.element_width();
+            let indices = Self::cpp_index_args(point);
+            memories += &format!(element_getter_template!(), name, width, indices);
+            if point.is_element_settable() {
+                memories += &format!(element_setter_template!(), name, width, indices);
+            }
+        }
+        for point in self.probe.get_memory_points() {
+            let name = point.get_dot_replaced_path();
+            let width = point.element_width();
+            let size = point.dimensions()[0].size();
+            memories += &format!(memory_getter_template!(), name, width, size);
+            if point.is_element_settable() {
+                memories += &format!(memory_loader_template!(), name);
+            }
+            memories += &format!(memory_dumper_template!(), name);
+        }
+        let content = content.replace("// TEMPLATED: memories", &memories);
+        self.put_file("memories.cpp", content.as_bytes())?;
+        Ok(())
+    }
+
     fn put_signals_cpp(&self) -> OombakGenResult<()> {
         let content = include_str!("templates/signals.cpp.templated");
         let content = content.replace(
@@ -281,43 +635,90 @@ impl<'a> Generator<'a> {
         let top_level_module_instantiation = self.generate_top_level_module_instantiation();
         let dpc_setters = self.generate_dpc_setters();
         let dpc_getters = self.generate_dpc_getters();
+        let dpc_forcers = self.generate_dpc_forcers();
+        let dpc_memories = self.generate_dpc_memories();
         let content = content.replace("// TEMPLATED: signals", &top_level_signal_declarations);
         let content = content.replace("// TEMPLATED: dut", &top_level_module_instantiation);
         let content = content.replace("// TEMPLATED: setters", &dpc_setters);
         let content = content.replace("// TEMPLATED: getters", &dpc_getters);
+        let content = content.replace("// TEMPLATED: forcers", &dpc_forcers);
+        let content = content.replace("// TEMPLATED: memories", &dpc_memories);
         self.put_file("ombak_dut.sv", content.as_bytes())?;
         Ok(())
     }
 
     fn put_cmakelists_txt(&self) -> OombakGenResult<()> {
-        let sv_dir = self
-            .sv_path
-            .parent()
-            .ok_or(OombakGenError::InvalidPath(self.sv_path.to_path_buf()))?;
+        let sources = Self::quoted_paths(self.source_config.source_paths.iter())?;
+        let include_dirs = Self::quoted_paths(
+            self.source_config
+                .library_dirs
+                .iter()
+                .chain(self.source_config.include_dirs.iter()),
+        )?;
+        let defines = self
+            .source_config
+            .defines
+            .iter()
+            .map(|d| match &d.value {
+                Some(value) => format!("\"-D{}={}\"", d.name, value),
+                None => format!("\"-D{}\"", d.name),
+            })
+            .collect::<Vec<String>>()
+            .join(" ");
         let content = include_str!("templates/CMakeLists.txt.templated");
-        let content = content.replace(
-            "/*OMBAK_INCLUDE_DIRS*/",
-            sv_dir
-                .to_str()
-                .ok_or(OombakGenError::InvalidPath(self.sv_path.to_path_buf()))?,
-        );
+        let content = content.replace("/*OMBAK_SOURCES*/", &sources);
+        let content = content.replace("/*OMBAK_VERILATOR_ARGS*/", &defines);
+        let content = content.replace("/*OMBAK_INCLUDE_DIRS*/", &include_dirs);
         self.put_file("CMakeLists.txt", content.as_bytes())?;
         Ok(())
     }
 
+    fn quoted_paths<'b>(paths: impl Iterator<Item = &'b PathBuf>) -> OombakGenResult<String> {
+        let paths = paths
+            .map(|p| {
+                p.to_str()
+                    .map(|p| format!("\"{p}\""))
+                    .ok_or(OombakGenError::InvalidPath(p.to_path_buf()))
+            })
+            .collect::<OombakGenResult<Vec<String>>>()?;
+        Ok(paths.join(" "))
+    }
+
     fn generate_signals_array(&self) -> String {
         let num_of_signals = self.probe.get_probed_points().len();
-        let mut signals_array = format!("sig_t signals[{}] = {{\n", num_of_signals);
+        let mut signals_array = String::new();
+        for point in self.probe.get_array_points() {
+            signals_array += &format!(
+                "static const dim_t dims_{}[] = {};\n",
+                point.get_dot_replaced_path(),
+                Self::cpp_dimensions(point)
+            );
+        }
+        signals_array += &format!("sig_t signals[{}] = {{\n", num_of_signals);
         for point in self.probe.get_probed_points() {
             let get = if point.is_gettable() { 1 } else { 0 };
             let set = if point.is_settable() { 1 } else { 0 };
+            let force = if point.is_forceable() { 1 } else { 0 };
+            let set_element = if point.is_element_settable() { 1 } else { 0 };
             let width = point.bit_width();
+            let dimensions = if point.is_array() {
+                format!(
+                    "dims_{}, {}",
+                    point.get_dot_replaced_path(),
+                    point.dimensions().len()
+                )
+            } else {
+                "NULL, 0".to_string()
+            };
             signals_array += &format!(
-                "    {{ \"{}\", {}, {}, {} }},\n",
+                "    {{ \"{}\", {}, {}, {}, {}, {}, {} }},\n",
                 point.path(),
                 width,
                 get,
-                set
+                set,
+                force,
+                set_element,
+                dimensions
             );
         }
         signals_array += "};";
@@ -329,15 +730,40 @@ impl<'a> Generator<'a> {
             .get_top_level_ports()
             .iter()
             .fold(String::from(""), |prev, p| {
-                let width = if p.bit_width() > 1 {
-                    format!("[{}:0]", p.bit_width() - 1)
+                let width = if p.element_width() > 1 {
+                    format!("[{}:0]", p.element_width() - 1)
                 } else {
                     "".to_string()
                 };
-                prev + &format!("logic {width} {};\n", p.path())
+                let dimensions: String = p
+                    .dimensions()
+                    .iter()
+                    .map(|d| format!("[{}:{}]", d.left, d.right))
+                    .collect();
+                if p.is_top_level_inout() {
+                    prev + &Self::generate_inout_declaration(p.path(), &width, &dimensions)
+                } else {
+                    prev + &format!("logic {width} {}{dimensions};\n", p.path())
+                }
             })
     }
 
+    /// Inout arrays are only read, so they get no driver.
+    fn generate_inout_declaration(name: &str, width: &str, dimensions: &str) -> String {
+        if !dimensions.is_empty() {
+            return format!("wire {width} {name}{dimensions};\n");
+        }
+        format!(
+            concat!(
+                "wire {1} {0};\n",
+                "logic {1} __ombak_value_{0};\n",
+                "logic __ombak_enable_{0} = 1'b0;\n",
+                "assign {0} = __ombak_enable_{0} ? __ombak_value_{0} : 'z;\n"
+            ),
+            name, width
+        )
+    }
+
     fn generate_top_level_module_instantiation(&self) -> String {
         let pin_assignments = self
             .probe
@@ -347,15 +773,36 @@ impl<'a> Generator<'a> {
                 prev + &format!(".{0}({0}),\n", p.path())
             });
         format!(
-            "{0} {0} (\n{1}\n);",
+            "{0} {1}{0} (\n{2}\n);",
             self.probe.top_level_module_name(),
+            self.generate_parameter_overrides(),
             &pin_assignments[..pin_assignments.len() - 2]
         )
     }
 
+    fn generate_parameter_overrides(&self) -> String {
+        if self.source_config.parameters.is_empty() {
+            return String::new();
+        }
+        let overrides = self
+            .source_config
+            .parameters
+            .iter()
+            .map(|p| format!(".{}({})", p.name, p.value))
+            .collect::<Vec<String>>()
+            .join(", ");
+        format!("#({overrides}) ")
+    }
+
     fn generate_dpc_setters(&self) -> String {
-        let single_bit_signals = self.probe.get_single_bit_settable_points();
-        let multi_bit_signals = self.probe.get_multibit_settable_points();
+        let single_bit_signals = self
+            .probe
+            .get_single_bit_settable_points()
+            .filter(|p| !p.is_top_level_inout());
+        let multi_bit_signals = self
+            .probe
+            .get_multibit_settable_points()
+            .filter(|p| !p.is_top_level_inout());
         let single_bit_setters = generate_lines_from_dot_replaced_name_name!(
             single_bit_dpc_setter_template!(),
             single_bit_signals
@@ -364,7 +811,23 @@ impl<'a> Generator<'a> {
             multi_bit_dpc_setter_template!(),
             multi_bit_signals
         );
-        single_bit_setters + &multi_bit_setters
+        let single_bit_inout_setters = generate_lines_from_dot_replaced_name_name!(
+            single_bit_dpc_inout_setter_template!(),
+            self.probe.get_inout_points().filter(|p| p.bit_width() == 1)
+        );
+        let multi_bit_inout_setters = generate_lines_from_dot_replaced_name_name_width!(
+            multi_bit_dpc_inout_setter_template!(),
+            self.probe.get_inout_points().filter(|p| p.bit_width() > 1)
+        );
+        let inout_releasers = generate_lines_from_name_template!(
+            dpc_inout_releaser_template!(),
+            self.probe.get_inout_points()
+        );
+        single_bit_setters
+            + &multi_bit_setters
+            + &single_bit_inout_setters
+            + &multi_bit_inout_setters
+            + &inout_releasers
     }
 
     fn generate_dpc_getters(&self) -> String {
@@ -381,6 +844,128 @@ impl<'a> Generator<'a> {
         single_bit_getters + &multi_bit_getters
     }
 
+    fn generate_dpc_forcers(&self) -> String {
+        let single_bit_signals = self.probe.get_single_bit_forceable_points();
+        let multi_bit_signals = self.probe.get_multibit_forceable_points();
+        let single_bit_forcers = generate_lines_from_dot_replaced_name_name!(
+            single_bit_dpc_forcer_template!(),
+            single_bit_signals
+        );
+        let multi_bit_forcers = generate_lines_from_dot_replaced_name_name_width!(
+            multi_bit_dpc_forcer_template!(),
+            multi_bit_signals
+        );
+        single_bit_forcers + &multi_bit_forcers
+    }
+
+    fn generate_memory_mappings(&self) -> String {
+        let mut mappings = vec![];
+        for point in self.probe.get_array_points() {
+            let (name, path) = (point.get_dot_replaced_path(), point.path());
+            mappings.push(format!(
+                "signalMapping[\"{path}\"].dimensions = {};",
+                Self::cpp_dimensions(point)
+            ));
+            mappings.push(format!(
+                "signalMapping[\"{path}\"].get_element = get_element_{name};"
+            ));
+            if point.is_element_settable() {
+                mappings.push(format!(
+                    "signalMapping[\"{path}\"].set_element = set_element_{name};"
+                ));
+            }
+        }
+        for point in self.probe.get_memory_points() {
+            let (name, path) = (point.get_dot_replaced_path(), point.path());
+            mappings.push(format!(
+                "signalMapping[\"{path}\"].get_memory = get_memory_{name};"
+            ));
+            if point.is_element_settable() {
+                mappings.push(format!(
+                    "signalMapping[\"{path}\"].load_memory = load_memory_{name};"
+                ));
+            }
+            mappings.push(format!(
+                "signalMapping[\"{path}\"].dump_memory = dump_memory_{name};"
+            ));
+        }
+        mappings.join("\n")
+    }
+
+    fn generate_dpc_memories(&self) -> String {
+        let mut memories = String::new();
+        for point in self.probe.get_array_points() {
+            let name = point.get_dot_replaced_path();
+            let msb = point.element_width() - 1;
+            let index_args = Self::sv_index_args(point);
+            let index_select = Self::sv_index_select(point);
+            memories += &format!(
+                dpc_element_getter_template!(),
+                name,
+                point.path(),
+                msb,
+                index_args,
+                index_select
+            );
+            if point.is_element_settable() {
+                memories += &format!(
+                    dpc_element_setter_template!(),
+                    name,
+                    point.path(),
+                    msb,
+                    index_args,
+                    index_select
+                );
+            }
+        }
+        for point in self.probe.get_memory_points() {
+            let name = point.get_dot_replaced_path();
+            let dimension = point.dimensions()[0];
+            memories += &format!(
+                dpc_memory_getter_template!(),
+                name,
+                point.path(),
+                point.element_width() - 1,
+                dimension.size(),
+                dimension.left.min(dimension.right)
+            );
+            if point.is_element_settable() {
+                memories += &format!(dpc_memory_loader_template!(), name, point.path());
+            }
+            memories += &format!(dpc_memory_dumper_template!(), name, point.path());
+        }
+        memories
+    }
+
+    /// `{{left, right}, ...}`, which initializes both `dim_t[]` and the dimensions of `Signal`.
+    fn cpp_dimensions(point: &ProbePoint) -> String {
+        let dimensions = point
+            .dimensions()
+            .iter()
+            .map(|d| format!("{{{}, {}}}", d.left, d.right))
+            .collect::<Vec<String>>()
+            .join(", ");
+        format!("{{{dimensions}}}")
+    }
+
+    fn cpp_index_args(point: &ProbePoint) -> String {
+        (0..point.dimensions().len())
+            .map(|i| format!("(int)idx[{i}], "))
+            .collect()
+    }
+
+    fn sv_index_args(point: &ProbePoint) -> String {
+        (0..point.dimensions().len())
+            .map(|i| format!("input int _i{i}, "))
+            .collect()
+    }
+
+    fn sv_index_select(point: &ProbePoint) -> String {
+        (0..point.dimensions().len())
+            .map(|i| format!("[_i{i}]"))
+            .collect()
+    }
+
     fn put_file(&self, file_name: &str, content: &[u8]) -> OombakGenResult<()> {
         let file_path = self.temp_dir.path().join(file_name);
         let mut file = File::create_new(file_path)?;
@@ -388,3 +973,116 @@ impl<'a> Generator<'a> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use oombak_rs::{
+        parser::{Dimension, Direction, InstanceNode, Signal, SignalType},
+        probe::Probe,
+    };
+
+    use crate::source::SourceConfig;
+
+    use super::Generator;
+
+    fn new_probe() -> Probe {
+        let root_node = InstanceNode::new(
+            "sample",
+            vec![
+                Signal::new("clk", SignalType::UnpackedArrPort(Direction::In, 1)),
+                Signal::new("q", SignalType::UnpackedArrPort(Direction::Out, 1)),
+                Signal::new("c", SignalType::UnpackedArrNetVar(6)),
+            ],
+        );
+        let mut probe = Probe::from_root_node(root_node).unwrap();
+        probe.add_signal_to_probe("sample.c").unwrap();
+        probe
+    }
+
+    fn new_array_probe() -> Probe {
+        let array = |name: &str, is_net| Signal {
+            dimensions: vec![Dimension { left: 0, right: 3 }],
+            is_net,
+            ..Signal::new(name, SignalType::UnpackedArrNetVar(8 * 4))
+        };
+        let root_node = InstanceNode::new("sample", vec![array("mem", false), array("bus", true)]);
+        let mut probe = Probe::from_root_node(root_node).unwrap();
+        probe.add_signal_to_probe("sample.mem").unwrap();
+        probe.add_signal_to_probe("sample.bus").unwrap();
+        probe
+    }
+
+    #[test]
+    fn test_dpc_forcers() {
+        let probe = new_probe();
+        let source_config = SourceConfig::default();
+        let generator = Generator::new(&probe, &source_config).unwrap();
+        let forcers = generator.generate_dpc_forcers();
+
+        assert!(forcers.contains("bit __ombak_force_q;\n"));
+        assert!(forcers.contains("  force q = __ombak_force_q;\n"));
+        assert!(forcers.contains("bit [5:0] __ombak_force_sample_DOT_c;\n"));
+        assert!(forcers.contains(
+            "function automatic void v_sample_force_sample_DOT_c(input bit [5:0] _in);\n"
+        ));
+        assert!(forcers.contains("  force sample.c = __ombak_force_sample_DOT_c;\n"));
+        assert!(forcers.contains("  release sample.c;\n"));
+        assert!(!forcers.contains("clk"));
+    }
+
+    #[test]
+    fn test_forcers_cpp() {
+        let probe = new_probe();
+        let source_config = SourceConfig::default();
+        let generator = Generator::new(&probe, &source_config).unwrap();
+        generator.put_forcers_cpp().unwrap();
+        let content = fs::read_to_string(generator.temp_dir.path().join("forcers.cpp")).unwrap();
+
+        assert!(content.contains("bool Dut::force_q(Dut *self, const vector<uint32_t> &words) {\n"));
+        assert!(content.contains("    self->vDut->v_sample_force_q(words[0]);\n"));
+        assert!(content.contains("  int nBits = 6;\n"));
+        assert!(content.contains("    self->vDut->v_sample_force_sample_DOT_c(in);\n"));
+        assert!(content.contains("void Dut::release_sample_DOT_c(Dut *self) { self->vDut->v_sample_release_sample_DOT_c(); }\n"));
+        assert!(!content.contains("force_clk"));
+    }
+
+    #[test]
+    fn test_dpc_memories_of_nets() {
+        let probe = new_array_probe();
+        let source_config = SourceConfig::default();
+        let generator = Generator::new(&probe, &source_config).unwrap();
+        let memories = generator.generate_dpc_memories();
+
+        assert!(memories.contains("  sample.mem[_i0] = _in;\n"));
+        assert!(memories.contains("  $readmemh(_path, sample.mem);\n"));
+        assert!(memories.contains("  _out = sample.bus[_i0];\n"));
+        assert!(!memories.contains("v_sample_set_element_sample_DOT_bus"));
+        assert!(!memories.contains("v_sample_load_memory_sample_DOT_bus"));
+    }
+
+    #[test]
+    fn test_memory_getters() {
+        let probe = new_array_probe();
+        let source_config = SourceConfig::default();
+        let generator = Generator::new(&probe, &source_config).unwrap();
+        let dpc_memories = generator.generate_dpc_memories();
+        generator.put_memories_cpp().unwrap();
+        let memories = fs::read_to_string(generator.temp_dir.path().join("memories.cpp")).unwrap();
+
+        assert!(dpc_memories.contains(
+            "function automatic void v_sample_get_memory_sample_DOT_mem(output logic [7:0] _out [4]);\n"
+        ));
+        assert!(dpc_memories
+            .contains("  for (int _i = 0; _i < 4; _i++) _out[_i] = sample.mem[0 + _i];\n"));
+        assert!(!dpc_memories.contains("v_sample_get_memory_sample_DOT_table"));
+        assert!(memories.contains("  if (words_len < 4 * 2 * len) {\n"));
+        assert!(
+            memories.contains("  self->vDut->v_sample_get_memory_sample_DOT_mem(out.data());\n")
+        );
+        assert!(generator
+            .generate_memory_mappings()
+            .contains("signalMapping[\"sample.mem\"].get_memory = get_memory_sample_DOT_mem;"));
+    }
+}
@@ -1,35 +1,56 @@
+pub mod cache;
+pub mod diagnostic;
 pub mod error;
 mod generator;
+pub mod source;
 
 use std::{
     path::{Path, PathBuf},
     process::Command,
 };
 
+use cache::BuildCache;
+use diagnostic::BuildError;
 use error::{OombakGenError, OombakGenResult};
 use oombak_rs::probe::Probe;
+use source::SourceConfig;
 use tempfile::TempDir;
 
 pub struct TempGenDir {
     tempdir: TempDir,
     lib_path: PathBuf,
+    key: String,
 }
 
-pub fn build(sv_path: &Path) -> OombakGenResult<(TempGenDir, Probe)> {
-    let source_paths: Vec<String> = source_paths_from_sv_path(sv_path)?
-        .iter()
-        .map(|p| p.to_string_lossy().to_string())
-        .collect();
-    let probe = Probe::try_from(&source_paths, "sample")?;
-    Ok((build_with_probe(sv_path, &probe)?, probe))
+pub fn build(source_config: &SourceConfig) -> OombakGenResult<(TempGenDir, Probe)> {
+    let source_paths = source_config.parser_source_paths()?;
+    let probe = Probe::try_from(
+        &source_paths,
+        &source_config.top_module_name,
+        &source_config.parse_options(),
+    )?;
+    Ok((build_with_probe(source_config, &probe)?, probe))
 }
 
-pub fn build_with_probe(sv_path: &Path, probe: &Probe) -> OombakGenResult<TempGenDir> {
-    let source_dir = generator::generate(sv_path, probe)?;
-    cmake(source_dir)
+pub fn build_with_probe(
+    source_config: &SourceConfig,
+    probe: &Probe,
+) -> OombakGenResult<TempGenDir> {
+    let source_dir = generator::generate(source_config, probe)?;
+    let key = BuildCache::key_of(source_config, source_dir.path())?;
+    let Some(cache) = BuildCache::open_default() else {
+        return cmake(source_dir, key);
+    };
+    if let Some(temp_gen_dir) = cache.fetch(&key)? {
+        return Ok(temp_gen_dir);
+    }
+    let temp_gen_dir = cmake(source_dir, key.clone())?;
+    // A failure to populate the cache should not fail an otherwise successful build
+    let _ = cache.store(&key, source_config, &temp_gen_dir.lib_path());
+    Ok(temp_gen_dir)
 }
 
-fn cmake(source_dir: TempDir) -> OombakGenResult<TempGenDir> {
+fn cmake(source_dir: TempDir, key: String) -> OombakGenResult<TempGenDir> {
     cmake_configure(source_dir.path())?;
     cmake_build(source_dir.path())?;
     let mut lib_path = PathBuf::from("build");
@@ -37,47 +58,42 @@ fn cmake(source_dir: TempDir) -> OombakGenResult<TempGenDir> {
     Ok(TempGenDir {
         tempdir: source_dir,
         lib_path,
+        key,
     })
 }
 
 fn cmake_configure(source_path: &Path) -> OombakGenResult<()> {
-    Command::new("cmake")
-        .current_dir(source_path)
-        .args(["-S", ".", "-B", "build"])
-        .output()?;
-    Ok(())
+    run_cmake(source_path, &["-S", ".", "-B", "build"])
 }
 
 fn cmake_build(source_path: &Path) -> OombakGenResult<()> {
-    Command::new("cmake")
-        .current_dir(source_path)
-        .args(["--build", "build"])
-        .output()?;
-    Ok(())
+    run_cmake(source_path, &["--build", "build"])
 }
 
-fn source_paths_from_sv_path(sv_path: &Path) -> OombakGenResult<Vec<PathBuf>> {
-    if !sv_path.exists() || !sv_path.is_file() {
-        return Err(OombakGenError::SvFilePathNotFound(sv_path.to_path_buf()));
-    }
-    let mut source_paths = vec![];
-    source_paths.push(sv_path.to_path_buf());
-    let parent_dir = sv_path
-        .parent()
-        .ok_or(OombakGenError::InvalidPath(sv_path.to_path_buf()))?;
-    for file in std::fs::read_dir(parent_dir)? {
-        let file = file?;
-        if let Some(ext) = file.path().extension() {
-            if ext == "sv" && file.path() != sv_path {
-                source_paths.push(file.path())
-            }
-        }
+fn run_cmake(source_path: &Path, args: &[&str]) -> OombakGenResult<()> {
+    let output = Command::new("cmake")
+        .current_dir(source_path)
+        .args(args)
+        .output()?;
+    if output.status.success() {
+        return Ok(());
     }
-    Ok(source_paths)
+    let log = String::from_utf8_lossy(&output.stdout).to_string()
+        + &String::from_utf8_lossy(&output.stderr);
+    Err(OombakGenError::Build(BuildError {
+        command: format!("cmake {}", args.join(" ")),
+        diagnostics: diagnostic::parse(&log),
+        log,
+    }))
 }
 
 impl TempGenDir {
     pub fn lib_path(&self) -> PathBuf {
         self.tempdir.path().join(&self.lib_path)
     }
+
+    /// Build cache key of the library, which tells the design and probe it was built from.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
 }
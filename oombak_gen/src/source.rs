@@ -0,0 +1,383 @@
+use std::path::{Path, PathBuf};
+
+use oombak_rs::parser::ParseOptions;
+
+use crate::error::{OombakGenError, OombakGenResult};
+
+const LIBRARY_EXTENSIONS: [&str; 2] = ["sv", "v"];
+
+/// Source files and options describing the design to be loaded.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SourceConfig {
+    pub top_module_name: String,
+    pub source_paths: Vec<PathBuf>,
+    pub include_dirs: Vec<PathBuf>,
+    pub defines: Vec<Define>,
+    pub library_dirs: Vec<PathBuf>,
+    /// Overrides of the top module's parameters.
+    pub parameters: Vec<Parameter>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Define {
+    pub name: String,
+    pub value: Option<String>,
+}
+
+/// `value` is a SystemVerilog expression, e.g. `8` or `"fast"`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Parameter {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Default)]
+struct Builder {
+    top_module_name: Option<String>,
+    source_paths: Vec<PathBuf>,
+    include_dirs: Vec<PathBuf>,
+    defines: Vec<Define>,
+    library_dirs: Vec<PathBuf>,
+    parameters: Vec<Parameter>,
+}
+
+impl SourceConfig {
+    /// Loads a single file. Other `.sv` files in the same directory are used to resolve modules
+    /// and the top module is named after the file.
+    pub fn from_sv_path(sv_path: &Path) -> OombakGenResult<Self> {
+        Self::from_args(&[&sv_path.to_string_lossy()])
+    }
+
+    /// Parses Verilator style arguments: source files, `-f <filelist>`, `-y <dir>`, `-v <file>`,
+    /// `+incdir+<dir>`, `+define+<name>[=<value>]`, `-G<name>=<value>` and `-top <name>`.
+    /// Relative paths are resolved against the current directory.
+    pub fn from_args(args: &[&str]) -> OombakGenResult<Self> {
+        let tokens: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        let mut builder = Builder::default();
+        builder.parse_tokens(&tokens, &std::env::current_dir()?)?;
+        builder.finish()
+    }
+
+    /// Parses a `.f` filelist. Relative paths are resolved against the filelist directory.
+    pub fn from_filelist(filelist_path: &Path) -> OombakGenResult<Self> {
+        let mut builder = Builder::default();
+        builder.parse_filelist(filelist_path)?;
+        builder.finish()
+    }
+
+    /// Source files given to the parser, including the files found in library directories.
+    pub fn parser_source_paths(&self) -> OombakGenResult<Vec<String>> {
+        let mut source_paths = self.source_paths.clone();
+        for library_dir in self.library_dirs.iter() {
+            let mut library_files = vec![];
+            for file in std::fs::read_dir(library_dir)? {
+                let path = file?.path();
+                if Self::is_library_file(&path) && !source_paths.contains(&path) {
+                    library_files.push(path);
+                }
+            }
+            library_files.sort();
+            source_paths.extend(library_files);
+        }
+        Ok(source_paths
+            .iter()
+            .map(|p| p.to_string_lossy().to_string())
+            .collect())
+    }
+
+    /// Include directories, defines and parameter overrides in the form slang takes them.
+    pub fn parse_options(&self) -> ParseOptions {
+        ParseOptions {
+            include_dirs: self
+                .include_dirs
+                .iter()
+                .map(|p| p.to_string_lossy().to_string())
+                .collect(),
+            defines: self
+                .defines
+                .iter()
+                .map(|d| match &d.value {
+                    Some(value) => format!("{}={value}", d.name),
+                    None => d.name.clone(),
+                })
+                .collect(),
+            parameters: self
+                .parameters
+                .iter()
+                .map(|p| format!("{}={}", p.name, p.value))
+                .collect(),
+        }
+    }
+
+    fn is_library_file(path: &Path) -> bool {
+        path.is_file()
+            && path
+                .extension()
+                .is_some_and(|ext| LIBRARY_EXTENSIONS.iter().any(|e| ext == *e))
+    }
+}
+
+impl Builder {
+    fn parse_filelist(&mut self, filelist_path: &Path) -> OombakGenResult<()> {
+        let filelist_path = canonicalize(filelist_path)?;
+        let content = std::fs::read_to_string(&filelist_path)?;
+        let tokens: Vec<String> = content
+            .lines()
+            .map(|line| line.split("//").next().unwrap_or_default())
+            .flat_map(|line| line.split_whitespace())
+            .map(|token| token.to_string())
+            .collect();
+        let base_dir = filelist_path
+            .parent()
+            .ok_or(OombakGenError::InvalidPath(filelist_path.clone()))?;
+        self.parse_tokens(&tokens, base_dir)
+    }
+
+    fn parse_tokens(&mut self, tokens: &[String], base_dir: &Path) -> OombakGenResult<()> {
+        let mut tokens = tokens.iter();
+        while let Some(token) = tokens.next() {
+            let mut argument = || {
+                tokens.next().ok_or(OombakGenError::InvalidFilelist(format!(
+                    "missing argument for {token}"
+                )))
+            };
+            match token.as_str() {
+                "-f" | "-F" => self.parse_filelist(&base_dir.join(argument()?))?,
+                "-y" => self
+                    .library_dirs
+                    .push(canonicalize(&base_dir.join(argument()?))?),
+                "-v" => self
+                    .source_paths
+                    .push(canonicalize(&base_dir.join(argument()?))?),
+                "-top" | "--top" | "--top-module" => {
+                    self.top_module_name = Some(argument()?.to_string())
+                }
+                _ if token.starts_with("+incdir+") => {
+                    for dir in plus_separated_values(token, "+incdir+") {
+                        self.include_dirs.push(canonicalize(&base_dir.join(dir))?);
+                    }
+                }
+                _ if token.starts_with("+define+") => {
+                    for define in plus_separated_values(token, "+define+") {
+                        self.defines.push(Define::from(define));
+                    }
+                }
+                _ if token.starts_with("-I") && token.len() > 2 => self
+                    .include_dirs
+                    .push(canonicalize(&base_dir.join(&token[2..]))?),
+                _ if token.starts_with("-D") && token.len() > 2 => {
+                    self.defines.push(Define::from(&token[2..]))
+                }
+                _ if token.starts_with("-G") => {
+                    self.parameters.push(Parameter::try_from(&token[2..])?)
+                }
+                _ if token.starts_with('-') || token.starts_with('+') => {
+                    return Err(OombakGenError::InvalidFilelist(format!(
+                        "unsupported option {token}"
+                    )))
+                }
+                _ => self.source_paths.push(canonicalize(&base_dir.join(token))?),
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(mut self) -> OombakGenResult<SourceConfig> {
+        let first_source_path = self
+            .source_paths
+            .first()
+            .ok_or(OombakGenError::NoSourceFiles)?
+            .clone();
+        if self.source_paths.len() == 1 && self.library_dirs.is_empty() {
+            let parent_dir = first_source_path
+                .parent()
+                .ok_or(OombakGenError::InvalidPath(first_source_path.clone()))?;
+            self.library_dirs.push(parent_dir.to_path_buf());
+        }
+        let top_module_name = match self.top_module_name {
+            Some(top_module_name) => top_module_name,
+            None => first_source_path
+                .file_stem()
+                .ok_or(OombakGenError::InvalidPath(first_source_path.clone()))?
+                .to_string_lossy()
+                .to_string(),
+        };
+        Ok(SourceConfig {
+            top_module_name,
+            source_paths: self.source_paths,
+            include_dirs: self.include_dirs,
+            defines: self.defines,
+            library_dirs: self.library_dirs,
+            parameters: self.parameters,
+        })
+    }
+}
+
+impl From<&str> for Define {
+    fn from(define: &str) -> Self {
+        match define.split_once('=') {
+            Some((name, value)) => Define {
+                name: name.to_string(),
+                value: Some(value.to_string()),
+            },
+            None => Define {
+                name: define.to_string(),
+                value: None,
+            },
+        }
+    }
+}
+
+impl TryFrom<&str> for Parameter {
+    type Error = OombakGenError;
+
+    fn try_from(parameter: &str) -> OombakGenResult<Self> {
+        match parameter.split_once('=') {
+            Some((name, value)) if !name.is_empty() && !value.is_empty() => Ok(Parameter {
+                name: name.to_string(),
+                value: value.to_string(),
+            }),
+            _ => Err(OombakGenError::InvalidFilelist(format!(
+                "expected -G<name>=<value>, got -G{parameter}"
+            ))),
+        }
+    }
+}
+
+fn plus_separated_values<'a>(token: &'a str, prefix: &str) -> impl Iterator<Item = &'a str> {
+    token[prefix.len()..].split('+').filter(|v| !v.is_empty())
+}
+
+fn canonicalize(path: &Path) -> OombakGenResult<PathBuf> {
+    std::fs::canonicalize(path).map_err(|_| OombakGenError::SvFilePathNotFound(path.to_path_buf()))
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fs, path::Path};
+
+    use tempfile::TempDir;
+
+    use super::{Define, Parameter, SourceConfig};
+
+    fn touch(dir: &Path, name: &str) {
+        fs::write(dir.join(name), "").unwrap();
+    }
+
+    #[test]
+    fn test_from_sv_path() {
+        let dir = TempDir::new().unwrap();
+        let dir_path = fs::canonicalize(dir.path()).unwrap();
+        touch(&dir_path, "sample.sv");
+        touch(&dir_path, "adder.sv");
+        touch(&dir_path, "notes.txt");
+
+        let config = SourceConfig::from_sv_path(&dir_path.join("sample.sv")).unwrap();
+        assert_eq!(config.top_module_name, "sample");
+        assert_eq!(config.library_dirs, vec![dir_path.clone()]);
+        assert_eq!(
+            config.parser_source_paths().unwrap(),
+            vec![
+                dir_path.join("sample.sv").to_string_lossy().to_string(),
+                dir_path.join("adder.sv").to_string_lossy().to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_from_filelist() {
+        let dir = TempDir::new().unwrap();
+        let dir_path = fs::canonicalize(dir.path()).unwrap();
+        fs::create_dir(dir_path.join("rtl")).unwrap();
+        fs::create_dir(dir_path.join("include")).unwrap();
+        fs::create_dir(dir_path.join("lib")).unwrap();
+        touch(&dir_path.join("rtl"), "core.sv");
+        touch(&dir_path.join("rtl"), "alu.sv");
+        touch(&dir_path.join("lib"), "fifo.v");
+        fs::write(dir_path.join("rtl/rtl.f"), "core.sv // top\nalu.sv\n").unwrap();
+        fs::write(
+            dir_path.join("project.f"),
+            "+incdir+include\n+define+WIDTH=8+SIM\n-y lib\n-f rtl/rtl.f\n--top-module core\n",
+        )
+        .unwrap();
+
+        let config = SourceConfig::from_filelist(&dir_path.join("project.f")).unwrap();
+        assert_eq!(config.top_module_name, "core");
+        assert_eq!(
+            config.source_paths,
+            vec![dir_path.join("rtl/core.sv"), dir_path.join("rtl/alu.sv")]
+        );
+        assert_eq!(config.include_dirs, vec![dir_path.join("include")]);
+        assert_eq!(config.library_dirs, vec![dir_path.join("lib")]);
+        assert_eq!(
+            config.defines,
+            vec![
+                Define {
+                    name: "WIDTH".to_string(),
+                    value: Some("8".to_string())
+                },
+                Define {
+                    name: "SIM".to_string(),
+                    value: None
+                }
+            ]
+        );
+        assert_eq!(config.parser_source_paths().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_parameters() {
+        let dir = TempDir::new().unwrap();
+        let dir_path = fs::canonicalize(dir.path()).unwrap();
+        fs::create_dir(dir_path.join("include")).unwrap();
+        touch(&dir_path, "sample.sv");
+        let sv_path = dir_path.join("sample.sv").to_string_lossy().to_string();
+        let include_arg = format!("+incdir+{}", dir_path.join("include").to_string_lossy());
+
+        let config = SourceConfig::from_args(&[
+            &sv_path,
+            "-GDEPTH=16",
+            "-GNAME=\"fast\"",
+            "+define+SIM",
+            &include_arg,
+        ])
+        .unwrap();
+        assert_eq!(
+            config.parameters,
+            vec![
+                Parameter {
+                    name: "DEPTH".to_string(),
+                    value: "16".to_string()
+                },
+                Parameter {
+                    name: "NAME".to_string(),
+                    value: "\"fast\"".to_string()
+                }
+            ]
+        );
+        let options = config.parse_options();
+        assert_eq!(options.defines, vec!["SIM".to_string()]);
+        assert_eq!(
+            options.parameters,
+            vec!["DEPTH=16".to_string(), "NAME=\"fast\"".to_string()]
+        );
+        assert_eq!(
+            options.include_dirs,
+            vec![dir_path.join("include").to_string_lossy().to_string()]
+        );
+        assert!(SourceConfig::from_args(&[&sv_path, "-GDEPTH"]).is_err());
+    }
+
+    #[test]
+    fn test_invalid_args() {
+        let dir = TempDir::new().unwrap();
+        let dir_path = fs::canonicalize(dir.path()).unwrap();
+        touch(&dir_path, "sample.sv");
+        let sv_path = dir_path.join("sample.sv").to_string_lossy().to_string();
+
+        assert!(SourceConfig::from_args(&[]).is_err());
+        assert!(SourceConfig::from_args(&[&sv_path, "-top"]).is_err());
+        assert!(SourceConfig::from_args(&[&sv_path, "--unknown"]).is_err());
+        assert!(SourceConfig::from_args(&["missing.sv"]).is_err());
+    }
+}
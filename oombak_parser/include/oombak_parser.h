@@ -16,14 +16,83 @@ enum SignalType {
   UnpackedArrPortIn,
   UnpackedArrPortOut,
   UnpackedArrVarNet,
+  UnpackedArrPortInOut,
+  UnpackedArrPortRef,
 };
 
+// Bounds of an unpacked dimension as declared, e.g. [0:1023] or [7:0].
+struct Dimension {
+  int64_t left;
+  int64_t right;
+};
+
+enum TypeKind {
+  TypeLogic,
+  TypePackedStruct,
+  TypePackedUnion,
+  TypeEnum,
+  TypePackedArray,
+};
+
+struct TypeInfo;
+
+// `lsb` is the offset of the field's least significant bit within its
+// parent.
+struct Field {
+  const char *name;
+  uint64_t lsb;
+  uint64_t width;
+  TypeInfo *type_info;
+};
+
+// `value` is written most significant bit first, e.g. "0110" or "01xz".
+struct EnumValue {
+  const char *name;
+  const char *value;
+};
+
+// Structure of a packed type. Only the members of `kind` are set: fields
+// for structs and unions, values for enums, and packed dimensions from the
+// outermost one with the type of a single element for packed arrays.
+struct TypeInfo {
+  TypeKind kind;
+  Field *fields;
+  uint64_t fields_len;
+  EnumValue *enum_values;
+  uint64_t enum_values_len;
+  Dimension *packed_dimensions;
+  uint64_t packed_dimensions_len;
+  TypeInfo *element_type;
+};
+
+// Where a signal or an instance is declared. `file` is empty, and `line` and
+// `column` are 0, if it is unknown. Both are 1-based otherwise.
+struct SourceLocation {
+  const char *file;
+  uint64_t line;
+  uint64_t column;
+};
+
+// `width` is the total width of the signal. Unpacked dimensions are listed
+// from the outermost (leftmost) one, and are empty for non-array signals.
+// `type_info` describes a single element. `is_net` is set for nets, and for
+// ports connected to a net inside the module, which cannot be assigned
+// procedurally.
 struct Signal {
   const char *name;
   SignalType type;
   uint64_t width;
+  Dimension *dimensions;
+  uint64_t dimensions_len;
+  TypeInfo *type_info;
+  SourceLocation location;
+  bool is_net;
 };
 
+// `location` is where the instance is instantiated, or where the module is
+// declared for the root instance. `uses_timing` is set if the module of the
+// instance, not counting its children, has timing constructs scheduled by
+// Verilator's --timing, e.g. delays or waits.
 struct Instance {
   const char *name;
   const char *module_name;
@@ -32,19 +101,68 @@ struct Instance {
   uint64_t child_instances_len;
   Signal *signals;
   uint64_t signals_len;
+  SourceLocation location;
+  bool uses_timing;
+};
+
+enum DiagnosticSeverity {
+  SeverityError,
+  SeverityWarning,
+  SeverityNote,
+};
+
+// Diagnostic reported by slang. `file` is empty, and `line` and `column` are
+// 0, if it has no source location. Both are 1-based otherwise.
+struct Diagnostic {
+  DiagnosticSeverity severity;
+  const char *file;
+  uint64_t line;
+  uint64_t column;
+  const char *message;
+};
+
+// `instance` is NULL if any diagnostic is an error. Warnings and notes are
+// reported either way. The diagnostics stay valid until the next parse with
+// the same context.
+struct ParseResult {
+  Instance *instance;
+  Diagnostic *diagnostics;
+  uint64_t diagnostics_len;
+};
+
+// Include directories searched by `include, defines written `NAME` or
+// `NAME=VALUE`, and overrides of the top module's parameters written
+// `NAME=VALUE`.
+struct ParseOptions {
+  const char *const *include_dirs;
+  uint64_t include_dirs_len;
+  const char *const *defines;
+  uint64_t defines_len;
+  const char *const *parameters;
+  uint64_t parameters_len;
 };
 
 typedef void *OombakCtx;
 
 OOMBAK_PARSER_EXPORT OombakCtx oombak_parser_get_ctx();
 
-OOMBAK_PARSER_EXPORT Instance *oombak_parser_parse(const char *source_paths,
-                                                   const char *top_module_name);
+OOMBAK_PARSER_EXPORT ParseResult *
+oombak_parser_parse(const char *source_paths, const char *top_module_name);
 
-OOMBAK_PARSER_EXPORT Instance *
+OOMBAK_PARSER_EXPORT ParseResult *
 oombak_parser_parse_r(OombakCtx ctx, const char *source_paths,
                       const char *top_module_name);
 
+OOMBAK_PARSER_EXPORT ParseResult *
+oombak_parser_parse_with_options(const char *source_paths,
+                                 const char *top_module_name,
+                                 const ParseOptions *options);
+
+OOMBAK_PARSER_EXPORT ParseResult *
+oombak_parser_parse_with_options_r(OombakCtx ctx, const char *source_paths,
+                                   const char *top_module_name,
+                                   const ParseOptions *options);
+
 OOMBAK_PARSER_EXPORT void oombak_parser_free_ctx(OombakCtx ctx);
 
 #ifdef __cplusplus
@@ -1,7 +1,10 @@
 #pragma once
 
 #include "oombak_parser.h"
+#include "timing_finder.hpp"
 #include "slang/ast/ASTVisitor.h"
+#include "slang/ast/types/AllTypes.h"
+#include "slang/text/SourceManager.h"
 #include <algorithm>
 #include <cstdlib>
 #include <cstring>
@@ -9,17 +12,28 @@
 #include <vector>
 
 using slang::ast::ASTVisitor;
+using slang::ast::EnumType;
+using slang::ast::EnumValueSymbol;
+using slang::ast::FieldSymbol;
+using slang::ast::FixedSizeUnpackedArrayType;
 using slang::ast::InstanceSymbol;
 using slang::ast::NetSymbol;
+using slang::ast::PackedArrayType;
+using slang::ast::PackedStructType;
+using slang::ast::PackedUnionType;
 using slang::ast::PortSymbol;
 using slang::ast::Scope;
+using slang::ast::Type;
 using slang::ast::VariableSymbol;
+using slang::SourceManager;
 using std::string;
 using std::vector;
 
 class InstanceTreeBuilder : public ASTVisitor<InstanceTreeBuilder, true, true> {
 public:
-  InstanceTreeBuilder(Instance *root_instance) {
+  InstanceTreeBuilder(Instance *root_instance,
+                      const SourceManager &source_manager)
+      : source_manager(source_manager) {
     this->root_instance = root_instance;
   }
 
@@ -30,15 +44,24 @@ public:
 
 private:
   Instance *root_instance;
+  const SourceManager &source_manager;
 
   void visitInstance(const InstanceSymbol &symbol, Instance *instance) {
     set_name(instance, symbol);
+    instance->location = get_location(symbol.location);
+    instance->uses_timing = uses_timing(symbol);
     auto signals = get_signals(symbol);
     set_signals(instance, signals);
     auto child_instances = visit_and_get_child_instances(symbol);
     set_child_instances(instance, child_instances);
   }
 
+  bool uses_timing(const InstanceSymbol &symbol) {
+    TimingFinder finder;
+    symbol.body.visit(finder);
+    return finder.found;
+  }
+
   void set_name(Instance *instance, const InstanceSymbol &symbol) {
     instance->name = strdup(string(symbol.name).c_str());
     instance->module_name = strdup(string(symbol.body.name).c_str());
@@ -110,6 +133,9 @@ private:
         continue;
       }
       sig.width = get_signal_width<T>(it);
+      sig.location = get_location(it->location);
+      sig.is_net = is_net<T>(it);
+      set_dimensions(sig, it->getType());
       if constexpr (std::is_same_v<PortSymbol, T>)
         sig.type = get_port_type(it);
       else
@@ -125,17 +151,135 @@ private:
     case slang::ast::ArgumentDirection::Out:
       return UnpackedArrPortOut;
     case slang::ast::ArgumentDirection::InOut:
+      return UnpackedArrPortInOut;
     case slang::ast::ArgumentDirection::Ref:
-      break;
+      return UnpackedArrPortRef;
     }
     throw new std::exception();
   }
 
+  SourceLocation get_location(slang::SourceLocation location) {
+    auto original = source_manager.getFullyOriginalLoc(location);
+    if (original == slang::SourceLocation::NoLocation) {
+      return {strdup(""), 0, 0};
+    }
+    return {strdup(string(source_manager.getFileName(original)).c_str()),
+            source_manager.getLineNumber(original),
+            source_manager.getColumnNumber(original)};
+  }
+
+  template <typename T> bool is_net(Scope::specific_symbol_iterator<T> symbol) {
+    if constexpr (std::is_same_v<PortSymbol, T>)
+      return symbol->internalSymbol &&
+             symbol->internalSymbol->kind == slang::ast::SymbolKind::Net;
+    else
+      return std::is_same_v<NetSymbol, T>;
+  }
+
   template <typename T>
   uint64_t get_signal_width(Scope::specific_symbol_iterator<T> symbol) {
     return symbol->getType().getBitWidth();
   }
 
+  void set_dimensions(Signal &sig, const Type &type) {
+    vector<Dimension> dimensions;
+    const Type *t = &type.getCanonicalType();
+    while (t->kind == slang::ast::SymbolKind::FixedSizeUnpackedArrayType) {
+      auto &array_type = t->as<FixedSizeUnpackedArrayType>();
+      dimensions.push_back({array_type.range.left, array_type.range.right});
+      t = &array_type.elementType.getCanonicalType();
+    }
+    sig.dimensions_len = dimensions.size();
+    sig.dimensions = (Dimension *)malloc(dimensions.size() * sizeof(Dimension));
+    for (int i = 0; i < dimensions.size(); i++) {
+      sig.dimensions[i] = dimensions[i];
+    }
+    sig.type_info = get_type_info(*t);
+  }
+
+  // A vector of single bits, e.g. logic [7:0], is kept as TypeLogic. Only
+  // multiple packed dimensions or arrays of structured elements are listed as
+  // TypePackedArray.
+  TypeInfo *get_type_info(const Type &type) {
+    TypeInfo *type_info = (TypeInfo *)calloc(1, sizeof(TypeInfo));
+    type_info->kind = TypeLogic;
+    const Type &t = type.getCanonicalType();
+    switch (t.kind) {
+    case slang::ast::SymbolKind::PackedStructType:
+      type_info->kind = TypePackedStruct;
+      set_fields(type_info, t.as<PackedStructType>());
+      break;
+    case slang::ast::SymbolKind::PackedUnionType:
+      type_info->kind = TypePackedUnion;
+      set_fields(type_info, t.as<PackedUnionType>());
+      break;
+    case slang::ast::SymbolKind::EnumType:
+      type_info->kind = TypeEnum;
+      set_enum_values(type_info, t.as<EnumType>());
+      break;
+    case slang::ast::SymbolKind::PackedArrayType:
+      set_packed_dimensions(type_info, t);
+      break;
+    default:
+      break;
+    }
+    return type_info;
+  }
+
+  void set_fields(TypeInfo *type_info, const Scope &scope) {
+    vector<Field> fields;
+    for (auto &field : scope.membersOfType<FieldSymbol>()) {
+      fields.push_back({strdup(string(field.name).c_str()), field.bitOffset,
+                        field.getType().getBitWidth(),
+                        get_type_info(field.getType())});
+    }
+    type_info->fields_len = fields.size();
+    type_info->fields = (Field *)malloc(fields.size() * sizeof(Field));
+    for (int i = 0; i < fields.size(); i++) {
+      type_info->fields[i] = fields[i];
+    }
+  }
+
+  void set_enum_values(TypeInfo *type_info, const EnumType &enum_type) {
+    vector<EnumValue> values;
+    for (auto &value : enum_type.values()) {
+      auto &integer = value.getValue().integer();
+      string bits;
+      for (int32_t i = integer.getBitWidth() - 1; i >= 0; i--) {
+        bits.push_back(integer[i].toChar());
+      }
+      values.push_back(
+          {strdup(string(value.name).c_str()), strdup(bits.c_str())});
+    }
+    type_info->enum_values_len = values.size();
+    type_info->enum_values =
+        (EnumValue *)malloc(values.size() * sizeof(EnumValue));
+    for (int i = 0; i < values.size(); i++) {
+      type_info->enum_values[i] = values[i];
+    }
+  }
+
+  void set_packed_dimensions(TypeInfo *type_info, const Type &type) {
+    vector<Dimension> dimensions;
+    const Type *t = &type;
+    while (t->kind == slang::ast::SymbolKind::PackedArrayType) {
+      auto &array_type = t->as<PackedArrayType>();
+      dimensions.push_back({array_type.range.left, array_type.range.right});
+      t = &array_type.elementType.getCanonicalType();
+    }
+    if (dimensions.size() == 1 && t->isScalar()) {
+      return;
+    }
+    type_info->kind = TypePackedArray;
+    type_info->packed_dimensions_len = dimensions.size();
+    type_info->packed_dimensions =
+        (Dimension *)malloc(dimensions.size() * sizeof(Dimension));
+    for (int i = 0; i < dimensions.size(); i++) {
+      type_info->packed_dimensions[i] = dimensions[i];
+    }
+    type_info->element_type = get_type_info(*t);
+  }
+
   static bool is_port_with_name_inside(const char *name,
                                        const vector<Signal> &signals) {
     return std::find_if(signals.begin(), signals.end(), port_with_name(name)) !=
@@ -143,7 +287,8 @@ private:
   }
 
   static bool is_port(const Signal &s) {
-    return (s.type == UnpackedArrPortOut || s.type == UnpackedArrPortIn);
+    return (s.type == UnpackedArrPortOut || s.type == UnpackedArrPortIn ||
+            s.type == UnpackedArrPortInOut || s.type == UnpackedArrPortRef);
   }
 
   static std::function<bool(Signal)> port_with_name(const char *name) {
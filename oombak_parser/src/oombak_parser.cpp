@@ -3,29 +3,60 @@
 #include "instance_tree_builder.hpp"
 #include "slang/ast/Compilation.h"
 #include "slang/ast/symbols/CompilationUnitSymbols.h"
+#include "slang/diagnostics/DiagnosticEngine.h"
+#include "slang/parsing/Preprocessor.h"
 #include "slang/syntax/SyntaxTree.h"
+#include "slang/text/SourceManager.h"
+#include "slang/util/Bag.h"
 #include <cstdlib>
-#include <exception>
+#include <cstring>
+#include <string>
 #include <string_view>
+#include <vector>
 
+using slang::Bag;
+using slang::DiagnosticEngine;
+using slang::SourceManager;
+using slang::parsing::PreprocessorOptions;
 using slang::ast::Compilation;
+using slang::ast::CompilationOptions;
 using slang::syntax::SyntaxTree;
 
 std::vector<std::string_view>
 from_colon_separated_paths(const char *colon_separated_paths);
 
+struct OwnedDiagnostic {
+  DiagnosticSeverity severity;
+  std::string file;
+  uint64_t line;
+  uint64_t column;
+  std::string message;
+};
+
 class OombakParser {
 public:
   OombakParser();
-  Instance *get_instance_tree(const std::vector<std::string_view> &source_paths,
-                              std::string_view top_module_name);
+  ParseResult *
+  get_instance_tree(const std::vector<std::string_view> &source_paths,
+                    std::string_view top_module_name,
+                    const ParseOptions *parse_options);
 
 private:
   Instance root_instance;
+  ParseResult result;
+  // `diagnostics` points into `owned_diagnostics`, and is only filled once all
+  // of them are collected.
+  std::vector<OwnedDiagnostic> owned_diagnostics;
+  std::vector<Diagnostic> diagnostics;
 
   void add_syntax_trees(Compilation &compilation,
-                        const std::vector<std::string_view> &source_paths);
-  void check_compilation(Compilation &compilation);
+                        const std::vector<std::string_view> &source_paths,
+                        const Bag &bag);
+  bool check_compilation(Compilation &compilation);
+  void add_diagnostic(DiagnosticSeverity severity, std::string_view file,
+                      uint64_t line, uint64_t column,
+                      const std::string &message);
+  ParseResult *finish(Instance *instance);
 };
 
 static OombakParser *parser = new OombakParser();
@@ -39,48 +70,147 @@ OOMBAK_PARSER_EXPORT void oombak_parser_free_ctx(OombakCtx ctx) {
   delete parser;
 }
 
-OOMBAK_PARSER_EXPORT Instance *
+OOMBAK_PARSER_EXPORT ParseResult *
 oombak_parser_parse(const char *source_paths, const char *top_module_name) {
   std::vector<std::string_view> source_paths_vec =
       from_colon_separated_paths(source_paths);
-  return parser->get_instance_tree(source_paths_vec, top_module_name);
+  return parser->get_instance_tree(source_paths_vec, top_module_name, NULL);
 }
 
-OOMBAK_PARSER_EXPORT Instance *
-oombak_parser_parse(OombakCtx ctx, const char *source_paths,
-                    const char *top_module_name) {
+OOMBAK_PARSER_EXPORT ParseResult *
+oombak_parser_parse_r(OombakCtx ctx, const char *source_paths,
+                      const char *top_module_name) {
   auto parser = (OombakParser *)ctx;
   std::vector<std::string_view> source_paths_vec =
       from_colon_separated_paths(source_paths);
-  return parser->get_instance_tree(source_paths_vec, top_module_name);
+  return parser->get_instance_tree(source_paths_vec, top_module_name, NULL);
+}
+
+OOMBAK_PARSER_EXPORT ParseResult *
+oombak_parser_parse_with_options(const char *source_paths,
+                                 const char *top_module_name,
+                                 const ParseOptions *options) {
+  std::vector<std::string_view> source_paths_vec =
+      from_colon_separated_paths(source_paths);
+  return parser->get_instance_tree(source_paths_vec, top_module_name, options);
+}
+
+OOMBAK_PARSER_EXPORT ParseResult *
+oombak_parser_parse_with_options_r(OombakCtx ctx, const char *source_paths,
+                                   const char *top_module_name,
+                                   const ParseOptions *options) {
+  auto parser = (OombakParser *)ctx;
+  std::vector<std::string_view> source_paths_vec =
+      from_colon_separated_paths(source_paths);
+  return parser->get_instance_tree(source_paths_vec, top_module_name, options);
 }
 
 OombakParser::OombakParser() {}
 
-Instance *OombakParser::get_instance_tree(
+ParseResult *OombakParser::get_instance_tree(
     const std::vector<std::string_view> &source_paths,
-    std::string_view top_module_name) {
-  InstanceTreeBuilder visitor(&root_instance);
-  Compilation compilation;
-  add_syntax_trees(compilation, source_paths);
-  check_compilation(compilation);
+    std::string_view top_module_name, const ParseOptions *parse_options) {
+  owned_diagnostics.clear();
+  diagnostics.clear();
+  CompilationOptions options;
+  PreprocessorOptions preprocessor_options;
+  options.topModules.emplace(top_module_name);
+  if (parse_options) {
+    for (int i = 0; i < parse_options->include_dirs_len; i++) {
+      preprocessor_options.additionalIncludePaths.emplace_back(
+          parse_options->include_dirs[i]);
+    }
+    for (int i = 0; i < parse_options->defines_len; i++) {
+      preprocessor_options.predefines.emplace_back(parse_options->defines[i]);
+    }
+    for (int i = 0; i < parse_options->parameters_len; i++) {
+      options.paramOverrides.emplace_back(parse_options->parameters[i]);
+    }
+  }
+  Bag bag;
+  bag.set(options);
+  bag.set(preprocessor_options);
+  Compilation compilation(bag);
+  add_syntax_trees(compilation, source_paths, bag);
+  if (!check_compilation(compilation)) {
+    return finish(NULL);
+  }
+  InstanceTreeBuilder visitor(&root_instance,
+                              *compilation.getSourceManager());
   compilation.getRoot().visit(visitor);
-  return &root_instance;
+  return finish(&root_instance);
 }
 
 void OombakParser::add_syntax_trees(
     Compilation &compilation,
-    const std::vector<std::string_view> &source_paths) {
+    const std::vector<std::string_view> &source_paths, const Bag &bag) {
   for (auto path : source_paths) {
-    auto tree = SyntaxTree::fromFile(path).value();
-    compilation.addSyntaxTree(tree);
+    auto tree =
+        SyntaxTree::fromFile(path, SyntaxTree::getDefaultSourceManager(), bag);
+    if (!tree) {
+      add_diagnostic(SeverityError, path, 0, 0, "cannot open source file");
+      continue;
+    }
+    compilation.addSyntaxTree(*tree);
+  }
+}
+
+// Collects every diagnostic, and returns false if any of them is an error.
+bool OombakParser::check_compilation(Compilation &compilation) {
+  bool has_error = false;
+  for (auto &diagnostic : owned_diagnostics) {
+    has_error |= diagnostic.severity == SeverityError;
+  }
+  auto &source_manager = *compilation.getSourceManager();
+  DiagnosticEngine engine(source_manager);
+  for (auto &diag : compilation.getAllDiagnostics()) {
+    DiagnosticSeverity severity;
+    switch (engine.getSeverity(diag.code, diag.location)) {
+    case slang::DiagnosticSeverity::Ignored:
+      continue;
+    case slang::DiagnosticSeverity::Note:
+      severity = SeverityNote;
+      break;
+    case slang::DiagnosticSeverity::Warning:
+      severity = SeverityWarning;
+      break;
+    case slang::DiagnosticSeverity::Error:
+    case slang::DiagnosticSeverity::Fatal:
+      severity = SeverityError;
+      has_error = true;
+      break;
+    }
+    auto location = source_manager.getFullyOriginalLoc(diag.location);
+    if (location == slang::SourceLocation::NoLocation) {
+      add_diagnostic(severity, "", 0, 0, engine.formatMessage(diag));
+      continue;
+    }
+    add_diagnostic(severity, source_manager.getFileName(location),
+                   source_manager.getLineNumber(location),
+                   source_manager.getColumnNumber(location),
+                   engine.formatMessage(diag));
   }
+  return !has_error;
+}
+
+void OombakParser::add_diagnostic(DiagnosticSeverity severity,
+                                  std::string_view file, uint64_t line,
+                                  uint64_t column,
+                                  const std::string &message) {
+  owned_diagnostics.push_back(
+      {severity, std::string(file), line, column, message});
 }
 
-void OombakParser::check_compilation(Compilation &compilation) {
-  if (!compilation.getAllDiagnostics().empty()) {
-    throw new std::exception;
+ParseResult *OombakParser::finish(Instance *instance) {
+  for (auto &diagnostic : owned_diagnostics) {
+    diagnostics.push_back({diagnostic.severity, diagnostic.file.c_str(),
+                           diagnostic.line, diagnostic.column,
+                           diagnostic.message.c_str()});
   }
+  result.instance = instance;
+  result.diagnostics_len = diagnostics.size();
+  result.diagnostics = diagnostics.data();
+  return &result;
 }
 
 std::vector<std::string_view>
@@ -0,0 +1,77 @@
+#pragma once
+
+#include "slang/ast/ASTVisitor.h"
+
+using slang::ast::ASTVisitor;
+using slang::ast::AssignmentExpression;
+using slang::ast::BlockStatement;
+using slang::ast::ContinuousAssignSymbol;
+using slang::ast::InstanceSymbol;
+using slang::ast::NetSymbol;
+using slang::ast::ProceduralBlockKind;
+using slang::ast::ProceduralBlockSymbol;
+using slang::ast::StatementBlockKind;
+using slang::ast::TimedStatement;
+using slang::ast::TimingControlKind;
+using slang::ast::WaitForkStatement;
+using slang::ast::WaitOrderStatement;
+using slang::ast::WaitStatement;
+
+// Finds timing constructs which Verilator schedules with --timing, e.g.
+// delays, waits, forks, and event controls other than the one an always block
+// starts with. Child instances are not visited.
+class TimingFinder : public ASTVisitor<TimingFinder, true, true> {
+public:
+  bool found = false;
+
+  void handle(const InstanceSymbol &) {}
+
+  void handle(const ProceduralBlockSymbol &block) {
+    auto &body = block.getBody();
+    if (block.procedureKind != ProceduralBlockKind::Initial &&
+        block.procedureKind != ProceduralBlockKind::Final &&
+        body.kind == slang::ast::StatementKind::Timed &&
+        is_event_control(body.as<TimedStatement>())) {
+      body.as<TimedStatement>().stmt.visit(*this);
+      return;
+    }
+    visitDefault(block);
+  }
+
+  void handle(const TimedStatement &) { found = true; }
+  void handle(const WaitStatement &) { found = true; }
+  void handle(const WaitForkStatement &) { found = true; }
+  void handle(const WaitOrderStatement &) { found = true; }
+
+  void handle(const BlockStatement &block) {
+    found |= block.blockKind != StatementBlockKind::Sequential;
+    visitDefault(block);
+  }
+
+  void handle(const AssignmentExpression &expression) {
+    found |= expression.timingControl != nullptr;
+    visitDefault(expression);
+  }
+
+  void handle(const ContinuousAssignSymbol &assign) {
+    found |= assign.getDelay() != nullptr;
+    visitDefault(assign);
+  }
+
+  void handle(const NetSymbol &net) {
+    found |= net.getDelay() != nullptr;
+    visitDefault(net);
+  }
+
+private:
+  static bool is_event_control(const TimedStatement &statement) {
+    switch (statement.timing.kind) {
+    case TimingControlKind::SignalEvent:
+    case TimingControlKind::EventList:
+    case TimingControlKind::ImplicitEvent:
+      return true;
+    default:
+      return false;
+    }
+  }
+};
@@ -0,0 +1,15 @@
+module memory (
+    input logic clk,
+    input logic we,
+    input logic [9:0] addr,
+    input logic [31:0] wdata,
+    output logic [31:0] rdata
+);
+  logic [31:0] mem[0:1023];
+  logic [7:0] regs[3:0][0:1];
+
+  always_ff @(posedge clk) begin
+    if (we) mem[addr] <= wdata;
+    rdata <= mem[addr];
+  end
+endmodule
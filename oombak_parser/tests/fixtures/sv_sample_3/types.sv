@@ -0,0 +1,34 @@
+package types_pkg;
+  typedef enum logic [1:0] {
+    IDLE = 2'b00,
+    BUSY = 2'b01,
+    DONE = 2'b10
+  } state_t;
+
+  typedef struct packed {
+    logic [3:0] tag;
+    state_t state;
+    logic valid;
+  } entry_t;
+
+  typedef union packed {
+    logic [6:0] raw;
+    entry_t entry;
+  } slot_t;
+endpackage
+
+module types
+  import types_pkg::*;
+(
+    input logic clk,
+    input entry_t in_entry,
+    output state_t state
+);
+  slot_t slot;
+  logic [3:0][7:0] word;
+
+  always_ff @(posedge clk) begin
+    slot.entry <= in_entry;
+    state <= in_entry.state;
+  end
+endmodule
@@ -0,0 +1,18 @@
+module pads (
+    input logic oe,
+    input logic [7:0] out_data,
+    output logic [7:0] in_data,
+    inout wire [7:0] pad,
+    inout wire sda
+);
+  assign pad = oe ? out_data : 'z;
+  assign in_data = pad;
+  assign sda = 1'bz;
+
+  counter counter_inst (.count(in_data));
+endmodule
+
+module counter (
+    ref logic [7:0] count
+);
+endmodule
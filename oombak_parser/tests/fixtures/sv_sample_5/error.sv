@@ -0,0 +1,5 @@
+module diagnostics (output logic [3:0] out);
+  logic [3:0] narrow;
+  assign narrow = undeclared;
+  assign out = narrow;
+endmodule
@@ -0,0 +1,5 @@
+module diagnostics (output logic [3:0] out);
+  logic [3:0] narrow;
+  assign narrow = 8'hff;
+  assign out = narrow;
+endmodule
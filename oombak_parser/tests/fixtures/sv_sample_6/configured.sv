@@ -0,0 +1,12 @@
+`include "widths.svh"
+
+module configured #(
+    parameter int DEPTH = 2
+) (
+    input  logic [`DATA_WIDTH - 1:0] in,
+    output logic [DEPTH - 1:0] out
+);
+`ifdef WITH_FLAG
+  logic flag;
+`endif
+endmodule
@@ -0,0 +1,21 @@
+module timing (
+    input  logic clk,
+    output logic out
+);
+  logic ready;
+
+  initial begin
+    ready = 0;
+    #10 ready = 1;
+  end
+
+  delayed delayed_inst (.clk(clk), .ready(ready), .out(out));
+endmodule
+
+module delayed (
+    input  logic clk,
+    input  logic ready,
+    output logic out
+);
+  always_ff @(posedge clk) out <= ready;
+endmodule
@@ -1,12 +1,14 @@
 #include "oombak_parser.h"
 #include "utils.hpp"
 #include "gtest/gtest.h"
+#include <cstring>
 
 TEST(ParseTest, SvSample1) {
   const char *source_paths =
       "fixtures/sv_sample_1/sample.sv:fixtures/sv_sample_1/adder.sv";
   const char *top_module_name = "sample";
-  auto root_instance = oombak_parser_parse(source_paths, top_module_name);
+  auto root_instance =
+      oombak_parser_parse(source_paths, top_module_name)->instance;
 
   ASSERT_NE(root_instance, (Instance *)NULL);
   EXPECT_STREQ(root_instance->name, "sample");
@@ -21,6 +23,11 @@ TEST(ParseTest, SvSample1) {
   EXPECT_EQ(root_instance->signals_len, 5);
   EXPECT_TRUE(isContainsAll(root_instance->signals, root_instance->signals_len,
                             expected_signals, 5));
+  // `input logic` ports are nets, `output logic` ports are variables.
+  for (uint64_t i = 0; i < root_instance->signals_len; i++) {
+    const Signal &signal = root_instance->signals[i];
+    EXPECT_EQ(signal.is_net, signal.type != UnpackedArrPortOut) << signal.name;
+  }
 
   ASSERT_EQ(root_instance->child_instances_len, 1);
   auto child_instance = root_instance->child_instances[0];
@@ -29,4 +36,190 @@ TEST(ParseTest, SvSample1) {
   ASSERT_STREQ(child_instance->module_name, "adder");
   ASSERT_EQ(child_instance->child_instances_len, 0);
   ASSERT_EQ(child_instance->signals_len, 4);
+  EXPECT_FALSE(root_instance->uses_timing);
+  EXPECT_FALSE(child_instance->uses_timing);
+}
+
+TEST(ParseTest, SvSample2) {
+  const char *source_paths = "fixtures/sv_sample_2/memory.sv";
+  const char *top_module_name = "memory";
+  auto root_instance =
+      oombak_parser_parse(source_paths, top_module_name)->instance;
+
+  ASSERT_NE(root_instance, (Instance *)NULL);
+  ASSERT_EQ(root_instance->signals_len, 7);
+  for (int i = 0; i < root_instance->signals_len; i++) {
+    auto signal = root_instance->signals[i];
+    if (strcmp(signal.name, "mem") == 0) {
+      EXPECT_EQ(signal.width, 32 * 1024);
+      ASSERT_EQ(signal.dimensions_len, 1);
+      EXPECT_EQ(signal.dimensions[0].left, 0);
+      EXPECT_EQ(signal.dimensions[0].right, 1023);
+    } else if (strcmp(signal.name, "regs") == 0) {
+      EXPECT_EQ(signal.width, 8 * 4 * 2);
+      ASSERT_EQ(signal.dimensions_len, 2);
+      EXPECT_EQ(signal.dimensions[0].left, 3);
+      EXPECT_EQ(signal.dimensions[0].right, 0);
+      EXPECT_EQ(signal.dimensions[1].left, 0);
+      EXPECT_EQ(signal.dimensions[1].right, 1);
+    } else {
+      EXPECT_EQ(signal.dimensions_len, 0);
+    }
+  }
+}
+
+TEST(ParseTest, SvSample3) {
+  const char *source_paths = "fixtures/sv_sample_3/types.sv";
+  const char *top_module_name = "types";
+  auto root_instance =
+      oombak_parser_parse(source_paths, top_module_name)->instance;
+
+  ASSERT_NE(root_instance, (Instance *)NULL);
+  for (int i = 0; i < root_instance->signals_len; i++) {
+    auto signal = root_instance->signals[i];
+    ASSERT_NE(signal.type_info, (TypeInfo *)NULL);
+    auto type_info = signal.type_info;
+    if (strcmp(signal.name, "in_entry") == 0) {
+      EXPECT_EQ(signal.width, 7);
+      ASSERT_EQ(type_info->kind, TypePackedStruct);
+      ASSERT_EQ(type_info->fields_len, 3);
+      EXPECT_STREQ(type_info->fields[0].name, "tag");
+      EXPECT_EQ(type_info->fields[0].lsb, 3);
+      EXPECT_EQ(type_info->fields[0].width, 4);
+      EXPECT_STREQ(type_info->fields[1].name, "state");
+      EXPECT_EQ(type_info->fields[1].lsb, 1);
+      EXPECT_EQ(type_info->fields[1].type_info->kind, TypeEnum);
+      EXPECT_STREQ(type_info->fields[2].name, "valid");
+      EXPECT_EQ(type_info->fields[2].lsb, 0);
+    } else if (strcmp(signal.name, "state") == 0) {
+      ASSERT_EQ(type_info->kind, TypeEnum);
+      ASSERT_EQ(type_info->enum_values_len, 3);
+      EXPECT_STREQ(type_info->enum_values[2].name, "DONE");
+      EXPECT_STREQ(type_info->enum_values[2].value, "10");
+    } else if (strcmp(signal.name, "slot") == 0) {
+      ASSERT_EQ(type_info->kind, TypePackedUnion);
+      ASSERT_EQ(type_info->fields_len, 2);
+      EXPECT_EQ(type_info->fields[1].lsb, 0);
+      EXPECT_EQ(type_info->fields[1].type_info->kind, TypePackedStruct);
+    } else if (strcmp(signal.name, "word") == 0) {
+      ASSERT_EQ(type_info->kind, TypePackedArray);
+      ASSERT_EQ(type_info->packed_dimensions_len, 2);
+      EXPECT_EQ(type_info->packed_dimensions[0].left, 3);
+      EXPECT_EQ(type_info->packed_dimensions[1].left, 7);
+      EXPECT_EQ(type_info->element_type->kind, TypeLogic);
+    } else {
+      EXPECT_EQ(type_info->kind, TypeLogic);
+    }
+  }
+}
+
+TEST(ParseTest, SvSample4) {
+  const char *source_paths = "fixtures/sv_sample_4/pads.sv";
+  const char *top_module_name = "pads";
+  auto root_instance =
+      oombak_parser_parse(source_paths, top_module_name)->instance;
+
+  ASSERT_NE(root_instance, (Instance *)NULL);
+  Signal expected_signals[] = {{"oe", UnpackedArrPortIn, 1},
+                               {"out_data", UnpackedArrPortIn, 8},
+                               {"in_data", UnpackedArrPortOut, 8},
+                               {"pad", UnpackedArrPortInOut, 8},
+                               {"sda", UnpackedArrPortInOut, 1}};
+  EXPECT_TRUE(isContainsAll(root_instance->signals, root_instance->signals_len,
+                            expected_signals, 5));
+
+  ASSERT_EQ(root_instance->child_instances_len, 1);
+  auto child_instance = root_instance->child_instances[0];
+  Signal expected_child_signals[] = {{"count", UnpackedArrPortRef, 8}};
+  EXPECT_TRUE(isContainsAll(child_instance->signals,
+                            child_instance->signals_len,
+                            expected_child_signals, 1));
+}
+
+TEST(ParseTest, SvSample5) {
+  const char *top_module_name = "diagnostics";
+  auto result = oombak_parser_parse("fixtures/sv_sample_5/warning.sv",
+                                    top_module_name);
+  ASSERT_NE(result->instance, (Instance *)NULL);
+  ASSERT_GE(result->diagnostics_len, 1);
+  EXPECT_EQ(result->diagnostics[0].severity, SeverityWarning);
+  EXPECT_EQ(result->diagnostics[0].line, 3);
+
+  result = oombak_parser_parse("fixtures/sv_sample_5/error.sv", top_module_name);
+  ASSERT_EQ(result->instance, (Instance *)NULL);
+  ASSERT_GE(result->diagnostics_len, 1);
+  EXPECT_EQ(result->diagnostics[0].severity, SeverityError);
+  EXPECT_EQ(result->diagnostics[0].line, 3);
+  EXPECT_NE(strstr(result->diagnostics[0].file, "error.sv"), (char *)NULL);
+
+  result = oombak_parser_parse("fixtures/sv_sample_5/missing.sv",
+                               top_module_name);
+  ASSERT_EQ(result->instance, (Instance *)NULL);
+}
+
+TEST(ParseTest, SourceLocations) {
+  const char *source_paths =
+      "fixtures/sv_sample_1/sample.sv:fixtures/sv_sample_1/adder.sv";
+  auto root_instance = oombak_parser_parse(source_paths, "sample")->instance;
+
+  ASSERT_NE(root_instance, (Instance *)NULL);
+  EXPECT_NE(strstr(root_instance->location.file, "sample.sv"), (char *)NULL);
+  EXPECT_EQ(root_instance->location.line, 1);
+  for (int i = 0; i < root_instance->signals_len; i++) {
+    auto signal = root_instance->signals[i];
+    if (strcmp(signal.name, "clk") == 0) {
+      EXPECT_EQ(signal.location.line, 4);
+      EXPECT_EQ(signal.location.column, 17);
+    } else if (strcmp(signal.name, "c") == 0) {
+      EXPECT_EQ(signal.location.line, 9);
+      EXPECT_EQ(signal.location.column, 21);
+    }
+  }
+
+  ASSERT_EQ(root_instance->child_instances_len, 1);
+  auto child_instance = root_instance->child_instances[0];
+  EXPECT_NE(strstr(child_instance->location.file, "sample.sv"), (char *)NULL);
+  EXPECT_EQ(child_instance->location.line, 11);
+  for (int i = 0; i < child_instance->signals_len; i++) {
+    auto signal = child_instance->signals[i];
+    EXPECT_NE(strstr(signal.location.file, "adder.sv"), (char *)NULL);
+    if (strcmp(signal.name, "d") == 0) {
+      EXPECT_EQ(signal.location.line, 9);
+      EXPECT_EQ(signal.location.column, 6);
+    }
+  }
+}
+
+TEST(ParseTest, SvSample6) {
+  const char *source_paths = "fixtures/sv_sample_6/configured.sv";
+  const char *top_module_name = "configured";
+  auto result = oombak_parser_parse(source_paths, top_module_name);
+  ASSERT_EQ(result->instance, (Instance *)NULL);
+
+  const char *include_dirs[] = {"fixtures/sv_sample_6/include"};
+  const char *defines[] = {"WITH_FLAG"};
+  const char *parameters[] = {"DEPTH=5"};
+  ParseOptions options = {include_dirs, 1, defines, 1, parameters, 1};
+  auto root_instance =
+      oombak_parser_parse_with_options(source_paths, top_module_name, &options)
+          ->instance;
+
+  ASSERT_NE(root_instance, (Instance *)NULL);
+  Signal expected_signals[] = {{"in", UnpackedArrPortIn, 4},
+                               {"out", UnpackedArrPortOut, 5},
+                               {"flag", UnpackedArrVarNet, 1}};
+  EXPECT_TRUE(isContainsAll(root_instance->signals, root_instance->signals_len,
+                            expected_signals, 3));
+}
+
+TEST(ParseTest, SvSample7) {
+  const char *source_paths = "fixtures/sv_sample_7/timing.sv";
+  const char *top_module_name = "timing";
+  auto root_instance =
+      oombak_parser_parse(source_paths, top_module_name)->instance;
+
+  ASSERT_NE(root_instance, (Instance *)NULL);
+  EXPECT_TRUE(root_instance->uses_timing);
+  ASSERT_EQ(root_instance->child_instances_len, 1);
+  EXPECT_FALSE(root_instance->child_instances[0]->uses_timing);
 }
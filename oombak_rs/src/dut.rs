@@ -1,14 +1,27 @@
 use bitvec::vec::BitVec;
 use dut_sys::SigT;
-use std::ffi::{CStr, CString};
+use std::{
+    ffi::{CStr, CString},
+    fs::{self, File},
+    path::{Path, PathBuf},
+};
 use thiserror::Error;
 
-use crate::error::{OombakError, OombakResult};
+use crate::{
+    error::{OombakError, OombakResult},
+    parser::Dimension,
+};
 
 mod dut_sys;
+mod logic_vec;
+mod mem_file;
+
+pub use logic_vec::{Logic, LogicVec};
 
 #[derive(Debug, Error)]
 pub enum Error {
+    #[error("failed to create an instance")]
+    Create,
     #[error("failed to query signals")]
     Query,
     #[error("failed to run")]
@@ -17,6 +30,40 @@ pub enum Error {
     Set(String, BitVec<u32>),
     #[error("failed to get signal {}", _0)]
     Get(String),
+    #[error("failed to get signals")]
+    GetAll,
+    #[error("failed to force signal {} to value {}", _0, _1)]
+    Force(String, BitVec<u32>),
+    #[error("failed to release signal {}", _0)]
+    Release(String),
+    #[error("failed to deposit value {} on signal {}", _1, _0)]
+    Deposit(String, BitVec<u32>),
+    #[error("failed to get element {}", element_name(_0, _1))]
+    GetElement(String, Vec<i64>),
+    #[error("failed to get the contents of memory {}", _0)]
+    GetMemory(String),
+    #[error("failed to set element {} with value {}", element_name(_0, _1), _2)]
+    SetElement(String, Vec<i64>, BitVec<u32>),
+    #[error("signal {} is not a memory", _0)]
+    NotMemory(String),
+    #[error("failed to load {} into memory {}: {}", _1.display(), _0, _2)]
+    LoadMemory(String, PathBuf, String),
+    #[error("failed to dump memory {} to {}: {}", _0, _1.display(), _2)]
+    DumpMemory(String, PathBuf, String),
+    #[error("failed to save state to {}", _0.display())]
+    Save(PathBuf),
+    #[error("failed to restore state from {}", _0.display())]
+    Restore(PathBuf),
+    #[error("failed to take a snapshot")]
+    Snapshot,
+    #[error("failed to restore a snapshot")]
+    RestoreSnapshot,
+}
+
+fn element_name(sig_name: &str, indices: &[i64]) -> String {
+    indices
+        .iter()
+        .fold(sig_name.to_string(), |name, idx| format!("{name}[{idx}]"))
 }
 
 impl From<Error> for OombakError {
@@ -25,25 +72,35 @@ impl From<Error> for OombakError {
     }
 }
 
+/// One instance of the design. Instances created from the same library are independent of each
+/// other.
 pub struct Dut {
     lib: dut_sys::DutLib,
+    ctx: *mut dut_sys::DutCtx,
 }
 
+// The instance is only reached through `ctx`, which is owned by this `Dut`.
+unsafe impl Send for Dut {}
+
 impl Dut {
     pub fn new(lib_path: &str) -> OombakResult<Self> {
         let lib = dut_sys::DutLib::new(lib_path)?;
-        Ok(Dut { lib })
+        let ctx = lib.create()?;
+        if ctx.is_null() {
+            return Err(Error::Create.into());
+        }
+        Ok(Dut { lib, ctx })
     }
 
     pub fn query(&self) -> OombakResult<Vec<Signal>> {
         let mut num_of_signals: u64 = 0;
-        let sig_t_ptr = self.lib.query(&mut num_of_signals as *mut u64)?;
+        let sig_t_ptr = self.lib.query(self.ctx, &mut num_of_signals as *mut u64)?;
         Ok(Self::signals_from(sig_t_ptr, num_of_signals as usize))
     }
 
     pub fn run(&self, duration: u64) -> OombakResult<u64> {
         let current_time: u64 = 0;
-        match self.lib.run(duration, &current_time)? {
+        match self.lib.run(self.ctx, duration, &current_time)? {
             0 => Ok(current_time),
             _ => Err(Error::Run.into()),
         }
@@ -54,31 +111,234 @@ impl Dut {
         let words = bit_vec.as_raw_slice();
         match self
             .lib
-            .set(c_str.as_ptr(), words.as_ptr(), words.len() as u64)?
+            .set(self.ctx, c_str.as_ptr(), words.as_ptr(), words.len() as u64)?
         {
             0 => Ok(()),
             _ => Err(Error::Set(sig_name.to_string(), bit_vec.clone()).into()),
         }
     }
 
-    pub fn get(&self, sig_name: &str) -> OombakResult<BitVec<u32>> {
+    /// Holds the signal at `bit_vec` until it is released.
+    pub fn force(&self, sig_name: &str, bit_vec: &BitVec<u32>) -> OombakResult<()> {
+        let c_str = CString::new(sig_name)?;
+        let words = bit_vec.as_raw_slice();
+        match self
+            .lib
+            .force(self.ctx, c_str.as_ptr(), words.as_ptr(), words.len() as u64)?
+        {
+            0 => Ok(()),
+            _ => Err(Error::Force(sig_name.to_string(), bit_vec.clone()).into()),
+        }
+    }
+
+    pub fn release(&self, sig_name: &str) -> OombakResult<()> {
+        let c_str = CString::new(sig_name)?;
+        match self.lib.release(self.ctx, c_str.as_ptr())? {
+            0 => Ok(()),
+            _ => Err(Error::Release(sig_name.to_string()).into()),
+        }
+    }
+
+    /// Writes `bit_vec` once. A variable keeps it until its next assignment, a net is driven
+    /// again right away.
+    pub fn deposit(&self, sig_name: &str, bit_vec: &BitVec<u32>) -> OombakResult<()> {
+        let c_str = CString::new(sig_name)?;
+        let words = bit_vec.as_raw_slice();
+        match self
+            .lib
+            .deposit(self.ctx, c_str.as_ptr(), words.as_ptr(), words.len() as u64)?
+        {
+            0 => Ok(()),
+            _ => Err(Error::Deposit(sig_name.to_string(), bit_vec.clone()).into()),
+        }
+    }
+
+    pub fn get(&self, sig_name: &str) -> OombakResult<LogicVec> {
         let sig_name_cstr = CString::new(sig_name)?;
         let mut n_bits: u64 = 0;
         let words_ptr = self
             .lib
-            .get(sig_name_cstr.as_ptr(), &mut n_bits as *mut u64)?;
+            .get(self.ctx, sig_name_cstr.as_ptr(), &mut n_bits as *mut u64)?;
         if words_ptr.is_null() {
             return Err(Error::Get(sig_name.to_string()).into());
         }
-        Ok(Self::bitvec_from(words_ptr, n_bits as usize))
+        Ok(Self::logic_vec_from(words_ptr, n_bits as usize))
+    }
+
+    /// Resolves a gettable signal once, so that it can be read by `get_all` without its name.
+    pub fn handle_of(&self, sig_name: &str) -> OombakResult<SignalHandle> {
+        let sig_name_cstr = CString::new(sig_name)?;
+        let mut n_bits: u64 = 0;
+        let id = self
+            .lib
+            .handle_of(self.ctx, sig_name_cstr.as_ptr(), &mut n_bits as *mut u64)?;
+        if id < 0 {
+            return Err(Error::Get(sig_name.to_string()).into());
+        }
+        Ok(SignalHandle {
+            id: id as u64,
+            width: n_bits,
+        })
     }
 
-    fn bitvec_from(words_ptr: *const u32, n_bits: usize) -> BitVec<u32> {
-        let num_of_words = n_bits / 32 + if n_bits % 32 != 0 { 1 } else { 0 };
-        let slice = unsafe { std::slice::from_raw_parts(words_ptr, num_of_words) };
-        let mut bit_vec = BitVec::from_slice(slice);
-        bit_vec.truncate(n_bits);
-        bit_vec
+    /// Reads all signals of `handles` in one call, returning their values in the same order.
+    pub fn get_all<'a>(&self, handles: &'a mut SignalHandles) -> OombakResult<&'a [LogicVec]> {
+        match self.lib.get_all(
+            self.ctx,
+            handles.ids.as_ptr(),
+            handles.ids.len() as u64,
+            handles.words.as_mut_ptr(),
+            handles.words.len() as u64,
+        )? {
+            0 => {
+                handles.decode();
+                Ok(&handles.values)
+            }
+            _ => Err(Error::GetAll.into()),
+        }
+    }
+
+    /// Reads one element of an unpacked array, with one index per dimension.
+    pub fn get_element(&self, sig_name: &str, indices: &[i64]) -> OombakResult<LogicVec> {
+        let sig_name_cstr = CString::new(sig_name)?;
+        let mut n_bits: u64 = 0;
+        let words_ptr = self.lib.get_element(
+            self.ctx,
+            sig_name_cstr.as_ptr(),
+            indices.as_ptr(),
+            indices.len() as u64,
+            &mut n_bits as *mut u64,
+        )?;
+        if words_ptr.is_null() {
+            return Err(Error::GetElement(sig_name.to_string(), indices.to_vec()).into());
+        }
+        Ok(Self::logic_vec_from(words_ptr, n_bits as usize))
+    }
+
+    /// Copies every element of a memory from its lowest index into `words` in one call, each
+    /// laid out as by `get` and so taking `LogicVec::words_len` of the element width.
+    pub fn get_memory(&self, sig_name: &str, words: &mut [u32]) -> OombakResult<()> {
+        let sig_name_cstr = CString::new(sig_name)?;
+        match self.lib.get_memory(
+            self.ctx,
+            sig_name_cstr.as_ptr(),
+            words.as_mut_ptr(),
+            words.len() as u64,
+        )? {
+            0 => Ok(()),
+            _ => Err(Error::GetMemory(sig_name.to_string()).into()),
+        }
+    }
+
+    pub fn set_element(
+        &self,
+        sig_name: &str,
+        indices: &[i64],
+        bit_vec: &BitVec<u32>,
+    ) -> OombakResult<()> {
+        let c_str = CString::new(sig_name)?;
+        let words = bit_vec.as_raw_slice();
+        match self.lib.set_element(
+            self.ctx,
+            c_str.as_ptr(),
+            indices.as_ptr(),
+            indices.len() as u64,
+            words.as_ptr(),
+            words.len() as u64,
+        )? {
+            0 => Ok(()),
+            _ => Err(
+                Error::SetElement(sig_name.to_string(), indices.to_vec(), bit_vec.clone()).into(),
+            ),
+        }
+    }
+
+    /// Loads a `$readmemh` file into a memory, which must have a single unpacked dimension.
+    pub fn load_memory(&self, sig_name: &str, file_path: &Path) -> OombakResult<()> {
+        let load_error =
+            |message: String| Error::LoadMemory(sig_name.to_string(), file_path.into(), message);
+        let dimension = self.memory_dimension(sig_name)?;
+        let text = fs::read_to_string(file_path).map_err(|e| load_error(e.to_string()))?;
+        mem_file::check_hex(&text, &dimension).map_err(load_error)?;
+        let c_str = CString::new(sig_name)?;
+        let path_cstr = CString::new(file_path.to_string_lossy().as_bytes())?;
+        match self
+            .lib
+            .load_memory(self.ctx, c_str.as_ptr(), path_cstr.as_ptr())?
+        {
+            0 => Ok(()),
+            _ => Err(load_error("not loadable".to_string()).into()),
+        }
+    }
+
+    /// Writes a memory to a file with `$writememh`.
+    pub fn dump_memory(&self, sig_name: &str, file_path: &Path) -> OombakResult<()> {
+        let dump_error =
+            |message: String| Error::DumpMemory(sig_name.to_string(), file_path.into(), message);
+        self.memory_dimension(sig_name)?;
+        File::create(file_path).map_err(|e| dump_error(e.to_string()))?;
+        let c_str = CString::new(sig_name)?;
+        let path_cstr = CString::new(file_path.to_string_lossy().as_bytes())?;
+        match self
+            .lib
+            .dump_memory(self.ctx, c_str.as_ptr(), path_cstr.as_ptr())?
+        {
+            0 => Ok(()),
+            _ => Err(dump_error("not dumpable".to_string()).into()),
+        }
+    }
+
+    /// Writes the whole state of the instance, including its time, to `file_path`.
+    pub fn save(&self, file_path: &Path) -> OombakResult<()> {
+        let path_cstr = CString::new(file_path.to_string_lossy().as_bytes())?;
+        match self.lib.save(self.ctx, path_cstr.as_ptr())? {
+            0 => Ok(()),
+            _ => Err(Error::Save(file_path.into()).into()),
+        }
+    }
+
+    /// Reads back a state written by `save` from an instance of the same library.
+    pub fn restore(&self, file_path: &Path) -> OombakResult<()> {
+        let path_cstr = CString::new(file_path.to_string_lossy().as_bytes())?;
+        match self.lib.restore(self.ctx, path_cstr.as_ptr())? {
+            0 => Ok(()),
+            _ => Err(Error::Restore(file_path.into()).into()),
+        }
+    }
+
+    /// Same as `save`, but kept in memory.
+    pub fn snapshot(&self) -> OombakResult<Snapshot> {
+        let mut len: u64 = 0;
+        let data_ptr = self.lib.snapshot(self.ctx, &mut len as *mut u64)?;
+        if data_ptr.is_null() {
+            return Err(Error::Snapshot.into());
+        }
+        let data = unsafe { std::slice::from_raw_parts(data_ptr, len as usize) }.to_vec();
+        Ok(Snapshot { data })
+    }
+
+    pub fn restore_snapshot(&self, snapshot: &Snapshot) -> OombakResult<()> {
+        match self.lib.restore_snapshot(
+            self.ctx,
+            snapshot.data.as_ptr(),
+            snapshot.data.len() as u64,
+        )? {
+            0 => Ok(()),
+            _ => Err(Error::RestoreSnapshot.into()),
+        }
+    }
+
+    fn memory_dimension(&self, sig_name: &str) -> OombakResult<Dimension> {
+        match self.query()?.into_iter().find(|s| s.name == sig_name) {
+            Some(Signal { dimensions, .. }) if dimensions.len() == 1 => Ok(dimensions[0]),
+            _ => Err(Error::NotMemory(sig_name.to_string()).into()),
+        }
+    }
+
+    /// `get` returns the `aval` words followed by the same number of `bval` words.
+    fn logic_vec_from(words_ptr: *const u32, n_bits: usize) -> LogicVec {
+        let slice = unsafe { std::slice::from_raw_parts(words_ptr, LogicVec::words_len(n_bits)) };
+        LogicVec::from_words(slice, n_bits)
     }
 
     fn signals_from(sig_t_ptr: *const dut_sys::SigT, num_of_signals: usize) -> Vec<Signal> {
@@ -87,12 +347,84 @@ impl Dut {
     }
 }
 
+impl Drop for Dut {
+    fn drop(&mut self) {
+        let _ = self.lib.destroy(self.ctx);
+    }
+}
+
+/// State of a `Dut` taken by `snapshot`, which can be restored into any instance of the same
+/// library.
+#[derive(Clone)]
+pub struct Snapshot {
+    data: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SignalHandle {
+    id: u64,
+    width: u64,
+}
+
+impl SignalHandle {
+    pub fn width(&self) -> u64 {
+        self.width
+    }
+
+    /// Number of words the signal takes in a `get_all` buffer.
+    pub fn num_of_words(&self) -> usize {
+        LogicVec::words_len(self.width as usize)
+    }
+}
+
+/// Signals resolved once by `Dut::handle_of`, with the buffers `Dut::get_all` reads them into
+/// so that reading them again allocates nothing.
+#[derive(Debug, Default)]
+pub struct SignalHandles {
+    handles: Vec<SignalHandle>,
+    ids: Vec<u64>,
+    /// Each signal laid out as by `get`, one after another.
+    words: Vec<u32>,
+    values: Vec<LogicVec>,
+}
+
+impl SignalHandles {
+    pub fn new(handles: Vec<SignalHandle>) -> Self {
+        let ids = handles.iter().map(|h| h.id).collect();
+        let words = vec![0; handles.iter().map(SignalHandle::num_of_words).sum()];
+        let values = handles
+            .iter()
+            .map(|h| LogicVec::filled(Logic::X, h.width as usize))
+            .collect();
+        Self {
+            handles,
+            ids,
+            words,
+            values,
+        }
+    }
+
+    /// Splits `words` back into one value per handle.
+    fn decode(&mut self) {
+        let mut offset = 0;
+        for (handle, value) in self.handles.iter().zip(self.values.iter_mut()) {
+            let len = handle.num_of_words();
+            value.assign_words(&self.words[offset..offset + len], handle.width as usize);
+            offset += len;
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Signal {
     pub name: String,
     pub width: u64,
     pub get: bool,
     pub set: bool,
+    pub force: bool,
+    pub set_element: bool,
+    /// Unpacked dimensions, empty unless the signal is an array.
+    pub dimensions: Vec<Dimension>,
 }
 
 impl From<&SigT> for Signal {
@@ -101,11 +433,53 @@ impl From<&SigT> for Signal {
             String::from_utf8_lossy((unsafe { CStr::from_ptr(value.name) }).to_bytes()).to_string();
         let get = value.get == 1;
         let set = value.set == 1;
+        let force = value.force == 1;
+        let set_element = value.set_element == 1;
+        let dimensions = if value.dimensions_len == 0 {
+            vec![]
+        } else {
+            unsafe { std::slice::from_raw_parts(value.dimensions, value.dimensions_len as usize) }
+                .iter()
+                .map(|d| Dimension {
+                    left: d.left,
+                    right: d.right,
+                })
+                .collect()
+        };
         Signal {
             name,
             width: value.width,
             get,
             set,
+            force,
+            set_element,
+            dimensions,
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{LogicVec, SignalHandle, SignalHandles};
+
+    #[test]
+    fn test_decode() {
+        let mut handles = SignalHandles::new(vec![
+            SignalHandle { id: 0, width: 1 },
+            SignalHandle { id: 3, width: 40 },
+        ]);
+        assert_eq!(handles.ids, [0, 3]);
+        let words = [0b1, 0b0, 0xffff_ffff, 0x12, 0x1, 0x0];
+        assert_eq!(handles.words.len(), words.len());
+
+        handles.words.copy_from_slice(&words);
+        handles.decode();
+        let values = &handles.values;
+        assert_eq!(values[0], LogicVec::from_ascii(b"1").unwrap());
+        let expected = format!("00010010{}x", "1".repeat(31));
+        assert_eq!(
+            values[1],
+            LogicVec::from_ascii(expected.as_bytes()).unwrap()
+        );
+    }
+}
@@ -13,33 +13,208 @@ impl DutLib {
         Ok(DutLib { lib })
     }
 
-    pub fn query(&self, num_of_signals: *mut u64) -> OombakResult<*const SigT> {
-        let f: Symbol<unsafe extern "C" fn(*mut u64) -> *const SigT> =
+    pub fn create(&self) -> OombakResult<*mut DutCtx> {
+        let f: Symbol<unsafe extern "C" fn() -> *mut DutCtx> = unsafe { self.lib.get(b"create")? };
+        Ok(unsafe { f() })
+    }
+
+    pub fn destroy(&self, ctx: *mut DutCtx) -> OombakResult<()> {
+        let f: Symbol<unsafe extern "C" fn(*mut DutCtx)> = unsafe { self.lib.get(b"destroy")? };
+        unsafe { f(ctx) };
+        Ok(())
+    }
+
+    pub fn query(&self, ctx: *mut DutCtx, num_of_signals: *mut u64) -> OombakResult<*const SigT> {
+        let f: Symbol<unsafe extern "C" fn(*mut DutCtx, *mut u64) -> *const SigT> =
             unsafe { self.lib.get(b"query")? };
-        Ok(unsafe { f(num_of_signals) })
+        Ok(unsafe { f(ctx, num_of_signals) })
     }
 
-    pub fn run(&self, duration: u64, current_time_o: *const u64) -> OombakResult<c_int> {
-        let f: Symbol<unsafe extern "C" fn(u64, *const u64) -> c_int> =
+    pub fn run(
+        &self,
+        ctx: *mut DutCtx,
+        duration: u64,
+        current_time_o: *const u64,
+    ) -> OombakResult<c_int> {
+        let f: Symbol<unsafe extern "C" fn(*mut DutCtx, u64, *const u64) -> c_int> =
             unsafe { self.lib.get(b"run")? };
-        Ok(unsafe { f(duration, current_time_o) })
+        Ok(unsafe { f(ctx, duration, current_time_o) })
     }
 
     pub fn set(
         &self,
+        ctx: *mut DutCtx,
         sig_name: *const c_char,
         words: *const u32,
         num_of_words: u64,
     ) -> OombakResult<c_int> {
-        let f: Symbol<unsafe extern "C" fn(*const c_char, *const u32, u64) -> c_int> =
+        let f: Symbol<unsafe extern "C" fn(*mut DutCtx, *const c_char, *const u32, u64) -> c_int> =
             unsafe { self.lib.get(b"set")? };
-        Ok(unsafe { f(sig_name, words, num_of_words) })
+        Ok(unsafe { f(ctx, sig_name, words, num_of_words) })
+    }
+
+    pub fn force(
+        &self,
+        ctx: *mut DutCtx,
+        sig_name: *const c_char,
+        words: *const u32,
+        num_of_words: u64,
+    ) -> OombakResult<c_int> {
+        let f: Symbol<unsafe extern "C" fn(*mut DutCtx, *const c_char, *const u32, u64) -> c_int> =
+            unsafe { self.lib.get(b"force")? };
+        Ok(unsafe { f(ctx, sig_name, words, num_of_words) })
+    }
+
+    pub fn release(&self, ctx: *mut DutCtx, sig_name: *const c_char) -> OombakResult<c_int> {
+        let f: Symbol<unsafe extern "C" fn(*mut DutCtx, *const c_char) -> c_int> =
+            unsafe { self.lib.get(b"release")? };
+        Ok(unsafe { f(ctx, sig_name) })
+    }
+
+    pub fn deposit(
+        &self,
+        ctx: *mut DutCtx,
+        sig_name: *const c_char,
+        words: *const u32,
+        num_of_words: u64,
+    ) -> OombakResult<c_int> {
+        let f: Symbol<unsafe extern "C" fn(*mut DutCtx, *const c_char, *const u32, u64) -> c_int> =
+            unsafe { self.lib.get(b"deposit")? };
+        Ok(unsafe { f(ctx, sig_name, words, num_of_words) })
     }
 
-    pub fn get(&self, sig_name: *const c_char, n_bits: *mut u64) -> OombakResult<*const u32> {
-        let f: Symbol<unsafe extern "C" fn(*const c_char, *mut u64) -> *mut u32> =
+    pub fn get(
+        &self,
+        ctx: *mut DutCtx,
+        sig_name: *const c_char,
+        n_bits: *mut u64,
+    ) -> OombakResult<*const u32> {
+        let f: Symbol<unsafe extern "C" fn(*mut DutCtx, *const c_char, *mut u64) -> *mut u32> =
             unsafe { self.lib.get(b"get")? };
-        Ok(unsafe { f(sig_name, n_bits) })
+        Ok(unsafe { f(ctx, sig_name, n_bits) })
+    }
+
+    pub fn handle_of(
+        &self,
+        ctx: *mut DutCtx,
+        sig_name: *const c_char,
+        n_bits: *mut u64,
+    ) -> OombakResult<i64> {
+        let f: Symbol<unsafe extern "C" fn(*mut DutCtx, *const c_char, *mut u64) -> i64> =
+            unsafe { self.lib.get(b"handle_of")? };
+        Ok(unsafe { f(ctx, sig_name, n_bits) })
+    }
+
+    pub fn get_all(
+        &self,
+        ctx: *mut DutCtx,
+        handles: *const u64,
+        num_of_handles: u64,
+        words: *mut u32,
+        num_of_words: u64,
+    ) -> OombakResult<c_int> {
+        let f: Symbol<unsafe extern "C" fn(*mut DutCtx, *const u64, u64, *mut u32, u64) -> c_int> =
+            unsafe { self.lib.get(b"get_all")? };
+        Ok(unsafe { f(ctx, handles, num_of_handles, words, num_of_words) })
+    }
+
+    pub fn get_element(
+        &self,
+        ctx: *mut DutCtx,
+        sig_name: *const c_char,
+        indices: *const i64,
+        num_of_indices: u64,
+        n_bits: *mut u64,
+    ) -> OombakResult<*const u32> {
+        let f: Symbol<
+            unsafe extern "C" fn(*mut DutCtx, *const c_char, *const i64, u64, *mut u64) -> *mut u32,
+        > = unsafe { self.lib.get(b"get_element")? };
+        Ok(unsafe { f(ctx, sig_name, indices, num_of_indices, n_bits) })
+    }
+
+    pub fn get_memory(
+        &self,
+        ctx: *mut DutCtx,
+        sig_name: *const c_char,
+        words: *mut u32,
+        num_of_words: u64,
+    ) -> OombakResult<c_int> {
+        let f: Symbol<unsafe extern "C" fn(*mut DutCtx, *const c_char, *mut u32, u64) -> c_int> =
+            unsafe { self.lib.get(b"get_memory")? };
+        Ok(unsafe { f(ctx, sig_name, words, num_of_words) })
+    }
+
+    pub fn set_element(
+        &self,
+        ctx: *mut DutCtx,
+        sig_name: *const c_char,
+        indices: *const i64,
+        num_of_indices: u64,
+        words: *const u32,
+        num_of_words: u64,
+    ) -> OombakResult<c_int> {
+        let f: Symbol<
+            unsafe extern "C" fn(
+                *mut DutCtx,
+                *const c_char,
+                *const i64,
+                u64,
+                *const u32,
+                u64,
+            ) -> c_int,
+        > = unsafe { self.lib.get(b"set_element")? };
+        Ok(unsafe { f(ctx, sig_name, indices, num_of_indices, words, num_of_words) })
+    }
+
+    pub fn load_memory(
+        &self,
+        ctx: *mut DutCtx,
+        sig_name: *const c_char,
+        file_path: *const c_char,
+    ) -> OombakResult<c_int> {
+        let f: Symbol<unsafe extern "C" fn(*mut DutCtx, *const c_char, *const c_char) -> c_int> =
+            unsafe { self.lib.get(b"load_memory")? };
+        Ok(unsafe { f(ctx, sig_name, file_path) })
+    }
+
+    pub fn dump_memory(
+        &self,
+        ctx: *mut DutCtx,
+        sig_name: *const c_char,
+        file_path: *const c_char,
+    ) -> OombakResult<c_int> {
+        let f: Symbol<unsafe extern "C" fn(*mut DutCtx, *const c_char, *const c_char) -> c_int> =
+            unsafe { self.lib.get(b"dump_memory")? };
+        Ok(unsafe { f(ctx, sig_name, file_path) })
+    }
+
+    pub fn save(&self, ctx: *mut DutCtx, file_path: *const c_char) -> OombakResult<c_int> {
+        let f: Symbol<unsafe extern "C" fn(*mut DutCtx, *const c_char) -> c_int> =
+            unsafe { self.lib.get(b"save")? };
+        Ok(unsafe { f(ctx, file_path) })
+    }
+
+    pub fn snapshot(&self, ctx: *mut DutCtx, len: *mut u64) -> OombakResult<*const u8> {
+        let f: Symbol<unsafe extern "C" fn(*mut DutCtx, *mut u64) -> *const u8> =
+            unsafe { self.lib.get(b"snapshot")? };
+        Ok(unsafe { f(ctx, len) })
+    }
+
+    pub fn restore_snapshot(
+        &self,
+        ctx: *mut DutCtx,
+        data: *const u8,
+        len: u64,
+    ) -> OombakResult<c_int> {
+        let f: Symbol<unsafe extern "C" fn(*mut DutCtx, *const u8, u64) -> c_int> =
+            unsafe { self.lib.get(b"restore_snapshot")? };
+        Ok(unsafe { f(ctx, data, len) })
+    }
+
+    pub fn restore(&self, ctx: *mut DutCtx, file_path: *const c_char) -> OombakResult<c_int> {
+        let f: Symbol<unsafe extern "C" fn(*mut DutCtx, *const c_char) -> c_int> =
+            unsafe { self.lib.get(b"restore")? };
+        Ok(unsafe { f(ctx, file_path) })
     }
 }
 
@@ -49,4 +224,20 @@ pub struct SigT {
     pub width: u64,
     pub get: u8,
     pub set: u8,
+    pub force: u8,
+    pub set_element: u8,
+    pub dimensions: *const DimT,
+    pub dimensions_len: u64,
+}
+
+#[repr(C)]
+pub struct DimT {
+    pub left: i64,
+    pub right: i64,
+}
+
+/// Opaque instance created by `create`.
+#[repr(C)]
+pub struct DutCtx {
+    _private: [u8; 0],
 }
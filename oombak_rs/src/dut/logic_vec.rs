@@ -0,0 +1,255 @@
+use std::fmt::Display;
+
+use bitvec::{order::Lsb0, vec::BitVec, view::BitView};
+
+/// A single four-state bit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Logic {
+    Zero,
+    One,
+    X,
+    Z,
+}
+
+/// Four-state vector, stored the way DPI's `svLogicVecVal` does: a bit is 0 as (0, 0), 1 as
+/// (1, 0), Z as (0, 1) and X as (1, 1) in (`aval`, `bval`). Index 0 is the least significant
+/// bit.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LogicVec {
+    aval: BitVec<u32>,
+    bval: BitVec<u32>,
+}
+
+impl LogicVec {
+    /// `bval` is resized to the length of `aval`.
+    pub fn new(aval: BitVec<u32>, mut bval: BitVec<u32>) -> Self {
+        bval.resize(aval.len(), false);
+        Self { aval, bval }
+    }
+
+    pub fn filled(logic: Logic, len: usize) -> Self {
+        let (a, b) = logic.to_ab();
+        Self {
+            aval: BitVec::repeat(a, len),
+            bval: BitVec::repeat(b, len),
+        }
+    }
+
+    /// Number of words a value of `len` bits takes when laid out as DPI does, its `aval` words
+    /// followed by as many `bval` words.
+    pub fn words_len(len: usize) -> usize {
+        2 * len.div_ceil(32)
+    }
+
+    /// Reads `len` bits laid out as in `words_len`.
+    pub fn from_words(words: &[u32], len: usize) -> Self {
+        let (aval_words, bval_words) = words.split_at(words.len() / 2);
+        let mut aval = BitVec::from_slice(aval_words);
+        let mut bval = BitVec::from_slice(bval_words);
+        aval.truncate(len);
+        bval.truncate(len);
+        Self { aval, bval }
+    }
+
+    /// Same as `from_words`, but reads into `self`, reusing its storage.
+    pub fn assign_words(&mut self, words: &[u32], len: usize) {
+        let (aval_words, bval_words) = words.split_at(words.len() / 2);
+        self.aval.clear();
+        self.aval
+            .extend_from_bitslice(&aval_words.view_bits::<Lsb0>()[..len]);
+        self.bval.clear();
+        self.bval
+            .extend_from_bitslice(&bval_words.view_bits::<Lsb0>()[..len]);
+    }
+
+    /// Parses the MSB-first notation used by VCD, e.g. `10xz`. Case is ignored.
+    pub fn from_ascii(value: &[u8]) -> Option<Self> {
+        let mut logic_vec = LogicVec::default();
+        for c in value.iter().rev() {
+            logic_vec.push(Logic::from_ascii(*c)?);
+        }
+        Some(logic_vec)
+    }
+
+    pub fn len(&self) -> usize {
+        self.aval.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.aval.is_empty()
+    }
+
+    pub fn get(&self, idx: usize) -> Option<Logic> {
+        let a = *self.aval.get(idx)?;
+        let b = *self.bval.get(idx)?;
+        Some(Logic::from_ab(a, b))
+    }
+
+    pub fn push(&mut self, logic: Logic) {
+        let (a, b) = logic.to_ab();
+        self.aval.push(a);
+        self.bval.push(b);
+    }
+
+    pub fn resize(&mut self, len: usize, fill: Logic) {
+        let (a, b) = fill.to_ab();
+        self.aval.resize(len, a);
+        self.bval.resize(len, b);
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = Logic> + '_ {
+        self.aval
+            .iter()
+            .zip(self.bval.iter())
+            .map(|(a, b)| Logic::from_ab(*a, *b))
+    }
+
+    pub fn aval(&self) -> &BitVec<u32> {
+        &self.aval
+    }
+
+    pub fn bval(&self) -> &BitVec<u32> {
+        &self.bval
+    }
+
+    /// Whether every bit is 0 or 1.
+    pub fn is_known(&self) -> bool {
+        self.bval.not_any()
+    }
+
+    pub fn all(&self, logic: Logic) -> bool {
+        !self.is_empty() && self.iter().all(|l| l == logic)
+    }
+
+    pub fn contains(&self, logic: Logic) -> bool {
+        self.iter().any(|l| l == logic)
+    }
+
+    /// The two-state value, or `None` if any bit is X or Z.
+    pub fn to_bitvec(&self) -> Option<BitVec<u32>> {
+        self.is_known().then(|| self.aval.clone())
+    }
+
+    /// The two-state value with X and Z bits read as 0.
+    pub fn to_bitvec_lossy(&self) -> BitVec<u32> {
+        self.aval.clone() & !self.bval.clone()
+    }
+}
+
+impl From<BitVec<u32>> for LogicVec {
+    fn from(bit_vec: BitVec<u32>) -> Self {
+        let len = bit_vec.len();
+        Self {
+            aval: bit_vec,
+            bval: BitVec::repeat(false, len),
+        }
+    }
+}
+
+impl From<&BitVec<u32>> for LogicVec {
+    fn from(bit_vec: &BitVec<u32>) -> Self {
+        Self::from(bit_vec.clone())
+    }
+}
+
+impl Display for LogicVec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for logic in self.iter().rev() {
+            write!(f, "{logic}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Logic {
+    fn from_ab(a: bool, b: bool) -> Self {
+        match (a, b) {
+            (false, false) => Logic::Zero,
+            (true, false) => Logic::One,
+            (false, true) => Logic::Z,
+            (true, true) => Logic::X,
+        }
+    }
+
+    fn to_ab(self) -> (bool, bool) {
+        match self {
+            Logic::Zero => (false, false),
+            Logic::One => (true, false),
+            Logic::Z => (false, true),
+            Logic::X => (true, true),
+        }
+    }
+
+    pub fn from_ascii(c: u8) -> Option<Self> {
+        match c {
+            b'0' => Some(Logic::Zero),
+            b'1' => Some(Logic::One),
+            b'x' | b'X' => Some(Logic::X),
+            b'z' | b'Z' => Some(Logic::Z),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Logic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let c = match self {
+            Logic::Zero => '0',
+            Logic::One => '1',
+            Logic::X => 'x',
+            Logic::Z => 'z',
+        };
+        write!(f, "{c}")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bitvec::{bitvec, order::Lsb0};
+
+    use super::{Logic, LogicVec};
+
+    #[test]
+    fn test_from_ascii() {
+        let logic_vec = LogicVec::from_ascii(b"1xZ0").unwrap();
+        assert_eq!(logic_vec.len(), 4);
+        assert_eq!(logic_vec.get(0), Some(Logic::Zero));
+        assert_eq!(logic_vec.get(1), Some(Logic::Z));
+        assert_eq!(logic_vec.get(2), Some(Logic::X));
+        assert_eq!(logic_vec.get(3), Some(Logic::One));
+        assert_eq!(logic_vec.aval(), &bitvec![u32, Lsb0; 0, 0, 1, 1]);
+        assert_eq!(logic_vec.bval(), &bitvec![u32, Lsb0; 0, 1, 1, 0]);
+        assert_eq!(logic_vec.to_string(), "1xz0");
+        assert!(LogicVec::from_ascii(b"10u").is_none());
+    }
+
+    #[test]
+    fn test_from_words() {
+        assert_eq!(LogicVec::words_len(2), 2);
+        assert_eq!(LogicVec::words_len(33), 4);
+        let value = LogicVec::from_words(&[0b10, 0b01], 2);
+        assert_eq!(value, LogicVec::from_ascii(b"1z").unwrap());
+        let value = LogicVec::from_words(&[u32::MAX, 0b1, 0, 0b1], 33);
+        let expected = format!("x{}", "1".repeat(32));
+        assert_eq!(value, LogicVec::from_ascii(expected.as_bytes()).unwrap());
+
+        let mut value = value;
+        value.assign_words(&[0b10, 0b01], 2);
+        assert_eq!(value, LogicVec::from_ascii(b"1z").unwrap());
+    }
+
+    #[test]
+    fn test_to_bitvec() {
+        let known = LogicVec::from(bitvec![u32, Lsb0; 1, 0, 1]);
+        assert!(known.is_known());
+        assert_eq!(known.to_bitvec(), Some(bitvec![u32, Lsb0; 1, 0, 1]));
+
+        let unknown = LogicVec::from_ascii(b"1x1z").unwrap();
+        assert!(!unknown.is_known());
+        assert!(unknown.contains(Logic::X));
+        assert!(!unknown.all(Logic::Z));
+        assert_eq!(unknown.to_bitvec(), None);
+        assert_eq!(unknown.to_bitvec_lossy(), bitvec![u32, Lsb0; 0, 1, 0, 1]);
+        assert!(LogicVec::filled(Logic::Z, 3).all(Logic::Z));
+    }
+}
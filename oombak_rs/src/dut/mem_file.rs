@@ -0,0 +1,102 @@
+use crate::parser::Dimension;
+
+/// Checks a `$readmemh` file against the memory it is loaded into. Verilator aborts the whole
+/// process on a malformed file or an out of range address, so this has to be caught first.
+/// Words are loaded from the lowest address upwards, unless moved by an `@addr` directive.
+pub fn check_hex(text: &str, dimension: &Dimension) -> Result<(), String> {
+    let lowest = dimension.left.min(dimension.right);
+    let highest = dimension.left.max(dimension.right);
+    let mut address = lowest;
+    for (line_idx, token) in tokens(text)? {
+        if let Some(digits) = token.strip_prefix('@') {
+            address = i64::from_str_radix(digits, 16)
+                .map_err(|_| format!("line {line_idx}: invalid address \"{token}\""))?;
+            if address < lowest || address > highest {
+                return Err(format!(
+                    "line {line_idx}: address {token} is outside [{lowest}:{highest}]"
+                ));
+            }
+            continue;
+        }
+        if !token
+            .chars()
+            .all(|c| c.is_ascii_hexdigit() || "xXzZ?_".contains(c))
+        {
+            return Err(format!("line {line_idx}: invalid word \"{token}\""));
+        }
+        if address > highest {
+            return Err(format!(
+                "line {line_idx}: more words than the {} elements of the memory",
+                dimension.size()
+            ));
+        }
+        address += 1;
+    }
+    Ok(())
+}
+
+/// Whitespace separated tokens with `//` and `/* */` comments removed, paired with their
+/// 1-based line number.
+fn tokens(text: &str) -> Result<Vec<(usize, &str)>, String> {
+    let mut tokens = vec![];
+    let mut in_block_comment = false;
+    for (line_idx, line) in text.lines().enumerate() {
+        let mut rest = line;
+        while !rest.is_empty() {
+            if in_block_comment {
+                match rest.find("*/") {
+                    Some(end) => {
+                        rest = &rest[end + 2..];
+                        in_block_comment = false;
+                    }
+                    None => rest = "",
+                }
+                continue;
+            }
+            let code_end = [rest.find("//"), rest.find("/*")]
+                .into_iter()
+                .flatten()
+                .min()
+                .unwrap_or(rest.len());
+            tokens.extend(
+                rest[..code_end]
+                    .split_whitespace()
+                    .map(|token| (line_idx + 1, token)),
+            );
+            rest = &rest[code_end..];
+            if rest.starts_with("//") {
+                rest = "";
+            } else if rest.starts_with("/*") {
+                rest = &rest[2..];
+                in_block_comment = true;
+            }
+        }
+    }
+    if in_block_comment {
+        return Err("unterminated block comment".to_string());
+    }
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::parser::Dimension;
+
+    use super::check_hex;
+
+    #[test]
+    fn test_check_hex() {
+        let dimension = Dimension { left: 0, right: 3 };
+        assert!(check_hex("de ad\nbe_ef // last word", &dimension).is_ok());
+        assert!(check_hex("/* header\n more */ @2 0x\n zz", &dimension).is_ok());
+        assert!(check_hex("00 01 02 03 04", &dimension).is_err());
+        assert!(check_hex("@3 00 01", &dimension).is_err());
+        assert!(check_hex("@4", &dimension).is_err());
+        assert!(check_hex("0g", &dimension).is_err());
+        assert!(check_hex("00 /* open", &dimension).is_err());
+
+        let descending = Dimension { left: 11, right: 8 };
+        assert!(check_hex("@8 00 01 02 03", &descending).is_ok());
+        assert!(check_hex("@7 00", &descending).is_err());
+    }
+}
@@ -1,17 +1,73 @@
 mod oombak_parser_sys;
 
-use std::ffi::{c_char, CStr, CString};
+use std::{
+    ffi::{c_char, CStr, CString},
+    fmt::Display,
+    path::PathBuf,
+};
 use thiserror::Error;
 
-use crate::error::{OombakError, OombakResult};
+use crate::{
+    dut::LogicVec,
+    error::{OombakError, OombakResult},
+};
+
+/// Fails with [`Error::Compilation`] if slang reports any error. Warnings alone do not fail, and
+/// are returned together with the root instance.
+pub fn parse(
+    source_paths: &[String],
+    top_module_name: &str,
+) -> OombakResult<(InstanceNode, Vec<Diagnostic>)> {
+    parse_with_options(source_paths, top_module_name, &ParseOptions::default())
+}
 
-pub fn parse(source_paths: &[String], top_module_name: &str) -> OombakResult<InstanceNode> {
+pub fn parse_with_options(
+    source_paths: &[String],
+    top_module_name: &str,
+    options: &ParseOptions,
+) -> OombakResult<(InstanceNode, Vec<Diagnostic>)> {
     let source_paths = CString::new(source_paths.join(":"))?;
     let top_module_name = CString::new(top_module_name)?;
-    let instance_sys = unsafe {
-        oombak_parser_sys::oombak_parser_parse(source_paths.as_ptr(), top_module_name.as_ptr())
+    let include_dirs = c_strings(&options.include_dirs)?;
+    let defines = c_strings(&options.defines)?;
+    let parameters = c_strings(&options.parameters)?;
+    let include_dir_ptrs: Vec<*const c_char> = include_dirs.iter().map(|s| s.as_ptr()).collect();
+    let define_ptrs: Vec<*const c_char> = defines.iter().map(|s| s.as_ptr()).collect();
+    let parameter_ptrs: Vec<*const c_char> = parameters.iter().map(|s| s.as_ptr()).collect();
+    let options = oombak_parser_sys::ParseOptions {
+        include_dirs: include_dir_ptrs.as_ptr(),
+        include_dirs_len: include_dir_ptrs.len() as u64,
+        defines: define_ptrs.as_ptr(),
+        defines_len: define_ptrs.len() as u64,
+        parameters: parameter_ptrs.as_ptr(),
+        parameters_len: parameter_ptrs.len() as u64,
+    };
+    let result_ptr = unsafe {
+        oombak_parser_sys::oombak_parser_parse_with_options(
+            source_paths.as_ptr(),
+            top_module_name.as_ptr(),
+            &options,
+        )
     };
-    InstanceNode::try_from(&instance_sys)
+    if result_ptr.is_null() {
+        return Err(Error::NullDereference.into());
+    }
+    let result = unsafe { &*result_ptr };
+    let diagnostics = diagnostics_ptr_to_vec(result.diagnostics, result.diagnostics_len as usize)?;
+    if result.instance.is_null() {
+        return Err(Error::Compilation(diagnostics).into());
+    }
+    Ok((InstanceNode::try_from(&result.instance)?, diagnostics))
+}
+
+/// Preprocessor and elaboration options, passed to slang as they would be on its command line.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ParseOptions {
+    pub include_dirs: Vec<String>,
+    /// `NAME` or `NAME=VALUE`.
+    pub defines: Vec<String>,
+    /// Overrides of the top module's parameters, `NAME=VALUE`.
+    pub parameters: Vec<String>,
 }
 
 #[derive(Default, Debug, Clone)]
@@ -20,12 +76,65 @@ pub struct InstanceNode {
     pub module_name: String,
     pub children: Vec<InstanceNode>,
     pub signals: Vec<Signal>,
+    /// Where the instance is instantiated, or where the module is declared for the root.
+    pub location: Option<SourceLocation>,
+    /// Whether the module, not counting its children, has timing constructs such as delays.
+    pub uses_timing: bool,
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Signal {
     pub name: String,
     pub signal_type: SignalType,
+    /// Unpacked dimensions from the outermost one, empty unless the signal is an array.
+    pub dimensions: Vec<Dimension>,
+    /// Structure of a single element.
+    pub type_info: TypeInfo,
+    pub location: Option<SourceLocation>,
+    /// Nets, including ports connected to a net, cannot be assigned procedurally.
+    pub is_net: bool,
+}
+
+/// `line` and `column` are 1-based.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SourceLocation {
+    pub file: PathBuf,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Structure of a packed type. A vector of single bits, e.g. `logic [7:0]`, is `Logic`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum TypeInfo {
+    #[default]
+    Logic,
+    Struct(Vec<Field>),
+    Union(Vec<Field>),
+    Enum(Vec<EnumValue>),
+    /// Packed dimensions from the outermost one, and the type of a single element.
+    PackedArray(Vec<Dimension>, Box<TypeInfo>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Field {
+    pub name: String,
+    /// Offset of the least significant bit within the parent.
+    pub lsb: usize,
+    pub width: usize,
+    pub type_info: TypeInfo,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EnumValue {
+    pub name: String,
+    pub value: LogicVec,
+}
+
+/// Bounds of an unpacked dimension as declared, e.g. `[0:1023]` or `[7:0]`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Dimension {
+    pub left: i64,
+    pub right: i64,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -38,12 +147,71 @@ pub enum SignalType {
 pub enum Direction {
     In,
     Out,
+    InOut,
+    Ref,
+}
+
+/// Message reported by slang, Verilator or the C++ compiler, pointing to a source location.
+/// `line` and `column` are 1-based. `line` is 0 if there is no location.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub file: PathBuf,
+    pub line: usize,
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
 }
 
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("null dereference")]
     NullDereference,
+    #[error("{}", first_error(_0))]
+    Compilation(Vec<Diagnostic>),
+    #[error("invalid enum value \"{}\"", _0)]
+    InvalidEnumValue(String),
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Note => write!(f, "note"),
+        }
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.line != 0 {
+            write!(f, "{}:{}:", self.file.to_string_lossy(), self.line)?;
+            if let Some(column) = self.column {
+                write!(f, "{column}:")?;
+            }
+            write!(f, " ")?;
+        }
+        write!(f, "{}: {}", self.severity, self.message)
+    }
+}
+
+fn first_error(diagnostics: &[Diagnostic]) -> String {
+    let errors = diagnostics
+        .iter()
+        .filter(|d| d.severity == Severity::Error)
+        .count();
+    match diagnostics.iter().find(|d| d.severity == Severity::Error) {
+        Some(diagnostic) if errors > 1 => format!("{diagnostic} (and {} more)", errors - 1),
+        Some(diagnostic) => diagnostic.to_string(),
+        None => "compilation failed".to_string(),
+    }
 }
 
 impl From<Error> for OombakError {
@@ -53,6 +221,17 @@ impl From<Error> for OombakError {
 }
 
 impl InstanceNode {
+    /// Instance `name` of module `name` with `signals` and no children, for building a design
+    /// by hand.
+    pub fn new(name: &str, signals: Vec<Signal>) -> Self {
+        Self {
+            name: name.to_string(),
+            module_name: name.to_string(),
+            signals,
+            ..Default::default()
+        }
+    }
+
     pub fn get_signal(&self, name: &str) -> OombakResult<Option<Signal>> {
         if let Some((head, tail)) = name.split_once('.') {
             if self.name != head {
@@ -77,6 +256,11 @@ impl InstanceNode {
     pub fn get_ports(&self) -> impl Iterator<Item = &Signal> {
         self.signals.iter().filter(|s| s.is_port())
     }
+
+    /// Whether this instance or any instance below it uses timing constructs.
+    pub fn tree_uses_timing(&self) -> bool {
+        self.uses_timing || self.children.iter().any(InstanceNode::tree_uses_timing)
+    }
 }
 
 impl TryFrom<&*const oombak_parser_sys::Instance> for InstanceNode {
@@ -91,11 +275,14 @@ impl TryFrom<&*const oombak_parser_sys::Instance> for InstanceNode {
             instance.child_instances,
             instance.child_instances_len as usize,
         )?;
+        let location = location_from(&instance.location)?;
         Ok(InstanceNode {
             name,
             module_name,
             signals,
             children,
+            location,
+            uses_timing: instance.uses_timing,
         })
     }
 }
@@ -106,6 +293,8 @@ impl TryFrom<&oombak_parser_sys::Signal> for Signal {
     fn try_from(value: &oombak_parser_sys::Signal) -> Result<Self, Self::Error> {
         let name = string_from_ptr(value.name)?;
         let width = value.width as usize;
+        let dimensions = dimensions_ptr_to_vec(value.dimensions, value.dimensions_len as usize)?;
+        let type_info = type_info_from_ptr(value.type_info)?;
         let signal_type = match value.signal_type {
             oombak_parser_sys::SignalType::UnpackedArrPortIn => {
                 SignalType::UnpackedArrPort(Direction::In, width)
@@ -116,12 +305,39 @@ impl TryFrom<&oombak_parser_sys::Signal> for Signal {
             oombak_parser_sys::SignalType::UnpackedArrVarNet => {
                 SignalType::UnpackedArrNetVar(width)
             }
+            oombak_parser_sys::SignalType::UnpackedArrPortInOut => {
+                SignalType::UnpackedArrPort(Direction::InOut, width)
+            }
+            oombak_parser_sys::SignalType::UnpackedArrPortRef => {
+                SignalType::UnpackedArrPort(Direction::Ref, width)
+            }
         };
-        Ok(Signal { name, signal_type })
+        let location = location_from(&value.location)?;
+        Ok(Signal {
+            name,
+            signal_type,
+            dimensions,
+            type_info,
+            location,
+            is_net: value.is_net,
+        })
     }
 }
 
 impl Signal {
+    /// Scalar `logic` variable or port with no location, for building a design by hand. Arrays
+    /// and nets are built from it with struct update syntax.
+    pub fn new(name: &str, signal_type: SignalType) -> Self {
+        Self {
+            name: name.to_string(),
+            signal_type,
+            dimensions: vec![],
+            type_info: TypeInfo::Logic,
+            location: None,
+            is_net: false,
+        }
+    }
+
     pub fn is_port(&self) -> bool {
         match &self.signal_type {
             SignalType::UnpackedArrPort(_, _) => true,
@@ -136,12 +352,86 @@ impl Signal {
         )
     }
 
+    pub fn is_inout_port(&self) -> bool {
+        matches!(
+            &self.signal_type,
+            SignalType::UnpackedArrPort(Direction::InOut, _)
+        )
+    }
+
+    pub fn direction(&self) -> Option<&Direction> {
+        match &self.signal_type {
+            SignalType::UnpackedArrPort(direction, _) => Some(direction),
+            SignalType::UnpackedArrNetVar(_) => None,
+        }
+    }
+
     pub fn bit_width(&self) -> usize {
         match &self.signal_type {
             SignalType::UnpackedArrPort(_, bit_width) => *bit_width,
             SignalType::UnpackedArrNetVar(bit_width) => *bit_width,
         }
     }
+
+    pub fn is_array(&self) -> bool {
+        !self.dimensions.is_empty()
+    }
+
+    pub fn num_of_elements(&self) -> usize {
+        self.dimensions.iter().map(Dimension::size).product()
+    }
+
+    /// Width of a single element, which is the whole signal if it is not an array.
+    pub fn element_width(&self) -> usize {
+        self.bit_width() / self.num_of_elements()
+    }
+}
+
+impl TypeInfo {
+    pub fn fields(&self) -> &[Field] {
+        match self {
+            TypeInfo::Struct(fields) | TypeInfo::Union(fields) => fields,
+            _ => &[],
+        }
+    }
+
+    /// Name of the enum value equal to `value`.
+    pub fn enum_name_of(&self, value: &LogicVec) -> Option<&str> {
+        match self {
+            TypeInfo::Enum(values) => values
+                .iter()
+                .find(|v| &v.value == value)
+                .map(|v| v.name.as_str()),
+            _ => None,
+        }
+    }
+}
+
+impl Dimension {
+    /// Number of elements, which is never zero.
+    pub fn size(&self) -> usize {
+        self.left.abs_diff(self.right) as usize + 1
+    }
+
+    pub fn contains(&self, idx: i64) -> bool {
+        (self.left.min(self.right)..=self.left.max(self.right)).contains(&idx)
+    }
+}
+
+impl From<&oombak_parser_sys::Dimension> for Dimension {
+    fn from(value: &oombak_parser_sys::Dimension) -> Self {
+        Dimension {
+            left: value.left,
+            right: value.right,
+        }
+    }
+}
+
+fn c_strings(strings: &[String]) -> OombakResult<Vec<CString>> {
+    Ok(strings
+        .iter()
+        .map(|s| CString::new(s.as_str()))
+        .collect::<Result<_, _>>()?)
 }
 
 fn string_from_ptr(ptr: *const c_char) -> OombakResult<String> {
@@ -151,6 +441,20 @@ fn string_from_ptr(ptr: *const c_char) -> OombakResult<String> {
     Ok(unsafe { CStr::from_ptr(ptr) }.to_str()?.to_string())
 }
 
+/// An unknown location, or one left unset by the parser, is `None`.
+fn location_from(
+    location: &oombak_parser_sys::SourceLocation,
+) -> OombakResult<Option<SourceLocation>> {
+    if location.file.is_null() || location.line == 0 {
+        return Ok(None);
+    }
+    Ok(Some(SourceLocation {
+        file: PathBuf::from(string_from_ptr(location.file)?),
+        line: location.line as usize,
+        column: location.column as usize,
+    }))
+}
+
 unsafe fn deref_instance_ptr(
     ptr: &*const oombak_parser_sys::Instance,
 ) -> OombakResult<oombak_parser_sys::Instance> {
@@ -173,6 +477,131 @@ fn signals_ptr_to_vec(
         .collect()
 }
 
+fn dimensions_ptr_to_vec(
+    dimensions: *const oombak_parser_sys::Dimension,
+    dimensions_len: usize,
+) -> OombakResult<Vec<Dimension>> {
+    if dimensions_len == 0 {
+        return Ok(vec![]);
+    }
+    if dimensions.is_null() {
+        return Err(Error::NullDereference.into());
+    }
+    Ok(
+        unsafe { std::slice::from_raw_parts(dimensions, dimensions_len) }
+            .iter()
+            .map(Dimension::from)
+            .collect(),
+    )
+}
+
+fn diagnostics_ptr_to_vec(
+    diagnostics: *const oombak_parser_sys::Diagnostic,
+    diagnostics_len: usize,
+) -> OombakResult<Vec<Diagnostic>> {
+    if diagnostics_len == 0 {
+        return Ok(vec![]);
+    }
+    if diagnostics.is_null() {
+        return Err(Error::NullDereference.into());
+    }
+    unsafe { std::slice::from_raw_parts(diagnostics, diagnostics_len) }
+        .iter()
+        .map(|diagnostic| {
+            let severity = match diagnostic.severity {
+                oombak_parser_sys::DiagnosticSeverity::SeverityError => Severity::Error,
+                oombak_parser_sys::DiagnosticSeverity::SeverityWarning => Severity::Warning,
+                oombak_parser_sys::DiagnosticSeverity::SeverityNote => Severity::Note,
+            };
+            Ok(Diagnostic {
+                severity,
+                file: PathBuf::from(string_from_ptr(diagnostic.file)?),
+                line: diagnostic.line as usize,
+                column: (diagnostic.column > 0).then_some(diagnostic.column as usize),
+                message: string_from_ptr(diagnostic.message)?,
+            })
+        })
+        .collect()
+}
+
+/// A null pointer is read as `TypeInfo::Logic`, for parsers which do not describe types.
+fn type_info_from_ptr(ptr: *const oombak_parser_sys::TypeInfo) -> OombakResult<TypeInfo> {
+    if ptr.is_null() {
+        return Ok(TypeInfo::Logic);
+    }
+    let type_info = unsafe { &*ptr };
+    match type_info.kind {
+        oombak_parser_sys::TypeKind::TypeLogic => Ok(TypeInfo::Logic),
+        oombak_parser_sys::TypeKind::TypePackedStruct => Ok(TypeInfo::Struct(fields_ptr_to_vec(
+            type_info.fields,
+            type_info.fields_len as usize,
+        )?)),
+        oombak_parser_sys::TypeKind::TypePackedUnion => Ok(TypeInfo::Union(fields_ptr_to_vec(
+            type_info.fields,
+            type_info.fields_len as usize,
+        )?)),
+        oombak_parser_sys::TypeKind::TypeEnum => Ok(TypeInfo::Enum(enum_values_ptr_to_vec(
+            type_info.enum_values,
+            type_info.enum_values_len as usize,
+        )?)),
+        oombak_parser_sys::TypeKind::TypePackedArray => Ok(TypeInfo::PackedArray(
+            dimensions_ptr_to_vec(
+                type_info.packed_dimensions,
+                type_info.packed_dimensions_len as usize,
+            )?,
+            Box::new(type_info_from_ptr(type_info.element_type)?),
+        )),
+    }
+}
+
+fn fields_ptr_to_vec(
+    fields: *const oombak_parser_sys::Field,
+    fields_len: usize,
+) -> OombakResult<Vec<Field>> {
+    if fields_len == 0 {
+        return Ok(vec![]);
+    }
+    if fields.is_null() {
+        return Err(Error::NullDereference.into());
+    }
+    unsafe { std::slice::from_raw_parts(fields, fields_len) }
+        .iter()
+        .map(|field| {
+            Ok(Field {
+                name: string_from_ptr(field.name)?,
+                lsb: field.lsb as usize,
+                width: field.width as usize,
+                type_info: type_info_from_ptr(field.type_info)?,
+            })
+        })
+        .collect()
+}
+
+fn enum_values_ptr_to_vec(
+    values: *const oombak_parser_sys::EnumValue,
+    values_len: usize,
+) -> OombakResult<Vec<EnumValue>> {
+    if values_len == 0 {
+        return Ok(vec![]);
+    }
+    if values.is_null() {
+        return Err(Error::NullDereference.into());
+    }
+    unsafe { std::slice::from_raw_parts(values, values_len) }
+        .iter()
+        .map(|value| {
+            let bits = string_from_ptr(value.value)?;
+            let Some(logic_vec) = LogicVec::from_ascii(bits.as_bytes()) else {
+                return Err(Error::InvalidEnumValue(bits).into());
+            };
+            Ok(EnumValue {
+                name: string_from_ptr(value.name)?,
+                value: logic_vec,
+            })
+        })
+        .collect()
+}
+
 fn child_instances_ptr_to_vec(
     child_instances: *const *const oombak_parser_sys::Instance,
     child_instances_len: usize,
@@ -190,31 +619,55 @@ fn child_instances_ptr_to_vec(
 mod test {
     use crate::parser::Direction;
 
-    use super::{oombak_parser_sys::Instance, parse, InstanceNode, Signal, SignalType};
+    use std::{ffi::CString, path::PathBuf};
+
+    use crate::dut::LogicVec;
+
+    use super::{
+        location_from, oombak_parser_sys, oombak_parser_sys::Instance, parse, Diagnostic,
+        Dimension, EnumValue, Error, Field, InstanceNode, Severity, Signal, SignalType,
+        SourceLocation, TypeInfo,
+    };
 
     #[test]
     fn test_get_signal() {
-        let mut root = InstanceNode::default();
-        root.name = "root".to_string();
+        let mut root = InstanceNode {
+            name: "root".to_string(),
+            ..Default::default()
+        };
 
-        let mut child_0 = InstanceNode::default();
-        child_0.name = "child_0".to_string();
+        let child_0 = InstanceNode {
+            name: "child_0".to_string(),
+            ..Default::default()
+        };
 
-        let mut child_1 = InstanceNode::default();
-        child_1.name = "child_1".to_string();
+        let mut child_1 = InstanceNode {
+            name: "child_1".to_string(),
+            ..Default::default()
+        };
 
-        let mut child_2 = InstanceNode::default();
-        child_2.name = "child_2".to_string();
-        child_2.signals = vec![
-            Signal {
-                name: "sig_0".to_string(),
-                signal_type: SignalType::UnpackedArrNetVar(1),
-            },
-            Signal {
-                name: "sig_1".to_string(),
-                signal_type: SignalType::UnpackedArrNetVar(1),
-            },
-        ];
+        let child_2 = InstanceNode {
+            name: "child_2".to_string(),
+            signals: vec![
+                Signal {
+                    name: "sig_0".to_string(),
+                    signal_type: SignalType::UnpackedArrNetVar(1),
+                    dimensions: vec![],
+                    type_info: TypeInfo::Logic,
+                    location: None,
+                    is_net: false,
+                },
+                Signal {
+                    name: "sig_1".to_string(),
+                    signal_type: SignalType::UnpackedArrNetVar(1),
+                    dimensions: vec![],
+                    type_info: TypeInfo::Logic,
+                    location: None,
+                    is_net: false,
+                },
+            ],
+            ..Default::default()
+        };
 
         child_1.children.push(child_2);
         root.children = vec![child_0, child_1];
@@ -225,36 +678,75 @@ mod test {
             .is_some())
     }
 
+    #[test]
+    fn test_tree_uses_timing() {
+        let mut root = InstanceNode {
+            children: vec![InstanceNode::default(), InstanceNode::default()],
+            ..Default::default()
+        };
+        assert!(!root.tree_uses_timing());
+        root.children[1].uses_timing = true;
+        assert!(root.tree_uses_timing());
+        assert!(!root.children[0].tree_uses_timing());
+    }
+
     #[test]
     fn test_parse() {
         let source_paths = [
             "/home/fuad1502/code/oombak_parser/tests/fixtures/sv_sample_1/sample.sv".to_string(),
             "/home/fuad1502/code/oombak_parser/tests/fixtures/sv_sample_1/adder.sv".to_string(),
         ];
-        let root = parse(&source_paths, "sample").unwrap();
+        let (root, _) = parse(&source_paths, "sample").unwrap();
+        let location = |file: usize, line, column| {
+            Some(SourceLocation {
+                file: PathBuf::from(&source_paths[file]),
+                line,
+                column,
+            })
+        };
         assert_eq!(root.name, "sample");
         assert_eq!(root.module_name, "sample");
 
         assert_eq!(root.signals.len(), 5);
         assert!(root.signals.contains(&Signal {
             name: "clk".to_string(),
-            signal_type: SignalType::UnpackedArrPort(Direction::In, 1)
+            signal_type: SignalType::UnpackedArrPort(Direction::In, 1),
+            dimensions: vec![],
+            type_info: TypeInfo::Logic,
+            location: location(0, 4, 17),
+            is_net: true,
         }));
         assert!(root.signals.contains(&Signal {
             name: "rst_n".to_string(),
-            signal_type: SignalType::UnpackedArrPort(Direction::In, 1)
+            signal_type: SignalType::UnpackedArrPort(Direction::In, 1),
+            dimensions: vec![],
+            type_info: TypeInfo::Logic,
+            location: location(0, 5, 17),
+            is_net: true,
         }));
         assert!(root.signals.contains(&Signal {
             name: "in".to_string(),
-            signal_type: SignalType::UnpackedArrPort(Direction::In, 6)
+            signal_type: SignalType::UnpackedArrPort(Direction::In, 6),
+            dimensions: vec![],
+            type_info: TypeInfo::Logic,
+            location: location(0, 6, 30),
+            is_net: true,
         }));
         assert!(root.signals.contains(&Signal {
             name: "out".to_string(),
-            signal_type: SignalType::UnpackedArrPort(Direction::Out, 6)
+            signal_type: SignalType::UnpackedArrPort(Direction::Out, 6),
+            dimensions: vec![],
+            type_info: TypeInfo::Logic,
+            location: location(0, 7, 31),
+            is_net: false,
         }));
         assert!(root.signals.contains(&Signal {
             name: "c".to_string(),
-            signal_type: SignalType::UnpackedArrNetVar(6)
+            signal_type: SignalType::UnpackedArrNetVar(6),
+            dimensions: vec![],
+            type_info: TypeInfo::Logic,
+            location: location(0, 9, 21),
+            is_net: true,
         }));
 
         assert_eq!(root.children.len(), 1);
@@ -266,26 +758,160 @@ mod test {
         assert_eq!(child.signals.len(), 4);
         assert!(child.signals.contains(&Signal {
             name: "a".to_string(),
-            signal_type: SignalType::UnpackedArrPort(Direction::In, 6)
+            signal_type: SignalType::UnpackedArrPort(Direction::In, 6),
+            dimensions: vec![],
+            type_info: TypeInfo::Logic,
+            location: location(1, 4, 29),
+            is_net: true,
         }));
         assert!(child.signals.contains(&Signal {
             name: "b".to_string(),
-            signal_type: SignalType::UnpackedArrPort(Direction::In, 6)
+            signal_type: SignalType::UnpackedArrPort(Direction::In, 6),
+            dimensions: vec![],
+            type_info: TypeInfo::Logic,
+            location: location(1, 5, 29),
+            is_net: true,
         }));
         assert!(child.signals.contains(&Signal {
             name: "c".to_string(),
-            signal_type: SignalType::UnpackedArrPort(Direction::Out, 6)
+            signal_type: SignalType::UnpackedArrPort(Direction::Out, 6),
+            dimensions: vec![],
+            type_info: TypeInfo::Logic,
+            location: location(1, 6, 29),
+            is_net: false,
         }));
         assert!(child.signals.contains(&Signal {
             name: "d".to_string(),
-            signal_type: SignalType::UnpackedArrNetVar(1)
+            signal_type: SignalType::UnpackedArrNetVar(1),
+            dimensions: vec![],
+            type_info: TypeInfo::Logic,
+            location: location(1, 9, 6),
+            is_net: true,
         }));
     }
 
+    #[test]
+    fn test_array_signal() {
+        let signal = Signal {
+            name: "mem".to_string(),
+            signal_type: SignalType::UnpackedArrNetVar(32 * 8 * 2),
+            dimensions: vec![
+                Dimension { left: 7, right: 0 },
+                Dimension { left: 0, right: 1 },
+            ],
+            type_info: TypeInfo::Logic,
+            location: None,
+            is_net: false,
+        };
+        assert!(signal.is_array());
+        assert_eq!(signal.num_of_elements(), 16);
+        assert_eq!(signal.element_width(), 32);
+        assert!(signal.dimensions[0].contains(0));
+        assert!(signal.dimensions[0].contains(7));
+        assert!(!signal.dimensions[1].contains(2));
+        assert!(!signal.dimensions[1].contains(-1));
+    }
+
+    #[test]
+    fn test_type_info() {
+        let state = TypeInfo::Enum(vec![
+            EnumValue {
+                name: "IDLE".to_string(),
+                value: LogicVec::from_ascii(b"00").unwrap(),
+            },
+            EnumValue {
+                name: "BUSY".to_string(),
+                value: LogicVec::from_ascii(b"01").unwrap(),
+            },
+        ]);
+        assert_eq!(
+            state.enum_name_of(&LogicVec::from_ascii(b"01").unwrap()),
+            Some("BUSY")
+        );
+        assert_eq!(
+            state.enum_name_of(&LogicVec::from_ascii(b"1x").unwrap()),
+            None
+        );
+        assert!(state.fields().is_empty());
+
+        let entry = TypeInfo::Struct(vec![
+            Field {
+                name: "tag".to_string(),
+                lsb: 2,
+                width: 4,
+                type_info: TypeInfo::Logic,
+            },
+            Field {
+                name: "state".to_string(),
+                lsb: 0,
+                width: 2,
+                type_info: state,
+            },
+        ]);
+        assert_eq!(entry.fields().len(), 2);
+        assert_eq!(
+            entry.fields()[1]
+                .type_info
+                .enum_name_of(&LogicVec::from_ascii(b"00").unwrap()),
+            Some("IDLE")
+        );
+    }
+
     #[test]
     fn test_null() {
-        let ptr = 0 as *const Instance;
+        let ptr = std::ptr::null::<Instance>();
         let e = InstanceNode::try_from(&ptr).unwrap_err();
         assert_eq!(&e.to_string(), "oombak_rs: parse: null dereference");
     }
+
+    #[test]
+    fn test_compilation_error() {
+        let diagnostic = |severity, line| Diagnostic {
+            severity,
+            file: PathBuf::from("sample.sv"),
+            line,
+            column: Some(3),
+            message: "something".to_string(),
+        };
+        let e = Error::Compilation(vec![
+            diagnostic(Severity::Warning, 2),
+            diagnostic(Severity::Error, 5),
+            diagnostic(Severity::Error, 7),
+        ]);
+        assert_eq!(
+            &e.to_string(),
+            "sample.sv:5:3: error: something (and 1 more)"
+        );
+        let e = Error::Compilation(vec![diagnostic(Severity::Note, 0)]);
+        assert_eq!(&e.to_string(), "compilation failed");
+    }
+
+    #[test]
+    fn test_location() {
+        let file = CString::new("sample.sv").unwrap();
+        let location = oombak_parser_sys::SourceLocation {
+            file: file.as_ptr(),
+            line: 4,
+            column: 17,
+        };
+        assert_eq!(
+            location_from(&location).unwrap(),
+            Some(SourceLocation {
+                file: PathBuf::from("sample.sv"),
+                line: 4,
+                column: 17,
+            })
+        );
+        let unknown = oombak_parser_sys::SourceLocation {
+            line: 0,
+            column: 0,
+            ..location
+        };
+        assert_eq!(location_from(&unknown).unwrap(), None);
+        let unset = oombak_parser_sys::SourceLocation {
+            file: std::ptr::null(),
+            ..location
+        };
+        assert_eq!(location_from(&unset).unwrap(), None);
+    }
 }
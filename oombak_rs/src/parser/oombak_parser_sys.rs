@@ -2,10 +2,46 @@ use std::ffi::c_char;
 
 #[link(name = "oombak_parser")]
 extern "C" {
-    pub fn oombak_parser_parse(
+    pub fn oombak_parser_parse_with_options(
         source_paths: *const c_char,
         top_module_name: *const c_char,
-    ) -> *const Instance;
+        options: *const ParseOptions,
+    ) -> *const ParseResult;
+}
+
+#[repr(C)]
+pub struct ParseOptions {
+    pub include_dirs: *const *const c_char,
+    pub include_dirs_len: u64,
+    pub defines: *const *const c_char,
+    pub defines_len: u64,
+    pub parameters: *const *const c_char,
+    pub parameters_len: u64,
+}
+
+#[repr(C)]
+pub struct ParseResult {
+    pub instance: *const Instance,
+    pub diagnostics: *const Diagnostic,
+    pub diagnostics_len: u64,
+}
+
+#[repr(C)]
+pub struct Diagnostic {
+    pub severity: DiagnosticSeverity,
+    pub file: *const c_char,
+    pub line: u64,
+    pub column: u64,
+    pub message: *const c_char,
+}
+
+/// Only ever constructed by the parser.
+#[repr(C)]
+#[allow(clippy::enum_variant_names, dead_code)]
+pub enum DiagnosticSeverity {
+    SeverityError,
+    SeverityWarning,
+    SeverityNote,
 }
 
 #[repr(C)]
@@ -18,6 +54,8 @@ pub struct Instance {
     pub child_instances_len: u64,
     pub signals: *const Signal,
     pub signals_len: u64,
+    pub location: SourceLocation,
+    pub uses_timing: bool,
 }
 
 #[repr(C)]
@@ -25,6 +63,61 @@ pub struct Signal {
     pub name: *const c_char,
     pub signal_type: SignalType,
     pub width: u64,
+    pub dimensions: *const Dimension,
+    pub dimensions_len: u64,
+    pub type_info: *const TypeInfo,
+    pub location: SourceLocation,
+    pub is_net: bool,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct SourceLocation {
+    pub file: *const c_char,
+    pub line: u64,
+    pub column: u64,
+}
+
+#[repr(C)]
+pub struct Dimension {
+    pub left: i64,
+    pub right: i64,
+}
+
+#[repr(C)]
+pub struct TypeInfo {
+    pub kind: TypeKind,
+    pub fields: *const Field,
+    pub fields_len: u64,
+    pub enum_values: *const EnumValue,
+    pub enum_values_len: u64,
+    pub packed_dimensions: *const Dimension,
+    pub packed_dimensions_len: u64,
+    pub element_type: *const TypeInfo,
+}
+
+#[repr(C)]
+pub struct Field {
+    pub name: *const c_char,
+    pub lsb: u64,
+    pub width: u64,
+    pub type_info: *const TypeInfo,
+}
+
+#[repr(C)]
+pub struct EnumValue {
+    pub name: *const c_char,
+    pub value: *const c_char,
+}
+
+#[repr(C)]
+#[allow(clippy::enum_variant_names)]
+pub enum TypeKind {
+    TypeLogic,
+    TypePackedStruct,
+    TypePackedUnion,
+    TypeEnum,
+    TypePackedArray,
 }
 
 #[repr(C)]
@@ -33,4 +126,6 @@ pub enum SignalType {
     UnpackedArrPortIn,
     UnpackedArrPortOut,
     UnpackedArrVarNet,
+    UnpackedArrPortInOut,
+    UnpackedArrPortRef,
 }
@@ -2,27 +2,40 @@ use thiserror::Error;
 
 use crate::{
     error::{OombakError, OombakResult},
-    parser::{self, InstanceNode, Signal},
+    parser::{
+        self, Diagnostic, Dimension, Direction, InstanceNode, ParseOptions, Signal, TypeInfo,
+    },
 };
 
+#[derive(Clone)]
 pub struct Probe {
     root_node: InstanceNode,
     points: Vec<ProbePoint>,
     top_level_ports: Vec<ProbePoint>,
     top_level_module_name: String,
+    /// Warnings and notes reported while parsing the design.
+    diagnostics: Vec<Diagnostic>,
 }
 
 #[derive(Clone)]
 pub struct ProbePoint {
     path: String,
     signal: parser::Signal,
+    /// Top-level inputs, and ref ports which are driven the same way.
     is_top_level_input: bool,
+    is_top_level_inout: bool,
+    /// Points added with [`Probe::add_signal_to_probe`], as opposed to top-level ports.
+    is_internal: bool,
 }
 
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("signal '{}' not available", _0)]
     UnknownSignal(String),
+    #[error("signal '{}' is not probed", _0)]
+    NotProbed(String),
+    #[error("signal '{}' is a top-level port and must be removed explicitly", _0)]
+    ProtectedTopLevelPort(String),
 }
 
 impl From<Error> for OombakError {
@@ -32,8 +45,20 @@ impl From<Error> for OombakError {
 }
 
 impl Probe {
-    pub fn try_from(source_paths: &[String], top_module_name: &str) -> OombakResult<Self> {
-        let root_node = parser::parse(source_paths, top_module_name)?;
+    pub fn try_from(
+        source_paths: &[String],
+        top_module_name: &str,
+        options: &ParseOptions,
+    ) -> OombakResult<Self> {
+        let (root_node, diagnostics) =
+            parser::parse_with_options(source_paths, top_module_name, options)?;
+        Ok(Self {
+            diagnostics,
+            ..Self::from_root_node(root_node)?
+        })
+    }
+
+    pub fn from_root_node(root_node: InstanceNode) -> OombakResult<Self> {
         let points = Self::create_top_level_points(&root_node)?;
         let top_level_ports = points.clone();
         let top_level_module_name = root_node.module_name.clone();
@@ -42,6 +67,7 @@ impl Probe {
             points,
             top_level_ports,
             top_level_module_name,
+            diagnostics: vec![],
         })
     }
 
@@ -54,39 +80,72 @@ impl Probe {
     }
 
     pub fn get_settable_points(&self) -> impl Iterator<Item = &ProbePoint> {
-        self.points.iter().filter(|p| p.is_top_level_input)
+        self.points.iter().filter(|p| p.is_settable())
     }
 
     pub fn get_gettable_points(&self) -> impl Iterator<Item = &ProbePoint> {
-        self.points.iter().filter(|_| true)
+        self.points.iter().filter(|p| p.is_gettable())
     }
 
     pub fn get_multibit_settable_points(&self) -> impl Iterator<Item = &ProbePoint> {
-        self.points
-            .iter()
-            .filter(|p| p.is_top_level_input && p.bit_width() > 1)
+        self.get_settable_points().filter(|p| p.bit_width() > 1)
     }
 
     pub fn get_multibit_gettable_points(&self) -> impl Iterator<Item = &ProbePoint> {
-        self.points.iter().filter(|p| p.bit_width() > 1)
+        self.get_gettable_points().filter(|p| p.bit_width() > 1)
     }
 
     pub fn get_single_bit_settable_points(&self) -> impl Iterator<Item = &ProbePoint> {
-        self.points
-            .iter()
-            .filter(|p| p.is_top_level_input && p.bit_width() == 1)
+        self.get_settable_points().filter(|p| p.bit_width() == 1)
     }
 
     pub fn get_single_bit_gettable_points(&self) -> impl Iterator<Item = &ProbePoint> {
-        self.points.iter().filter(|p| p.bit_width() == 1)
+        self.get_gettable_points().filter(|p| p.bit_width() == 1)
+    }
+
+    pub fn get_array_points(&self) -> impl Iterator<Item = &ProbePoint> {
+        self.points.iter().filter(|p| p.is_array())
+    }
+
+    pub fn get_element_settable_points(&self) -> impl Iterator<Item = &ProbePoint> {
+        self.points.iter().filter(|p| p.is_element_settable())
+    }
+
+    /// Arrays which `$readmemh` and `$writememh` can load and dump, i.e. memories with a single
+    /// unpacked dimension.
+    pub fn get_memory_points(&self) -> impl Iterator<Item = &ProbePoint> {
+        self.get_array_points()
+            .filter(|p| p.dimensions().len() == 1)
+    }
+
+    pub fn get_inout_points(&self) -> impl Iterator<Item = &ProbePoint> {
+        self.get_settable_points()
+            .filter(|p| p.is_top_level_inout())
+    }
+
+    pub fn get_forceable_points(&self) -> impl Iterator<Item = &ProbePoint> {
+        self.points.iter().filter(|p| p.is_forceable())
+    }
+
+    pub fn get_multibit_forceable_points(&self) -> impl Iterator<Item = &ProbePoint> {
+        self.get_forceable_points().filter(|p| p.bit_width() > 1)
+    }
+
+    pub fn get_single_bit_forceable_points(&self) -> impl Iterator<Item = &ProbePoint> {
+        self.get_forceable_points().filter(|p| p.bit_width() == 1)
     }
 
     pub fn add_signal_to_probe(&mut self, path: &str) -> OombakResult<()> {
+        if self.points.iter().any(|p| p.path == path) {
+            return Ok(());
+        }
         if let Ok(Some(signal)) = self.root_node.get_signal(path) {
             let probe_point = ProbePoint {
                 path: path.to_string(),
                 signal,
                 is_top_level_input: false,
+                is_top_level_inout: false,
+                is_internal: true,
             };
             self.points.push(probe_point);
             Ok(())
@@ -95,6 +154,28 @@ impl Probe {
         }
     }
 
+    /// Removes a probed point. Top-level ports are refused, use
+    /// [`Probe::remove_top_level_port_from_probe`] to remove them.
+    pub fn remove_signal_from_probe(&mut self, path: &str) -> OombakResult<()> {
+        if self.is_top_level_port(path) {
+            return Err(Error::ProtectedTopLevelPort(path.to_string()).into());
+        }
+        self.remove_probed_point(path)
+    }
+
+    /// Removes a top-level port from the probed points. The port stays connected to the DUT, but
+    /// can no longer be read or driven.
+    pub fn remove_top_level_port_from_probe(&mut self, path: &str) -> OombakResult<()> {
+        if !self.is_top_level_port(path) {
+            return Err(Error::UnknownSignal(path.to_string()).into());
+        }
+        self.remove_probed_point(path)
+    }
+
+    pub fn is_top_level_port(&self, path: &str) -> bool {
+        self.top_level_ports.iter().any(|p| p.path == path)
+    }
+
     pub fn top_level_module_name(&self) -> &str {
         &self.top_level_module_name
     }
@@ -103,6 +184,20 @@ impl Probe {
         &self.root_node
     }
 
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    fn remove_probed_point(&mut self, path: &str) -> OombakResult<()> {
+        match self.points.iter().position(|p| p.path == path) {
+            Some(idx) => {
+                self.points.remove(idx);
+                Ok(())
+            }
+            None => Err(Error::NotProbed(path.to_string()).into()),
+        }
+    }
+
     fn create_top_level_points(root_node: &InstanceNode) -> OombakResult<Vec<ProbePoint>> {
         root_node
             .get_ports()
@@ -116,12 +211,20 @@ impl Probe {
                 let signal = Signal {
                     name: path.clone(),
                     signal_type: s.signal_type.clone(),
+                    dimensions: s.dimensions.clone(),
+                    type_info: s.type_info.clone(),
+                    location: s.location.clone(),
+                    is_net: s.is_net,
                 };
-                let is_top_level_input = signal.is_input_port();
+                let is_top_level_input =
+                    matches!(signal.direction(), Some(Direction::In | Direction::Ref));
+                let is_top_level_inout = signal.is_inout_port();
                 Ok(ProbePoint {
                     path,
                     signal,
                     is_top_level_input,
+                    is_top_level_inout,
+                    is_internal: false,
                 })
             })
             .collect()
@@ -141,11 +244,155 @@ impl ProbePoint {
         self.signal.bit_width()
     }
 
+    pub fn dimensions(&self) -> &[Dimension] {
+        &self.signal.dimensions
+    }
+
+    pub fn element_width(&self) -> usize {
+        self.signal.element_width()
+    }
+
+    pub fn type_info(&self) -> &TypeInfo {
+        &self.signal.type_info
+    }
+
+    /// Arrays are only accessed element by element.
     pub fn is_gettable(&self) -> bool {
-        true
+        !self.is_array()
     }
 
+    /// Top-level inouts are set through a driver which `release` turns off again.
     pub fn is_settable(&self) -> bool {
-        self.is_top_level_input
+        (self.is_top_level_input || self.is_top_level_inout) && !self.is_array()
+    }
+
+    /// Top-level inputs and inouts are driven through `set` instead.
+    pub fn is_forceable(&self) -> bool {
+        !self.is_top_level_input && !self.is_top_level_inout && !self.is_array()
+    }
+
+    pub fn is_top_level_inout(&self) -> bool {
+        self.is_top_level_inout
+    }
+
+    pub fn is_array(&self) -> bool {
+        self.signal.is_array()
+    }
+
+    /// Elements of top-level outputs are driven by the DUT, and nets cannot be written
+    /// procedurally, so only top-level inputs and internal variables are written.
+    pub fn is_element_settable(&self) -> bool {
+        self.is_array() && (self.is_top_level_input || (self.is_internal && !self.signal.is_net))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::parser::{Dimension, Direction, InstanceNode, Signal, SignalType};
+
+    use super::{Probe, ProbePoint};
+
+    fn new_probe() -> Probe {
+        let root_node = InstanceNode::new(
+            "sample",
+            vec![
+                Signal::new("clk", SignalType::UnpackedArrPort(Direction::In, 1)),
+                Signal::new("c", SignalType::UnpackedArrNetVar(6)),
+            ],
+        );
+        Probe::from_root_node(root_node).unwrap()
+    }
+
+    fn probed_paths(probe: &Probe) -> Vec<&str> {
+        probe.get_probed_points().iter().map(|p| p.path()).collect()
+    }
+
+    #[test]
+    fn test_add_remove_signal() {
+        let mut probe = new_probe();
+        probe.add_signal_to_probe("sample.c").unwrap();
+        probe.add_signal_to_probe("sample.c").unwrap();
+        assert_eq!(probed_paths(&probe), vec!["clk", "sample.c"]);
+
+        probe.remove_signal_from_probe("sample.c").unwrap();
+        assert_eq!(probed_paths(&probe), vec!["clk"]);
+        assert!(probe.remove_signal_from_probe("sample.c").is_err());
+    }
+
+    #[test]
+    fn test_remove_top_level_port() {
+        let mut probe = new_probe();
+        assert!(probe.remove_signal_from_probe("clk").is_err());
+        assert!(probe.remove_top_level_port_from_probe("sample.c").is_err());
+        probe.remove_top_level_port_from_probe("clk").unwrap();
+        assert!(probed_paths(&probe).is_empty());
+        assert_eq!(probe.get_top_level_ports().len(), 1);
+    }
+
+    #[test]
+    fn test_array_points() {
+        let root_node = InstanceNode::new(
+            "sample",
+            vec![
+                Signal::new("clk", SignalType::UnpackedArrPort(Direction::In, 1)),
+                Signal {
+                    dimensions: vec![Dimension { left: 0, right: 3 }],
+                    ..Signal::new("mem", SignalType::UnpackedArrNetVar(8 * 4))
+                },
+                Signal {
+                    dimensions: vec![Dimension { left: 0, right: 1 }],
+                    is_net: true,
+                    ..Signal::new("bus", SignalType::UnpackedArrNetVar(8 * 2))
+                },
+                Signal {
+                    dimensions: vec![
+                        Dimension { left: 1, right: 0 },
+                        Dimension { left: 1, right: 0 },
+                    ],
+                    ..Signal::new("table", SignalType::UnpackedArrPort(Direction::Out, 8 * 4))
+                },
+            ],
+        );
+        let mut probe = Probe::from_root_node(root_node).unwrap();
+        probe.add_signal_to_probe("sample.mem").unwrap();
+        probe.add_signal_to_probe("sample.bus").unwrap();
+        let paths = |points: Vec<&ProbePoint>| -> Vec<String> {
+            points.iter().map(|p| p.path().to_string()).collect()
+        };
+        assert_eq!(paths(probe.get_gettable_points().collect()), vec!["clk"]);
+        assert_eq!(
+            paths(probe.get_array_points().collect()),
+            vec!["table", "sample.mem", "sample.bus"]
+        );
+        assert_eq!(
+            paths(probe.get_element_settable_points().collect()),
+            vec!["sample.mem"]
+        );
+        assert_eq!(
+            paths(probe.get_memory_points().collect()),
+            vec!["sample.mem", "sample.bus"]
+        );
+        assert_eq!(probe.get_probed_points()[2].element_width(), 8);
+    }
+
+    #[test]
+    fn test_inout_points() {
+        let root_node = InstanceNode::new(
+            "pads",
+            vec![
+                Signal::new("pad", SignalType::UnpackedArrPort(Direction::InOut, 8)),
+                Signal::new("shared", SignalType::UnpackedArrPort(Direction::Ref, 8)),
+            ],
+        );
+        let probe = Probe::from_root_node(root_node).unwrap();
+        let paths = |points: Vec<&ProbePoint>| -> Vec<String> {
+            points.iter().map(|p| p.path().to_string()).collect()
+        };
+        assert_eq!(
+            paths(probe.get_settable_points().collect()),
+            vec!["pad", "shared"]
+        );
+        assert_eq!(paths(probe.get_inout_points().collect()), vec!["pad"]);
+        assert!(probe.get_forceable_points().next().is_none());
     }
 }
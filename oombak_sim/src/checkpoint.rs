@@ -0,0 +1,268 @@
+use std::{
+    io::{BufRead, Write},
+    path::{Path, PathBuf},
+};
+
+use bitvec::vec::BitVec;
+use oombak_rs::dut::LogicVec;
+
+use crate::{
+    clock::{ClockSource, ResetSequence},
+    error::{OombakSimError, OombakSimResult},
+    history::Stimulus,
+    sim::SimulationResult,
+};
+
+const HEADER: &str = "oombak-checkpoint 2";
+
+/// A checkpoint is a directory holding the state saved by the DUT and the state of the request
+/// server, written as lines of whitespace separated fields.
+pub(crate) fn model_path(checkpoint_path: &Path) -> PathBuf {
+    checkpoint_path.join("model")
+}
+
+pub(crate) fn state_path(checkpoint_path: &Path) -> PathBuf {
+    checkpoint_path.join("state")
+}
+
+/// Values of a wave as in `Wave::values`.
+type WaveValues = Vec<(LogicVec, usize, usize)>;
+
+pub(crate) struct Checkpoint {
+    /// Build cache key of the library the DUT state was saved from.
+    pub build_key: String,
+    pub time: u64,
+    pub total_time: usize,
+    pub time_step_ps: usize,
+    pub waves: Vec<(String, WaveValues)>,
+    pub stimuli: Vec<Stimulus>,
+}
+
+pub(crate) fn write<W: Write>(
+    writer: &mut W,
+    build_key: &str,
+    time: u64,
+    simulation_result: &SimulationResult,
+    stimuli: &[Stimulus],
+) -> OombakSimResult<()> {
+    writeln!(writer, "{HEADER}")?;
+    writeln!(writer, "build_key {build_key}")?;
+    writeln!(writer, "time {time}")?;
+    writeln!(writer, "total_time {}", simulation_result.total_time)?;
+    writeln!(writer, "time_step_ps {}", simulation_result.time_step_ps)?;
+    for wave in simulation_result.waves.iter() {
+        writeln!(writer, "wave {} {}", wave.signal_name, wave.values.len())?;
+        for (value, start, count) in wave.values.iter() {
+            writeln!(writer, "{start} {count} {value}")?;
+        }
+    }
+    for stimulus in stimuli {
+        match stimulus {
+            Stimulus::SetSignal(name, value) => writeln!(writer, "set {name} {}", bits(value))?,
+            Stimulus::Force(name, value) => writeln!(writer, "force {name} {}", bits(value))?,
+            Stimulus::Release(name) => writeln!(writer, "release {name}")?,
+            Stimulus::Deposit(name, value) => writeln!(writer, "deposit {name} {}", bits(value))?,
+            Stimulus::AddClock(clock) => writeln!(
+                writer,
+                "clock {} {} {} {}",
+                clock.signal_name, clock.period, clock.duty_cycle, clock.phase
+            )?,
+            Stimulus::AddReset(reset) => writeln!(
+                writer,
+                "reset {} {} {}",
+                reset.signal_name,
+                bits(&reset.active_value),
+                reset.cycles
+            )?,
+            Stimulus::RunTo(time) => writeln!(writer, "run_to {time}")?,
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn read<R: BufRead>(reader: R) -> OombakSimResult<Checkpoint> {
+    let lines = reader.lines().collect::<Result<Vec<String>, _>>()?;
+    let mut lines = lines.iter().enumerate().map(|(i, l)| (i + 1, l));
+    match lines.next() {
+        Some((_, header)) if header == HEADER => (),
+        _ => return Err(invalid("missing header".to_string())),
+    }
+    let mut checkpoint = Checkpoint {
+        build_key: String::new(),
+        time: 0,
+        total_time: 0,
+        time_step_ps: 1,
+        waves: vec![],
+        stimuli: vec![],
+    };
+    while let Some((line_number, line)) = lines.next() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let field = |i: usize| -> OombakSimResult<&str> {
+            fields
+                .get(i)
+                .copied()
+                .ok_or(invalid(format!("line {line_number}: missing field")))
+        };
+        let number = |i: usize| -> OombakSimResult<u64> {
+            field(i)?
+                .parse()
+                .map_err(|_| invalid(format!("line {line_number}: expected a number")))
+        };
+        let bit_vec = |i: usize| -> OombakSimResult<BitVec<u32>> {
+            parse_bits(field(i)?).ok_or(invalid(format!("line {line_number}: expected bits")))
+        };
+        match field(0)? {
+            "build_key" => checkpoint.build_key = field(1)?.to_string(),
+            "time" => checkpoint.time = number(1)?,
+            "total_time" => checkpoint.total_time = number(1)? as usize,
+            "time_step_ps" => checkpoint.time_step_ps = number(1)? as usize,
+            "wave" => {
+                let mut values = vec![];
+                for _ in 0..number(2)? {
+                    let (line_number, line) = lines
+                        .next()
+                        .ok_or(invalid(format!("line {line_number}: missing values")))?;
+                    values.push(parse_value(line).ok_or(invalid(format!(
+                        "line {line_number}: expected start, count and value"
+                    )))?);
+                }
+                checkpoint.waves.push((field(1)?.to_string(), values));
+            }
+            "set" => {
+                let stimulus = Stimulus::SetSignal(field(1)?.to_string(), bit_vec(2)?);
+                checkpoint.stimuli.push(stimulus);
+            }
+            "force" => {
+                let stimulus = Stimulus::Force(field(1)?.to_string(), bit_vec(2)?);
+                checkpoint.stimuli.push(stimulus);
+            }
+            "release" => {
+                let stimulus = Stimulus::Release(field(1)?.to_string());
+                checkpoint.stimuli.push(stimulus);
+            }
+            "deposit" => {
+                let stimulus = Stimulus::Deposit(field(1)?.to_string(), bit_vec(2)?);
+                checkpoint.stimuli.push(stimulus);
+            }
+            "clock" => checkpoint.stimuli.push(Stimulus::AddClock(ClockSource {
+                signal_name: field(1)?.to_string(),
+                period: number(2)?,
+                duty_cycle: number(3)?,
+                phase: number(4)?,
+            })),
+            "reset" => checkpoint.stimuli.push(Stimulus::AddReset(ResetSequence {
+                signal_name: field(1)?.to_string(),
+                active_value: bit_vec(2)?,
+                cycles: number(3)?,
+            })),
+            "run_to" => checkpoint.stimuli.push(Stimulus::RunTo(number(1)?)),
+            other => {
+                return Err(invalid(format!(
+                    "line {line_number}: unknown entry \"{other}\""
+                )))
+            }
+        }
+    }
+    Ok(checkpoint)
+}
+
+fn parse_value(line: &str) -> Option<(LogicVec, usize, usize)> {
+    let mut fields = line.split_whitespace();
+    let start = fields.next()?.parse().ok()?;
+    let count = fields.next()?.parse().ok()?;
+    let value = LogicVec::from_ascii(fields.next()?.as_bytes())?;
+    Some((value, start, count))
+}
+
+/// Most significant bit first, as `LogicVec` is displayed.
+fn bits(bit_vec: &BitVec<u32>) -> String {
+    bit_vec
+        .iter()
+        .rev()
+        .map(|b| if *b { '1' } else { '0' })
+        .collect()
+}
+
+fn parse_bits(text: &str) -> Option<BitVec<u32>> {
+    text.bytes()
+        .rev()
+        .map(|c| match c {
+            b'0' => Some(false),
+            b'1' => Some(true),
+            _ => None,
+        })
+        .collect()
+}
+
+fn invalid(message: String) -> OombakSimError {
+    OombakSimError::InvalidCheckpoint(message)
+}
+
+#[cfg(test)]
+mod test {
+    use bitvec::{bitvec, order::Lsb0, vec::BitVec};
+    use oombak_rs::dut::LogicVec;
+
+    use crate::{
+        clock::{ClockSource, ResetSequence},
+        history::Stimulus,
+        sim::{SimulationResult, TypeInfo, Wave},
+    };
+
+    use super::{read, write};
+
+    #[test]
+    fn test_write_read_roundtrip() {
+        let simulation_result = SimulationResult {
+            waves: vec![Wave {
+                signal_name: "sample.c".to_string(),
+                width: 2,
+                values: vec![
+                    (LogicVec::from(bitvec![u32, Lsb0; 0, 1]), 0, 3),
+                    (LogicVec::from_ascii(b"xz").unwrap(), 3, 0),
+                ],
+                type_info: TypeInfo::Logic,
+            }],
+            time_step_ps: 1,
+            total_time: 3,
+            forced_signals: vec![],
+            memories: vec![],
+        };
+        let stimuli = vec![
+            Stimulus::AddClock(ClockSource::new("clk", 10)),
+            Stimulus::AddReset(ResetSequence {
+                signal_name: "rst_n".to_string(),
+                active_value: BitVec::repeat(false, 1),
+                cycles: 2,
+            }),
+            Stimulus::SetSignal("in".to_string(), bitvec![u32, Lsb0; 0, 0, 1]),
+            Stimulus::Force("sample.c".to_string(), bitvec![u32, Lsb0; 1, 0]),
+            Stimulus::RunTo(3),
+            Stimulus::Release("sample.c".to_string()),
+        ];
+
+        let mut output = vec![];
+        write(&mut output, "abc", 3, &simulation_result, &stimuli).unwrap();
+        let checkpoint = read(output.as_slice()).unwrap();
+
+        assert_eq!(checkpoint.build_key, "abc");
+        assert_eq!(checkpoint.time, 3);
+        assert_eq!(checkpoint.total_time, 3);
+        assert_eq!(
+            checkpoint.waves,
+            vec![(
+                "sample.c".to_string(),
+                simulation_result.waves[0].values.clone()
+            )]
+        );
+        assert_eq!(checkpoint.stimuli, stimuli);
+    }
+
+    #[test]
+    fn test_read_invalid() {
+        assert!(read("time 3\n".as_bytes()).is_err());
+        assert!(read("oombak-checkpoint 1\ntime 3\n".as_bytes()).is_err());
+        assert!(read("oombak-checkpoint 2\nwave out 2\n0 1 1\n".as_bytes()).is_err());
+        assert!(read("oombak-checkpoint 2\nset in 2\n".as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,205 @@
+use bitvec::vec::BitVec;
+
+use crate::error::{OombakSimError, OombakSimResult};
+
+/// Periodic clock driven onto an input port. The clock is low until `phase`, then goes high for
+/// `duty_cycle` percent of each `period`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClockSource {
+    pub signal_name: String,
+    pub period: u64,
+    pub duty_cycle: u64,
+    pub phase: u64,
+}
+
+/// Drives `active_value` onto an input port for a number of primary clock cycles, then releases
+/// it to the inverse value.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResetSequence {
+    pub signal_name: String,
+    pub active_value: BitVec<u32>,
+    pub cycles: u64,
+}
+
+impl ClockSource {
+    pub fn new(signal_name: &str, period: u64) -> Self {
+        Self {
+            signal_name: signal_name.to_string(),
+            period,
+            duty_cycle: 50,
+            phase: 0,
+        }
+    }
+
+    pub fn validate(&self) -> OombakSimResult<()> {
+        if self.high_time() == 0 || self.high_time() >= self.period {
+            return Err(OombakSimError::InvalidGenerator(format!(
+                "clock {} with period {} and duty cycle {}% never toggles",
+                self.signal_name, self.period, self.duty_cycle
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn value_at(&self, time: u64) -> bool {
+        time >= self.phase && (time - self.phase) % self.period < self.high_time()
+    }
+
+    pub fn next_edge_after(&self, time: u64) -> u64 {
+        if time < self.phase {
+            return self.phase;
+        }
+        let offset = (time - self.phase) % self.period;
+        let period_start = time - offset;
+        if offset < self.high_time() {
+            period_start + self.high_time()
+        } else {
+            period_start + self.period
+        }
+    }
+
+    fn high_time(&self) -> u64 {
+        self.period * self.duty_cycle / 100
+    }
+}
+
+#[derive(Clone)]
+struct Reset {
+    sequence: ResetSequence,
+    release_value: BitVec<u32>,
+    release_time: u64,
+}
+
+/// Clock sources and reset sequences applied by the request server while running.
+#[derive(Clone, Default)]
+pub(crate) struct Generators {
+    clocks: Vec<ClockSource>,
+    resets: Vec<Reset>,
+}
+
+impl Generators {
+    pub fn add_clock(&mut self, clock: ClockSource) -> OombakSimResult<()> {
+        clock.validate()?;
+        self.remove_signal(&clock.signal_name);
+        self.clocks.push(clock);
+        Ok(())
+    }
+
+    pub fn add_reset(
+        &mut self,
+        sequence: ResetSequence,
+        width: usize,
+        time: u64,
+    ) -> OombakSimResult<()> {
+        let period = self
+            .primary_period()
+            .ok_or(OombakSimError::InvalidGenerator(
+                "reset cycles require a clock source".to_string(),
+            ))?;
+        let mut sequence = sequence;
+        sequence.active_value.resize(width, false);
+        let release_value = !sequence.active_value.clone();
+        self.remove_signal(&sequence.signal_name);
+        self.resets.push(Reset {
+            release_time: time + sequence.cycles * period,
+            sequence,
+            release_value,
+        });
+        Ok(())
+    }
+
+    pub fn remove_signal(&mut self, signal_name: &str) {
+        self.clocks.retain(|c| c.signal_name != signal_name);
+        self.resets
+            .retain(|r| r.sequence.signal_name != signal_name);
+    }
+
+    pub fn clear(&mut self) {
+        self.clocks.clear();
+        self.resets.clear();
+    }
+
+    /// Period of the first declared clock, used to convert cycles to time.
+    pub fn primary_period(&self) -> Option<u64> {
+        self.clocks.first().map(|c| c.period)
+    }
+
+    pub fn values_at(&self, time: u64) -> Vec<(&str, BitVec<u32>)> {
+        let clock_values = self
+            .clocks
+            .iter()
+            .map(|c| (c.signal_name.as_str(), BitVec::repeat(c.value_at(time), 1)));
+        let reset_values = self.resets.iter().map(|r| {
+            let value = if time < r.release_time {
+                r.sequence.active_value.clone()
+            } else {
+                r.release_value.clone()
+            };
+            (r.sequence.signal_name.as_str(), value)
+        });
+        clock_values.chain(reset_values).collect()
+    }
+
+    pub fn next_edge_after(&self, time: u64) -> Option<u64> {
+        let clock_edges = self.clocks.iter().map(|c| c.next_edge_after(time));
+        let reset_edges = self
+            .resets
+            .iter()
+            .map(|r| r.release_time)
+            .filter(|t| *t > time);
+        clock_edges.chain(reset_edges).min()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bitvec::vec::BitVec;
+
+    use super::{ClockSource, Generators, ResetSequence};
+
+    #[test]
+    fn test_clock_source() {
+        let clock = ClockSource {
+            signal_name: "clk".to_string(),
+            period: 10,
+            duty_cycle: 30,
+            phase: 5,
+        };
+        let values: Vec<bool> = [0, 4, 5, 7, 8, 14, 15].map(|t| clock.value_at(t)).to_vec();
+        assert_eq!(values, vec![false, false, true, true, false, false, true]);
+        let edges: Vec<u64> = [0, 5, 7, 8, 14].map(|t| clock.next_edge_after(t)).to_vec();
+        assert_eq!(edges, vec![5, 8, 8, 15, 15]);
+    }
+
+    #[test]
+    fn test_invalid_clock_source() {
+        let mut clock = ClockSource::new("clk", 1);
+        assert!(clock.validate().is_err());
+        clock.period = 10;
+        clock.duty_cycle = 100;
+        assert!(clock.validate().is_err());
+    }
+
+    #[test]
+    fn test_reset_sequence() {
+        let mut generators = Generators::default();
+        let reset = ResetSequence {
+            signal_name: "rst_n".to_string(),
+            active_value: BitVec::repeat(false, 1),
+            cycles: 5,
+        };
+        assert!(generators.add_reset(reset.clone(), 1, 0).is_err());
+        generators.add_clock(ClockSource::new("clk", 10)).unwrap();
+        generators.add_reset(reset, 1, 3).unwrap();
+
+        assert_eq!(rst_n_at(&generators, 52), BitVec::<u32>::repeat(false, 1));
+        assert_eq!(rst_n_at(&generators, 53), BitVec::<u32>::repeat(true, 1));
+        assert_eq!(generators.next_edge_after(47), Some(50));
+        assert_eq!(generators.next_edge_after(50), Some(53));
+        assert_eq!(generators.primary_period(), Some(10));
+    }
+
+    fn rst_n_at(generators: &Generators, time: u64) -> BitVec<u32> {
+        generators.values_at(time)[1].1.clone()
+    }
+}
@@ -0,0 +1,583 @@
+use std::{cmp::Ordering, fmt::Display};
+
+use bitvec::vec::BitVec;
+use oombak_rs::dut::{Logic, LogicVec};
+
+use crate::{
+    error::{OombakSimError, OombakSimResult},
+    literal::Literal,
+};
+
+/// Boolean condition over probed signals, e.g. `rising(clk) && sample.c == 0x3f`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Condition {
+    text: String,
+    /// Top level `||` terms, each with its text as written.
+    terms: Vec<(Expr, String)>,
+}
+
+/// Values of probed signals at the current and at the previous timeslot.
+pub trait SignalValues {
+    fn current(&self, signal_name: &str) -> Option<&LogicVec>;
+
+    fn previous(&self, signal_name: &str) -> Option<&LogicVec>;
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Expr {
+    Literal(Literal),
+    Signal(String),
+    Prev(String),
+    Rising(String),
+    Falling(String),
+    Not(Box<Expr>),
+    Compare(Box<Expr>, CompareOp, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Identifier(String),
+    Number(Literal),
+    Operator(&'static str),
+    LeftParen,
+    RightParen,
+}
+
+const OPERATORS: [&str; 9] = ["==", "!=", "<=", ">=", "&&", "||", "<", ">", "!"];
+
+impl Condition {
+    pub fn parse(text: &str) -> OombakSimResult<Self> {
+        let chars: Vec<char> = text.chars().collect();
+        let (tokens, spans): (Vec<Token>, Vec<(usize, usize)>) =
+            tokenize(&chars)?.into_iter().unzip();
+        let mut parser = Parser { tokens, pos: 0 };
+        let mut terms = vec![];
+        loop {
+            let start = parser.pos;
+            let term = parser.parse_and()?;
+            let term_text = String::from_iter(&chars[spans[start].0..spans[parser.pos - 1].1]);
+            terms.push((term, term_text));
+            if !parser.consume_operator("||") {
+                break;
+            }
+        }
+        match parser.peek() {
+            None => Ok(Condition {
+                text: text.trim().to_string(),
+                terms,
+            }),
+            Some(token) => Err(invalid_condition(&format!("unexpected token {token:?}"))),
+        }
+    }
+
+    /// Names of every signal referred to by the condition.
+    pub fn signal_names(&self) -> Vec<&str> {
+        let mut names = vec![];
+        for (term, _) in self.terms.iter() {
+            term.collect_signal_names(&mut names);
+        }
+        names
+    }
+
+    /// Returns the top level `||` term which holds, as written, or `None` if the condition does
+    /// not hold. A term whose value is unknown because of X or Z bits does not hold.
+    pub fn fired_term(&self, values: &impl SignalValues) -> OombakSimResult<Option<String>> {
+        for (term, term_text) in self.terms.iter() {
+            if truth_of(&term.eval(values)?) == Logic::One {
+                return Ok(Some(term_text.clone()));
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl Display for Condition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl Expr {
+    /// Four-state value, where comparisons and edges of operands with X or Z bits are X.
+    fn eval(&self, values: &impl SignalValues) -> OombakSimResult<LogicVec> {
+        match self {
+            Expr::Literal(literal) => Ok(literal_bits(literal, 0)?.into()),
+            Expr::Signal(name) => Ok(current_of(values, name)?.clone()),
+            Expr::Prev(name) => Ok(previous_of(values, name)?.clone()),
+            Expr::Rising(name) => Ok(edge_of(values, name, Logic::Zero, Logic::One)?),
+            Expr::Falling(name) => Ok(edge_of(values, name, Logic::One, Logic::Zero)?),
+            Expr::Not(expr) => Ok(logic_vec_of(match truth_of(&expr.eval(values)?) {
+                Logic::Zero => Logic::One,
+                Logic::One => Logic::Zero,
+                _ => Logic::X,
+            })),
+            Expr::Compare(lhs, op, rhs) => {
+                // A literal takes the width of what it is compared with, so negative ones match
+                // the two's complement value of the signal
+                let (lhs, rhs) = match (lhs.as_ref(), rhs.as_ref()) {
+                    (Expr::Literal(literal), rhs) => {
+                        let rhs = rhs.eval(values)?;
+                        (literal_bits(literal, rhs.len())?.into(), rhs)
+                    }
+                    (lhs, Expr::Literal(literal)) => {
+                        let lhs = lhs.eval(values)?;
+                        let rhs = literal_bits(literal, lhs.len())?.into();
+                        (lhs, rhs)
+                    }
+                    (lhs, rhs) => (lhs.eval(values)?, rhs.eval(values)?),
+                };
+                Ok(logic_vec_of(match (lhs.to_bitvec(), rhs.to_bitvec()) {
+                    (Some(lhs), Some(rhs)) => bool_to_logic(op.holds(compare(&lhs, &rhs))),
+                    _ => Logic::X,
+                }))
+            }
+            Expr::And(lhs, rhs) => {
+                let lhs = truth_of(&lhs.eval(values)?);
+                let rhs = truth_of(&rhs.eval(values)?);
+                Ok(logic_vec_of(match (lhs, rhs) {
+                    (Logic::Zero, _) | (_, Logic::Zero) => Logic::Zero,
+                    (Logic::One, Logic::One) => Logic::One,
+                    _ => Logic::X,
+                }))
+            }
+            Expr::Or(lhs, rhs) => {
+                let lhs = truth_of(&lhs.eval(values)?);
+                let rhs = truth_of(&rhs.eval(values)?);
+                Ok(logic_vec_of(match (lhs, rhs) {
+                    (Logic::One, _) | (_, Logic::One) => Logic::One,
+                    (Logic::Zero, Logic::Zero) => Logic::Zero,
+                    _ => Logic::X,
+                }))
+            }
+        }
+    }
+
+    fn collect_signal_names<'a>(&'a self, names: &mut Vec<&'a str>) {
+        match self {
+            Expr::Literal(_) => (),
+            Expr::Signal(name) | Expr::Prev(name) | Expr::Rising(name) | Expr::Falling(name) => {
+                if !names.contains(&name.as_str()) {
+                    names.push(name);
+                }
+            }
+            Expr::Not(expr) => expr.collect_signal_names(names),
+            Expr::Compare(lhs, _, rhs) | Expr::And(lhs, rhs) | Expr::Or(lhs, rhs) => {
+                lhs.collect_signal_names(names);
+                rhs.collect_signal_names(names);
+            }
+        }
+    }
+}
+
+impl CompareOp {
+    fn from_operator(operator: &str) -> Option<Self> {
+        match operator {
+            "==" => Some(CompareOp::Eq),
+            "!=" => Some(CompareOp::Ne),
+            "<" => Some(CompareOp::Lt),
+            "<=" => Some(CompareOp::Le),
+            ">" => Some(CompareOp::Gt),
+            ">=" => Some(CompareOp::Ge),
+            _ => None,
+        }
+    }
+
+    fn holds(&self, ordering: Ordering) -> bool {
+        match self {
+            CompareOp::Eq => ordering.is_eq(),
+            CompareOp::Ne => ordering.is_ne(),
+            CompareOp::Lt => ordering.is_lt(),
+            CompareOp::Le => ordering.is_le(),
+            CompareOp::Gt => ordering.is_gt(),
+            CompareOp::Ge => ordering.is_ge(),
+        }
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn parse_or(&mut self) -> OombakSimResult<Expr> {
+        let mut expr = self.parse_and()?;
+        while self.consume_operator("||") {
+            expr = Expr::Or(Box::new(expr), Box::new(self.parse_and()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> OombakSimResult<Expr> {
+        let mut expr = self.parse_compare()?;
+        while self.consume_operator("&&") {
+            expr = Expr::And(Box::new(expr), Box::new(self.parse_compare()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_compare(&mut self) -> OombakSimResult<Expr> {
+        let lhs = self.parse_unary()?;
+        if let Some(Token::Operator(operator)) = self.peek() {
+            if let Some(op) = CompareOp::from_operator(operator) {
+                self.pos += 1;
+                let rhs = self.parse_unary()?;
+                return Ok(Expr::Compare(Box::new(lhs), op, Box::new(rhs)));
+            }
+        }
+        Ok(lhs)
+    }
+
+    fn parse_unary(&mut self) -> OombakSimResult<Expr> {
+        if self.consume_operator("!") {
+            return Ok(Expr::Not(Box::new(self.parse_unary()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> OombakSimResult<Expr> {
+        match self.next() {
+            Some(Token::Number(value)) => Ok(Expr::Literal(value)),
+            Some(Token::LeftParen) => {
+                let expr = self.parse_or()?;
+                self.expect_right_paren()?;
+                Ok(expr)
+            }
+            Some(Token::Identifier(name)) if self.peek() == Some(&Token::LeftParen) => {
+                self.pos += 1;
+                let argument = match self.next() {
+                    Some(Token::Identifier(argument)) => argument,
+                    _ => return Err(invalid_condition(&format!("{name} expects a signal name"))),
+                };
+                self.expect_right_paren()?;
+                match name.as_str() {
+                    "prev" => Ok(Expr::Prev(argument)),
+                    "rising" => Ok(Expr::Rising(argument)),
+                    "falling" => Ok(Expr::Falling(argument)),
+                    _ => Err(invalid_condition(&format!("unknown function {name}"))),
+                }
+            }
+            Some(Token::Identifier(name)) => Ok(Expr::Signal(name)),
+            Some(token) => Err(invalid_condition(&format!("unexpected token {token:?}"))),
+            None => Err(invalid_condition("unexpected end of condition")),
+        }
+    }
+
+    fn expect_right_paren(&mut self) -> OombakSimResult<()> {
+        match self.next() {
+            Some(Token::RightParen) => Ok(()),
+            _ => Err(invalid_condition("expected ')'")),
+        }
+    }
+
+    fn consume_operator(&mut self, operator: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Operator(o)) if *o == operator) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+}
+
+/// Tokens, each with the range of characters it was read from.
+fn tokenize(chars: &[char]) -> OombakSimResult<Vec<(Token, (usize, usize))>> {
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let (token, len) = if c.is_whitespace() {
+            i += 1;
+            continue;
+        } else if c == '(' {
+            (Token::LeftParen, 1)
+        } else if c == ')' {
+            (Token::RightParen, 1)
+        } else if c.is_ascii_digit()
+            || (c == '-' && chars.get(i + 1).is_some_and(char::is_ascii_digit))
+        {
+            let sign_len = (c == '-') as usize;
+            let len = sign_len
+                + chars[i + sign_len..]
+                    .iter()
+                    .take_while(|c| c.is_ascii_alphanumeric() || **c == '_' || **c == '\'')
+                    .count();
+            let literal = String::from_iter(&chars[i..i + len]);
+            (Token::Number(parse_number(&literal)?), len)
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let len = chars[i..]
+                .iter()
+                .take_while(|c| c.is_ascii_alphanumeric() || **c == '_' || **c == '.')
+                .count();
+            (
+                Token::Identifier(String::from_iter(&chars[i..i + len])),
+                len,
+            )
+        } else if let Some(operator) = OPERATORS
+            .iter()
+            .find(|o| chars[i..].starts_with(&o.chars().collect::<Vec<char>>()))
+        {
+            (Token::Operator(operator), operator.len())
+        } else {
+            return Err(invalid_condition(&format!("unexpected character '{c}'")));
+        };
+        tokens.push((token, (i, i + len)));
+        i += len;
+    }
+    Ok(tokens)
+}
+
+fn parse_number(literal: &str) -> OombakSimResult<Literal> {
+    Literal::parse(literal).map_err(|e| invalid_condition(&e.to_string()))
+}
+
+/// The literal as at least `width` bits.
+fn literal_bits(literal: &Literal, width: usize) -> OombakSimResult<BitVec<u32>> {
+    literal.to_bitvec(usize::max(width, literal.width()))
+}
+
+fn compare(lhs: &BitVec<u32>, rhs: &BitVec<u32>) -> Ordering {
+    let len = usize::max(lhs.len(), rhs.len());
+    for i in (0..len).rev() {
+        let l = lhs.get(i).as_deref().copied().unwrap_or(false);
+        let r = rhs.get(i).as_deref().copied().unwrap_or(false);
+        if l != r {
+            return l.cmp(&r);
+        }
+    }
+    Ordering::Equal
+}
+
+fn current_of<'a>(values: &'a impl SignalValues, name: &str) -> OombakSimResult<&'a LogicVec> {
+    values
+        .current(name)
+        .ok_or(OombakSimError::UnknownSignal(name.to_string()))
+}
+
+fn previous_of<'a>(values: &'a impl SignalValues, name: &str) -> OombakSimResult<&'a LogicVec> {
+    match values.previous(name) {
+        Some(value) => Ok(value),
+        None => current_of(values, name),
+    }
+}
+
+/// Whether the least significant bit went from `from` to `to`, X if either end is unknown.
+fn edge_of(
+    values: &impl SignalValues,
+    name: &str,
+    from: Logic,
+    to: Logic,
+) -> OombakSimResult<LogicVec> {
+    let previous = previous_of(values, name)?.get(0).unwrap_or(Logic::Zero);
+    let current = current_of(values, name)?.get(0).unwrap_or(Logic::Zero);
+    let is_known = |logic| matches!(logic, Logic::Zero | Logic::One);
+    let edge = if !is_known(previous) || !is_known(current) {
+        Logic::X
+    } else {
+        bool_to_logic(previous == from && current == to && values.previous(name).is_some())
+    };
+    Ok(logic_vec_of(edge))
+}
+
+/// 1 if any bit is 1, 0 if every bit is 0, X otherwise.
+fn truth_of(value: &LogicVec) -> Logic {
+    if value.contains(Logic::One) {
+        Logic::One
+    } else if value.is_known() {
+        Logic::Zero
+    } else {
+        Logic::X
+    }
+}
+
+fn bool_to_logic(value: bool) -> Logic {
+    if value {
+        Logic::One
+    } else {
+        Logic::Zero
+    }
+}
+
+fn logic_vec_of(logic: Logic) -> LogicVec {
+    LogicVec::filled(logic, 1)
+}
+
+fn invalid_condition(message: &str) -> OombakSimError {
+    OombakSimError::InvalidCondition(message.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use bitvec::vec::BitVec;
+    use oombak_rs::dut::LogicVec;
+
+    use super::{Condition, SignalValues};
+
+    struct Values {
+        current: HashMap<&'static str, LogicVec>,
+        previous: HashMap<&'static str, LogicVec>,
+    }
+
+    impl SignalValues for Values {
+        fn current(&self, signal_name: &str) -> Option<&LogicVec> {
+            self.current.get(signal_name)
+        }
+
+        fn previous(&self, signal_name: &str) -> Option<&LogicVec> {
+            self.previous.get(signal_name)
+        }
+    }
+
+    fn values(current: &[(&'static str, u32)], previous: &[(&'static str, u32)]) -> Values {
+        let to_map = |v: &[(&'static str, u32)]| {
+            v.iter()
+                .map(|(n, v)| (*n, BitVec::from_slice(&[*v]).into()))
+                .collect()
+        };
+        Values {
+            current: to_map(current),
+            previous: to_map(previous),
+        }
+    }
+
+    #[test]
+    fn test_compare() {
+        let condition = Condition::parse("sample.c == 0x3f").unwrap();
+        let holds = values(&[("sample.c", 63)], &[]);
+        let not_holds = values(&[("sample.c", 62)], &[]);
+        assert_eq!(
+            condition.fired_term(&holds).unwrap(),
+            Some("sample.c == 0x3f".to_string())
+        );
+        assert_eq!(condition.fired_term(&not_holds).unwrap(), None);
+    }
+
+    #[test]
+    fn test_rising_and() {
+        let condition = Condition::parse("rising(clk) && valid").unwrap();
+        let rising = values(&[("clk", 1), ("valid", 1)], &[("clk", 0), ("valid", 1)]);
+        let high = values(&[("clk", 1), ("valid", 1)], &[("clk", 1), ("valid", 1)]);
+        assert!(condition.fired_term(&rising).unwrap().is_some());
+        assert!(condition.fired_term(&high).unwrap().is_none());
+        assert_eq!(condition.signal_names(), vec!["clk", "valid"]);
+    }
+
+    #[test]
+    fn test_prev() {
+        let condition = Condition::parse("sig != prev(sig)").unwrap();
+        let changed = values(&[("sig", 2)], &[("sig", 1)]);
+        let unchanged = values(&[("sig", 2)], &[("sig", 2)]);
+        assert!(condition.fired_term(&changed).unwrap().is_some());
+        assert!(condition.fired_term(&unchanged).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_fired_term() {
+        let condition = Condition::parse("a == 1 || (b > 1_000 && !c)").unwrap();
+        let fired = values(&[("a", 0), ("b", 1001), ("c", 0)], &[]);
+        assert_eq!(
+            condition.fired_term(&fired).unwrap(),
+            Some("(b > 1_000 && !c)".to_string())
+        );
+    }
+
+    #[test]
+    fn test_negative_literal() {
+        let condition = Condition::parse("sig == -1").unwrap();
+        let all_ones = values(&[("sig", u32::MAX)], &[]);
+        let one = values(&[("sig", 1)], &[]);
+        assert!(condition.fired_term(&all_ones).unwrap().is_some());
+        assert!(condition.fired_term(&one).unwrap().is_none());
+        let condition = Condition::parse("sig == 4'sb1111").unwrap();
+        assert!(condition.fired_term(&all_ones).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_unknown_bits() {
+        let logic_values = |current: &[(&'static str, &str)], previous: &[(&'static str, &str)]| {
+            let to_map = |v: &[(&'static str, &str)]| {
+                v.iter()
+                    .map(|(n, v)| (*n, LogicVec::from_ascii(v.as_bytes()).unwrap()))
+                    .collect()
+            };
+            Values {
+                current: to_map(current),
+                previous: to_map(previous),
+            }
+        };
+        let uninitialized = logic_values(&[("sample.c", "xxxx")], &[("sample.c", "xxxx")]);
+        for text in [
+            "sample.c == 0",
+            "sample.c != 0",
+            "!(sample.c == 0)",
+            "sample.c < 1",
+            "sample.c",
+            "!sample.c",
+        ] {
+            let condition = Condition::parse(text).unwrap();
+            assert_eq!(
+                condition.fired_term(&uninitialized).unwrap(),
+                None,
+                "{text}"
+            );
+        }
+        let partly_known = logic_values(&[("sample.c", "1x00")], &[]);
+        assert!(Condition::parse("sample.c")
+            .unwrap()
+            .fired_term(&partly_known)
+            .unwrap()
+            .is_some());
+        assert!(Condition::parse("sample.c == 0 || sample.c")
+            .unwrap()
+            .fired_term(&partly_known)
+            .unwrap()
+            .is_some());
+
+        let rising = Condition::parse("rising(clk)").unwrap();
+        let from_x = logic_values(&[("clk", "1")], &[("clk", "x")]);
+        let from_zero = logic_values(&[("clk", "1")], &[("clk", "0")]);
+        assert_eq!(rising.fired_term(&from_x).unwrap(), None);
+        assert!(rising.fired_term(&from_zero).unwrap().is_some());
+        let falling_to_z = logic_values(&[("clk", "z")], &[("clk", "1")]);
+        assert_eq!(
+            Condition::parse("falling(clk)")
+                .unwrap()
+                .fired_term(&falling_to_z)
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(Condition::parse("a ==").is_err());
+        assert!(Condition::parse("(a == 1").is_err());
+        assert!(Condition::parse("unknown(a)").is_err());
+        assert!(Condition::parse("a = 1").is_err());
+        assert!(Condition::parse("0xg").is_err());
+    }
+}
@@ -0,0 +1,233 @@
+use std::{collections::HashMap, fs::File, io::BufReader, path::Path};
+
+use oombak_rs::dut::{Logic, LogicVec};
+use oombak_rs::parser::{Direction, InstanceNode, Signal, SignalType, TypeInfo};
+
+use crate::{
+    error::{OombakSimError, OombakSimResult},
+    fst,
+    sim::{SimulationResult, Wave},
+    vcd,
+};
+
+const SYNTHETIC_TOP_SCOPE_NAME: &str = "TOP";
+
+/// Waveform dump loaded from a file, together with the scope hierarchy it was recorded with.
+pub struct Dump {
+    pub simulation_result: SimulationResult,
+    pub root_node: InstanceNode,
+}
+
+/// Reads a VCD or FST file. The format is detected from the file content.
+pub fn read(path: &Path) -> OombakSimResult<Dump> {
+    let mut reader = BufReader::new(File::open(path)?);
+    if fst_reader::is_fst_file(&mut reader) {
+        fst::read(reader)
+    } else {
+        vcd::read(reader)
+    }
+}
+
+pub(crate) struct DumpBuilder {
+    scopes: Vec<InstanceNode>,
+    waves: Vec<Wave>,
+    wave_indices: HashMap<String, Vec<usize>>,
+    current_values: Vec<Option<(LogicVec, usize)>>,
+    time: usize,
+    last_change_time: Option<usize>,
+    time_step_ps: usize,
+}
+
+impl DumpBuilder {
+    pub fn new() -> Self {
+        Self {
+            scopes: vec![InstanceNode::default()],
+            waves: vec![],
+            wave_indices: HashMap::new(),
+            current_values: vec![],
+            time: 0,
+            last_change_time: None,
+            time_step_ps: 1,
+        }
+    }
+
+    pub fn set_time_step_ps(&mut self, time_step_ps: usize) {
+        self.time_step_ps = time_step_ps;
+    }
+
+    pub fn push_scope(&mut self, name: &str, module_name: &str) {
+        self.scopes.push(InstanceNode {
+            name: name.to_string(),
+            module_name: module_name.to_string(),
+            ..InstanceNode::default()
+        });
+    }
+
+    pub fn pop_scope(&mut self) {
+        if self.scopes.len() > 1 {
+            let scope = self.scopes.pop().expect("scope stack must not be empty");
+            self.current_scope().children.push(scope);
+        }
+    }
+
+    pub fn add_var(&mut self, id: &str, name: &str, width: usize, direction: Option<Direction>) {
+        let signal_type = match direction {
+            Some(direction) => SignalType::UnpackedArrPort(direction, width),
+            None => SignalType::UnpackedArrNetVar(width),
+        };
+        let signal = Signal {
+            name: name.to_string(),
+            signal_type,
+            dimensions: vec![],
+            type_info: TypeInfo::Logic,
+            location: None,
+            is_net: false,
+        };
+        let path = self.path_of(name);
+        self.current_scope().signals.push(signal);
+        self.wave_indices
+            .entry(id.to_string())
+            .or_default()
+            .push(self.waves.len());
+        self.waves.push(Wave {
+            signal_name: path,
+            width,
+            values: vec![],
+            type_info: TypeInfo::Logic,
+        });
+        self.current_values.push(None);
+    }
+
+    pub fn set_time(&mut self, time: usize) -> OombakSimResult<()> {
+        if time < self.time {
+            return Err(OombakSimError::InvalidDump(format!(
+                "time moves backwards from {} to {time}",
+                self.time
+            )));
+        }
+        self.time = time;
+        Ok(())
+    }
+
+    pub fn change(&mut self, id: &str, bits: &[u8]) -> OombakSimResult<()> {
+        let indices = self
+            .wave_indices
+            .get(id)
+            .ok_or(OombakSimError::InvalidDump(format!(
+                "unknown identifier {id}"
+            )))?;
+        for idx in indices.iter() {
+            let value = logic_vec_from_ascii(bits, self.waves[*idx].width);
+            let wave = &mut self.waves[*idx];
+            self.current_values[*idx] = match self.current_values[*idx].take() {
+                Some((_, start)) if start == self.time => {
+                    Self::merge_with_last(wave, value, self.time)
+                }
+                Some((prev_value, start)) if prev_value != value => {
+                    wave.values.push((prev_value, start, self.time - start));
+                    Some((value, self.time))
+                }
+                Some(current_value) => Some(current_value),
+                None => Some((value, self.time)),
+            }
+        }
+        self.last_change_time = Some(self.time);
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> Dump {
+        while self.scopes.len() > 1 {
+            self.pop_scope();
+        }
+        let total_time = if self.last_change_time == Some(self.time) {
+            self.time + 1
+        } else {
+            self.time
+        };
+        for (wave, current_value) in self.waves.iter_mut().zip(self.current_values) {
+            if let Some((value, start)) = current_value {
+                wave.values.push((value, start, total_time - start));
+            }
+        }
+        let mut root_node = self.scopes.pop().expect("scope stack must not be empty");
+        if root_node.signals.is_empty() && root_node.children.len() == 1 {
+            root_node = root_node
+                .children
+                .pop()
+                .expect("root must have a single child");
+        } else {
+            root_node.name = SYNTHETIC_TOP_SCOPE_NAME.to_string();
+            root_node.module_name = SYNTHETIC_TOP_SCOPE_NAME.to_string();
+            for wave in self.waves.iter_mut() {
+                wave.signal_name = format!("{SYNTHETIC_TOP_SCOPE_NAME}.{}", wave.signal_name);
+            }
+        }
+        Dump {
+            simulation_result: SimulationResult {
+                waves: self.waves,
+                time_step_ps: self.time_step_ps,
+                total_time,
+                forced_signals: vec![],
+                memories: vec![],
+            },
+            root_node,
+        }
+    }
+
+    fn merge_with_last(wave: &mut Wave, value: LogicVec, time: usize) -> Option<(LogicVec, usize)> {
+        match wave.values.last() {
+            Some((last_value, start, count)) if *last_value == value && start + count == time => {
+                let (value, start, _) = wave.values.pop().expect("wave must have a last value");
+                Some((value, start))
+            }
+            _ => Some((value, time)),
+        }
+    }
+
+    fn current_scope(&mut self) -> &mut InstanceNode {
+        self.scopes
+            .last_mut()
+            .expect("scope stack must not be empty")
+    }
+
+    fn path_of(&self, name: &str) -> String {
+        self.scopes
+            .iter()
+            .skip(1)
+            .map(|s| s.name.as_str())
+            .chain(std::iter::once(name))
+            .collect::<Vec<&str>>()
+            .join(".")
+    }
+}
+
+pub(crate) fn time_step_ps_from_exponent(exponent: i8) -> OombakSimResult<usize> {
+    if !(-12..=0).contains(&exponent) {
+        return Err(OombakSimError::InvalidDump(format!(
+            "unsupported timescale 1e{exponent} s"
+        )));
+    }
+    Ok(10usize.pow((exponent + 12) as u32))
+}
+
+/// Values narrower than the wave are extended with X or Z if their leftmost bit is X or Z, and
+/// with 0 otherwise. Nine-valued states from VHDL based dumps are folded into the closest of the
+/// four.
+fn logic_vec_from_ascii(bits: &[u8], width: usize) -> LogicVec {
+    let logic_of = |b: &u8| match b {
+        b'0' | b'l' | b'L' => Logic::Zero,
+        b'1' | b'h' | b'H' => Logic::One,
+        b'z' | b'Z' => Logic::Z,
+        _ => Logic::X,
+    };
+    let mut logic_vec = LogicVec::default();
+    for b in bits.iter().rev() {
+        logic_vec.push(logic_of(b));
+    }
+    let fill = match bits.first().map(logic_of) {
+        Some(logic @ (Logic::X | Logic::Z)) => logic,
+        _ => Logic::Zero,
+    };
+    logic_vec.resize(width, fill);
+    logic_vec
+}
@@ -12,6 +12,31 @@ pub enum OombakSimError {
     OombakGen(OombakGenError),
     #[error("oombak_rs: {}", _0)]
     Oombak(OombakError),
+    #[error("IO error: {}", _0)]
+    Io(std::io::Error),
+    #[error("invalid waveform dump: {}", _0)]
+    InvalidDump(String),
+    #[error("invalid condition: {}", _0)]
+    InvalidCondition(String),
+    #[error("unknown signal: {}", _0)]
+    UnknownSignal(String),
+    #[error("build cache unavailable, neither XDG_CACHE_HOME nor HOME is set")]
+    CacheUnavailable,
+    #[error("invalid generator: {}", _0)]
+    InvalidGenerator(String),
+    #[error("invalid literal: {}", _0)]
+    InvalidLiteral(String),
+    #[error("signal {} is forced, release it first", _0)]
+    SignalForced(String),
+    #[error("invalid checkpoint: {}", _0)]
+    InvalidCheckpoint(String),
+    #[error("no snapshot at or before time {}", _0)]
+    NoSnapshot(u64),
+    #[error(
+        "{} not supported for designs with timing constructs such as delays",
+        _0
+    )]
+    UsesTiming(String),
 }
 
 impl From<OombakGenError> for OombakSimError {
@@ -25,3 +50,9 @@ impl From<OombakError> for OombakSimError {
         Self::Oombak(value)
     }
 }
+
+impl From<std::io::Error> for OombakSimError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
@@ -0,0 +1,70 @@
+use std::io::{BufRead, Seek};
+
+use fst_reader::{FstFilter, FstHierarchyEntry, FstReader, FstSignalValue, FstVarDirection};
+use oombak_rs::parser::Direction;
+
+use crate::{
+    dump::{self, Dump, DumpBuilder},
+    error::{OombakSimError, OombakSimResult},
+};
+
+/// Reads a GTKWave Fast Signal Trace file. Real valued variables are skipped.
+pub fn read<R: BufRead + Seek>(input: R) -> OombakSimResult<Dump> {
+    let mut reader = FstReader::open(input).map_err(invalid_dump)?;
+    let mut builder = DumpBuilder::new();
+    builder.set_time_step_ps(dump::time_step_ps_from_exponent(
+        reader.get_header().timescale_exponent,
+    )?);
+
+    reader
+        .read_hierarchy(|entry| match entry {
+            FstHierarchyEntry::Scope {
+                name, component, ..
+            } => builder.push_scope(&name, &component),
+            FstHierarchyEntry::UpScope => builder.pop_scope(),
+            FstHierarchyEntry::Var {
+                direction,
+                name,
+                length,
+                handle,
+                ..
+            } => builder.add_var(
+                &handle.to_string(),
+                &name,
+                length as usize,
+                direction_of(direction),
+            ),
+            _ => (),
+        })
+        .map_err(invalid_dump)?;
+
+    let mut changes = vec![];
+    reader
+        .read_signals(&FstFilter::all(), |time, handle, value| {
+            if let FstSignalValue::String(bits) = value {
+                changes.push((time as usize, handle.to_string(), bits.to_vec()));
+            }
+            Ok::<(), OombakSimError>(())
+        })
+        .map_err(|e| OombakSimError::InvalidDump(e.to_string()))?;
+    changes.sort_by_key(|(time, _, _)| *time);
+
+    for (time, id, bits) in changes.iter() {
+        builder.set_time(*time)?;
+        builder.change(id, bits)?;
+    }
+    Ok(builder.finish())
+}
+
+fn direction_of(direction: FstVarDirection) -> Option<Direction> {
+    match direction {
+        FstVarDirection::Input => Some(Direction::In),
+        FstVarDirection::Output => Some(Direction::Out),
+        FstVarDirection::InOut => Some(Direction::InOut),
+        _ => None,
+    }
+}
+
+fn invalid_dump(error: fst_reader::ReaderError) -> OombakSimError {
+    OombakSimError::InvalidDump(error.to_string())
+}
@@ -0,0 +1,131 @@
+use bitvec::vec::BitVec;
+
+use crate::clock::{ClockSource, ResetSequence};
+
+/// Stimulus applied to the DUT, in the order it was applied.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Stimulus {
+    SetSignal(String, BitVec<u32>),
+    Force(String, BitVec<u32>),
+    Release(String),
+    Deposit(String, BitVec<u32>),
+    AddClock(ClockSource),
+    AddReset(ResetSequence),
+    RunTo(u64),
+}
+
+/// Timed history of stimulus, replayed to bring a rebuilt DUT back to the same state.
+#[derive(Clone, Default)]
+pub(crate) struct StimulusHistory {
+    stimuli: Vec<Stimulus>,
+}
+
+impl StimulusHistory {
+    pub fn record(&mut self, stimulus: Stimulus) {
+        match (self.stimuli.last_mut(), &stimulus) {
+            (Some(Stimulus::RunTo(last_time)), Stimulus::RunTo(time)) => *last_time = *time,
+            (_, Stimulus::RunTo(0)) => (),
+            _ => self.stimuli.push(stimulus),
+        }
+    }
+
+    pub fn stimuli(&self) -> &[Stimulus] {
+        &self.stimuli
+    }
+
+    pub fn clear(&mut self) {
+        self.stimuli.clear();
+    }
+
+    /// Keeps the first `at` stimuli, returning the rest.
+    pub fn split_off(&mut self, at: usize) -> StimulusHistory {
+        StimulusHistory {
+            stimuli: self.stimuli.split_off(at),
+        }
+    }
+
+    /// Keeps the stimuli applied until a run first reached `time`, dropping everything applied
+    /// later.
+    pub fn truncate_at(&mut self, time: u64) {
+        let Some(idx) = self
+            .stimuli
+            .iter()
+            .position(|s| matches!(s, Stimulus::RunTo(t) if *t >= time))
+        else {
+            return;
+        };
+        self.stimuli.truncate(idx);
+        self.record(Stimulus::RunTo(time));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bitvec::vec::BitVec;
+
+    use super::{Stimulus, StimulusHistory};
+
+    #[test]
+    fn test_record() {
+        let mut history = StimulusHistory::default();
+        let value: BitVec<u32> = BitVec::from_slice(&[16]);
+        history.record(Stimulus::RunTo(0));
+        history.record(Stimulus::SetSignal("in".to_string(), value.clone()));
+        history.record(Stimulus::RunTo(5));
+        history.record(Stimulus::RunTo(10));
+        history.record(Stimulus::SetSignal("in".to_string(), value.clone()));
+        history.record(Stimulus::RunTo(12));
+        assert_eq!(
+            history.stimuli(),
+            &[
+                Stimulus::SetSignal("in".to_string(), value.clone()),
+                Stimulus::RunTo(10),
+                Stimulus::SetSignal("in".to_string(), value),
+                Stimulus::RunTo(12),
+            ]
+        );
+        history.clear();
+        assert!(history.stimuli().is_empty());
+    }
+
+    #[test]
+    fn test_truncate() {
+        let mut history = StimulusHistory::default();
+        let value: BitVec<u32> = BitVec::from_slice(&[16]);
+        history.record(Stimulus::SetSignal("in".to_string(), value.clone()));
+        history.record(Stimulus::RunTo(10));
+        history.record(Stimulus::SetSignal("in".to_string(), value.clone()));
+        history.record(Stimulus::RunTo(12));
+
+        history.truncate_at(20);
+        assert_eq!(history.stimuli().len(), 4);
+        history.truncate_at(10);
+        assert_eq!(
+            history.stimuli(),
+            &[
+                Stimulus::SetSignal("in".to_string(), value.clone()),
+                Stimulus::RunTo(10),
+            ]
+        );
+        history.truncate_at(4);
+        assert_eq!(
+            history.stimuli(),
+            &[
+                Stimulus::SetSignal("in".to_string(), value.clone()),
+                Stimulus::RunTo(4),
+            ]
+        );
+        history.truncate_at(0);
+        assert_eq!(
+            history.stimuli(),
+            &[Stimulus::SetSignal("in".to_string(), value.clone())]
+        );
+
+        let tail = history.split_off(0);
+        assert!(history.stimuli().is_empty());
+        assert_eq!(
+            tail.stimuli(),
+            &[Stimulus::SetSignal("in".to_string(), value)]
+        );
+    }
+}
@@ -1,2 +1,11 @@
+mod checkpoint;
+pub mod clock;
+pub mod condition;
+pub mod dump;
 pub mod error;
+pub mod fst;
+mod history;
+pub mod literal;
+pub mod memory;
 pub mod sim;
+pub mod vcd;
@@ -0,0 +1,263 @@
+use bitvec::vec::BitVec;
+
+use crate::error::{OombakSimError, OombakSimResult};
+
+/// Number written by the user, either plain (`42`, `-5`, `0xff`) or as a SystemVerilog literal
+/// (`8'hFF`, `12'sd-5`, `'b1010`). The value is only turned into bits once the width of the
+/// signal it is assigned to is known.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Literal {
+    magnitude: BitVec<u32>,
+    is_negative: bool,
+    size: Option<usize>,
+    is_signed: bool,
+}
+
+impl Literal {
+    pub fn parse(text: &str) -> OombakSimResult<Self> {
+        let text = text.replace('_', "");
+        let (is_negative, unsigned_text) = match text.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, text.as_str()),
+        };
+        let literal = match unsigned_text.split_once('\'') {
+            Some((size, based)) => Self::parse_based(&text, size, based, is_negative)?,
+            None => {
+                let (digits, radix) = match unsigned_text.get(0..2) {
+                    Some("0x") => (&unsigned_text[2..], 16),
+                    Some("0o") => (&unsigned_text[2..], 8),
+                    Some("0b") => (&unsigned_text[2..], 2),
+                    _ => (unsigned_text, 10),
+                };
+                Literal {
+                    magnitude: parse_digits(&text, digits, radix)?,
+                    is_negative,
+                    size: None,
+                    is_signed: is_negative,
+                }
+            }
+        };
+        if let Some(size) = literal.size {
+            if significant_bits(&literal.magnitude) > size {
+                return Err(invalid_literal(&format!(
+                    "{text} does not fit in {size} bits"
+                )));
+            }
+        }
+        Ok(literal)
+    }
+
+    /// The declared size, or the fewest bits which hold the value.
+    pub fn width(&self) -> usize {
+        self.size.unwrap_or(usize::max(
+            significant_bits(&self.magnitude) + self.is_negative as usize,
+            1,
+        ))
+    }
+
+    /// The value as `width` bits. Sized literals are sign extended if they are signed and zero
+    /// extended otherwise. Fails if the literal does not fit, instead of truncating it.
+    pub fn to_bitvec(&self, width: usize) -> OombakSimResult<BitVec<u32>> {
+        match self.size {
+            Some(size) if size > width => Err(invalid_literal(&format!(
+                "{size}-bit literal does not fit a {width}-bit signal"
+            ))),
+            Some(size) if self.is_negative && !fits_negative(&self.magnitude, size) => Err(
+                invalid_literal(&format!("negative value does not fit a {size}-bit literal")),
+            ),
+            Some(size) => {
+                let mut value = self.magnitude.clone();
+                value.resize(size, false);
+                if self.is_negative {
+                    negate(&mut value);
+                }
+                let fill = self.is_signed && value.last().is_some_and(|b| *b);
+                value.resize(width, fill);
+                Ok(value)
+            }
+            None if self.is_negative => {
+                if !fits_negative(&self.magnitude, width) {
+                    return Err(invalid_literal(&format!(
+                        "negative value does not fit a {width}-bit signal"
+                    )));
+                }
+                let mut value = self.magnitude.clone();
+                value.resize(width, false);
+                negate(&mut value);
+                Ok(value)
+            }
+            None => {
+                let required = significant_bits(&self.magnitude);
+                if required > width {
+                    return Err(invalid_literal(&format!(
+                        "value needs {required} bits but the signal is {width} bits wide"
+                    )));
+                }
+                let mut value = self.magnitude.clone();
+                value.resize(width, false);
+                Ok(value)
+            }
+        }
+    }
+
+    /// `size` and `based` are the parts before and after the apostrophe of a literal such as
+    /// `8'shFF`.
+    fn parse_based(
+        text: &str,
+        size: &str,
+        based: &str,
+        is_negative: bool,
+    ) -> OombakSimResult<Self> {
+        let size = match size {
+            "" => None,
+            size => match size.parse::<usize>() {
+                Ok(0) | Err(_) => return Err(invalid_literal(&format!("invalid size in {text}"))),
+                Ok(size) => Some(size),
+            },
+        };
+        let (is_signed, based) = match based.strip_prefix(['s', 'S']) {
+            Some(rest) => (true, rest),
+            None => (false, based),
+        };
+        let mut chars = based.chars();
+        let radix = match chars.next().map(|c| c.to_ascii_lowercase()) {
+            Some('b') => 2,
+            Some('o') => 8,
+            Some('d') => 10,
+            Some('h') => 16,
+            _ => return Err(invalid_literal(&format!("invalid base in {text}"))),
+        };
+        let digits = chars.as_str();
+        let (is_negative, digits) = match digits.strip_prefix('-') {
+            Some(rest) if radix == 10 && !is_negative => (true, rest),
+            _ => (is_negative, digits),
+        };
+        if digits.contains(['x', 'X', 'z', 'Z', '?']) {
+            return Err(invalid_literal(&format!(
+                "{text} has X or Z digits, which cannot be driven"
+            )));
+        }
+        Ok(Literal {
+            magnitude: parse_digits(text, digits, radix)?,
+            is_negative,
+            size,
+            is_signed,
+        })
+    }
+}
+
+fn parse_digits(text: &str, digits: &str, radix: u32) -> OombakSimResult<BitVec<u32>> {
+    if digits.is_empty() {
+        return Err(invalid_literal(&format!("missing digits in {text}")));
+    }
+    let mut value: BitVec<u32> = BitVec::new();
+    for c in digits.chars() {
+        let digit = c
+            .to_digit(radix)
+            .ok_or(invalid_literal(&format!("invalid digit '{c}' in {text}")))?;
+        value = multiply_add(&value, radix, digit);
+    }
+    Ok(value)
+}
+
+fn multiply_add(value: &BitVec<u32>, multiplier: u32, addend: u32) -> BitVec<u32> {
+    let mut result = BitVec::new();
+    let mut carry = addend as u64;
+    for word in value.as_raw_slice() {
+        let product = *word as u64 * multiplier as u64 + carry;
+        result.extend_from_raw_slice(&[product as u32]);
+        carry = product >> 32;
+    }
+    if carry != 0 {
+        result.extend_from_raw_slice(&[carry as u32]);
+    }
+    result
+}
+
+fn significant_bits(value: &BitVec<u32>) -> usize {
+    value.last_one().map_or(0, |idx| idx + 1)
+}
+
+/// Whether `-magnitude` is within the two's complement range of `width` bits.
+fn fits_negative(magnitude: &BitVec<u32>, width: usize) -> bool {
+    let required = significant_bits(magnitude);
+    required < width || (required == width && magnitude.count_ones() == 1)
+}
+
+/// Two's complement negation within the width of `value`.
+fn negate(value: &mut BitVec<u32>) {
+    for mut bit in value.iter_mut() {
+        *bit = !*bit;
+    }
+    for mut bit in value.iter_mut() {
+        *bit = !*bit;
+        if *bit {
+            break;
+        }
+    }
+}
+
+fn invalid_literal(message: &str) -> OombakSimError {
+    OombakSimError::InvalidLiteral(message.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use bitvec::{bitvec, order::Lsb0, vec::BitVec};
+
+    use super::Literal;
+
+    fn bits(text: &str, width: usize) -> BitVec<u32> {
+        Literal::parse(text).unwrap().to_bitvec(width).unwrap()
+    }
+
+    #[test]
+    fn test_plain() {
+        assert_eq!(bits("5", 4), bitvec![u32, Lsb0; 1, 0, 1, 0]);
+        assert_eq!(bits("0x1_f", 5), bitvec![u32, Lsb0; 1, 1, 1, 1, 1]);
+        assert_eq!(bits("0o7", 3), bitvec![u32, Lsb0; 1, 1, 1]);
+        assert_eq!(bits("0b10", 2), bitvec![u32, Lsb0; 0, 1]);
+        assert_eq!(bits("-1", 3), bitvec![u32, Lsb0; 1, 1, 1]);
+        assert_eq!(bits("-4", 3), bitvec![u32, Lsb0; 0, 0, 1]);
+        let wide = bits("1208925819614629174706175", 80);
+        assert_eq!(wide.count_ones(), 80);
+    }
+
+    #[test]
+    fn test_sized() {
+        assert_eq!(bits("8'hFF", 8).count_ones(), 8);
+        assert_eq!(bits("4'b10_01", 4), bitvec![u32, Lsb0; 1, 0, 0, 1]);
+        assert_eq!(bits("4'b1001", 6), bitvec![u32, Lsb0; 1, 0, 0, 1, 0, 0]);
+        assert_eq!(bits("4'sb1001", 6), bitvec![u32, Lsb0; 1, 0, 0, 1, 1, 1]);
+        assert_eq!(
+            bits("12'sd-5", 12),
+            bitvec![u32, Lsb0; 1, 1, 0, 1, 1, 1, 1, 1, 1, 1, 1, 1]
+        );
+        assert_eq!(bits("-4'sd5", 4), bitvec![u32, Lsb0; 1, 1, 0, 1]);
+        assert_eq!(bits("'hA", 4), bitvec![u32, Lsb0; 0, 1, 0, 1]);
+    }
+
+    #[test]
+    fn test_width() {
+        assert_eq!(Literal::parse("8'h1").unwrap().width(), 8);
+        assert_eq!(Literal::parse("5").unwrap().width(), 3);
+        assert_eq!(Literal::parse("-4").unwrap().width(), 4);
+        assert_eq!(Literal::parse("0").unwrap().width(), 1);
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(Literal::parse("").is_err());
+        assert!(Literal::parse("0x").is_err());
+        assert!(Literal::parse("12a").is_err());
+        assert!(Literal::parse("8'hx0").is_err());
+        assert!(Literal::parse("4'hFF").is_err());
+        assert!(Literal::parse("0'd1").is_err());
+        assert!(Literal::parse("8'q1").is_err());
+        assert!(Literal::parse("16").unwrap().to_bitvec(4).is_err());
+        assert!(Literal::parse("-9").unwrap().to_bitvec(4).is_err());
+        assert!(Literal::parse("8'h1").unwrap().to_bitvec(4).is_err());
+        assert!(Literal::parse("4'sd-9").unwrap().to_bitvec(4).is_err());
+        assert!(Literal::parse("-4'sd9").unwrap().to_bitvec(8).is_err());
+    }
+}
@@ -0,0 +1,166 @@
+use oombak_rs::{dut::LogicVec, parser::Dimension};
+
+/// Contents of a probed memory over time. The first sample is kept whole, later samples only as
+/// the elements which changed. Elements are indexed by their offset from the lowest address.
+#[derive(Clone, Debug)]
+pub struct MemoryTrace {
+    pub signal_name: String,
+    pub element_width: usize,
+    pub dimension: Dimension,
+    initial: Vec<LogicVec>,
+    /// (time, offset, value), in time order.
+    changes: Vec<(usize, usize, LogicVec)>,
+    latest: Vec<LogicVec>,
+    /// Contents as copied from the DUT, laid out as by `Dut::get_memory`.
+    words: Vec<u32>,
+    /// `words` as last recorded, empty if `latest` was not recorded from them.
+    latest_words: Vec<u32>,
+}
+
+impl MemoryTrace {
+    pub fn new(signal_name: &str, element_width: usize, dimension: Dimension) -> Self {
+        Self {
+            signal_name: signal_name.to_string(),
+            element_width,
+            dimension,
+            initial: vec![],
+            changes: vec![],
+            latest: vec![],
+            words: vec![0; dimension.size() * LogicVec::words_len(element_width)],
+            latest_words: vec![],
+        }
+    }
+
+    pub fn lowest_address(&self) -> i64 {
+        self.dimension.left.min(self.dimension.right)
+    }
+
+    pub fn size(&self) -> usize {
+        self.dimension.size()
+    }
+
+    /// Copies the contents with `copy`, laid out as by `Dut::get_memory`, and records them from
+    /// time `time` on. Only elements whose words changed are decoded.
+    pub fn sample<E>(
+        &mut self,
+        time: usize,
+        copy: impl FnOnce(&str, &mut [u32]) -> Result<(), E>,
+    ) -> Result<(), E> {
+        copy(&self.signal_name, &mut self.words)?;
+        let len = LogicVec::words_len(self.element_width);
+        if self.initial.is_empty() {
+            self.initial = self
+                .words
+                .chunks(len)
+                .map(|words| LogicVec::from_words(words, self.element_width))
+                .collect();
+            self.latest = self.initial.clone();
+        } else {
+            for (offset, words) in self.words.chunks(len).enumerate() {
+                let is_changed = match self.latest_words.get(offset * len..(offset + 1) * len) {
+                    Some(latest_words) => latest_words != words,
+                    None => LogicVec::from_words(words, self.element_width) != self.latest[offset],
+                };
+                if is_changed {
+                    let value = LogicVec::from_words(words, self.element_width);
+                    self.changes.push((time, offset, value.clone()));
+                    self.latest[offset] = value;
+                }
+            }
+        }
+        self.latest_words.clone_from(&self.words);
+        Ok(())
+    }
+
+    /// Forgets the changes after `time`.
+    pub fn truncate(&mut self, time: usize) {
+        let end = self.changes.partition_point(|(t, _, _)| *t <= time);
+        self.changes.truncate(end);
+        if !self.initial.is_empty() {
+            self.latest = self.contents_at(time);
+            self.latest_words.clear();
+        }
+    }
+
+    /// Every element as of `time`, or nothing if the memory was not sampled yet.
+    pub fn contents_at(&self, time: usize) -> Vec<LogicVec> {
+        let mut contents = self.initial.clone();
+        let end = self.changes.partition_point(|(t, _, _)| *t <= time);
+        for (_, offset, value) in self.changes[..end].iter() {
+            contents[*offset] = value.clone();
+        }
+        contents
+    }
+
+    /// Whether each element changed value at `time`.
+    pub fn changed_at(&self, time: usize) -> Vec<bool> {
+        let mut changed = vec![false; self.initial.len()];
+        let start = self.changes.partition_point(|(t, _, _)| *t < time);
+        let end = self.changes.partition_point(|(t, _, _)| *t <= time);
+        for (_, offset, _) in self.changes[start..end].iter() {
+            changed[*offset] = true;
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bitvec::vec::BitVec;
+    use oombak_rs::{dut::LogicVec, parser::Dimension};
+
+    use super::MemoryTrace;
+
+    fn value(bit: bool) -> LogicVec {
+        LogicVec::from(BitVec::repeat(bit, 1))
+    }
+
+    /// Records 1-bit elements, each copied as an `aval` and a `bval` word.
+    fn record(trace: &mut MemoryTrace, time: usize, bits: &[bool]) {
+        let copy = |_: &str, words: &mut [u32]| {
+            for (words, bit) in words.chunks_mut(2).zip(bits) {
+                words[0] = *bit as u32;
+                words[1] = 0;
+            }
+            Ok::<(), ()>(())
+        };
+        trace.sample(time, copy).unwrap();
+    }
+
+    #[test]
+    fn test_record() {
+        let mut trace = MemoryTrace::new("mem", 1, Dimension { left: 3, right: 1 });
+        assert_eq!(trace.lowest_address(), 1);
+        assert_eq!(trace.size(), 3);
+        assert!(trace.contents_at(0).is_empty());
+
+        record(&mut trace, 0, &[false, false, false]);
+        record(&mut trace, 5, &[false, true, false]);
+        record(&mut trace, 10, &[true, true, false]);
+
+        assert_eq!(trace.contents_at(4), vec![value(false); 3]);
+        assert_eq!(
+            trace.contents_at(7),
+            vec![value(false), value(true), value(false)]
+        );
+        assert_eq!(
+            trace.contents_at(10),
+            vec![value(true), value(true), value(false)]
+        );
+        assert_eq!(trace.changed_at(0), vec![false; 3]);
+        assert_eq!(trace.changed_at(5), vec![false, true, false]);
+        assert_eq!(trace.changed_at(6), vec![false; 3]);
+    }
+
+    #[test]
+    fn test_truncate() {
+        let mut trace = MemoryTrace::new("mem", 1, Dimension { left: 0, right: 1 });
+        record(&mut trace, 0, &[false, false]);
+        record(&mut trace, 5, &[true, false]);
+        trace.truncate(4);
+        assert_eq!(trace.contents_at(5), vec![value(false); 2]);
+
+        record(&mut trace, 6, &[false, true]);
+        assert_eq!(trace.changed_at(6), vec![false, true]);
+    }
+}
@@ -1,18 +1,37 @@
 use std::{
+    fmt::Display,
+    fs::{self, File},
+    io::{BufReader, BufWriter, Write},
     path::{Path, PathBuf},
     sync::{
         mpsc::{self, Receiver, Sender},
         Arc, RwLock,
     },
     thread,
+    time::Duration,
 };
 
 use bitvec::vec::BitVec;
 
-use oombak_gen::TempGenDir;
-use oombak_rs::{dut::Dut, error::OombakResult, probe::Probe};
+use oombak_gen::{cache::BuildCache, error::OombakGenError, TempGenDir};
+use oombak_rs::{
+    dut::{Dut, SignalHandle, SignalHandles, Snapshot},
+    error::{OombakError, OombakResult},
+    parser,
+    probe::Probe,
+};
 
-use crate::error::{OombakSimError, OombakSimResult};
+use crate::{
+    checkpoint::{self, Checkpoint},
+    clock::{ClockSource, Generators, ResetSequence},
+    condition::{Condition, SignalValues},
+    dump,
+    error::{OombakSimError, OombakSimResult},
+    history::{Stimulus, StimulusHistory},
+    literal::Literal,
+    memory::MemoryTrace,
+    vcd,
+};
 
 pub struct Simulator {
     request_tx: Sender<Request>,
@@ -27,31 +46,90 @@ pub trait Listener: Send + Sync {
 
 pub enum Request {
     Run(u64),
-    SetSignal(String, BitVec<u32>),
-    Load(PathBuf),
+    /// Goes back by the given duration, to where the simulation first reached that time.
+    RunBack(u64),
+    RunUntil(Condition, u64),
+    RunCycles(u64),
+    SetSignal(String, Literal),
+    Force(String, Literal),
+    Release(String),
+    Deposit(String, Literal),
+    Load(SourceConfig),
+    LoadWaveform(PathBuf),
     ModifyProbedPoints(ProbePointsModification),
     GetSimulationResult,
+    ExportVcd(PathBuf),
+    /// Saves the DUT and the simulation so far into a checkpoint directory.
+    SaveCheckpoint(PathBuf),
+    /// Restores a checkpoint saved from the same design with the same probed points.
+    RestoreCheckpoint(PathBuf),
+    AddClock(ClockSource),
+    /// Signal name, active value and number of cycles. The active value is sized to the signal
+    /// once the request is served, as for `SetSignal`.
+    AddReset(String, Literal, u64),
+    ListCache,
+    PruneCache(Duration),
+    ClearCache,
     Terminate,
 }
 
 pub enum Response<'a> {
     RunResult(Result<u64, String>),
+    RunUntilResult(Result<Breakpoint, String>),
     SetSignalResult(Result<(), String>),
-    LoadResult(Result<LoadedDut, String>),
-    ModifyProbedPointsResult(Result<LoadedDut, String>),
+    ForceResult(Result<(), String>),
+    ReleaseResult(Result<(), String>),
+    DepositResult(Result<(), String>),
+    LoadResult(Result<LoadedDut, LoadError>),
+    LoadWaveformResult(Result<LoadedDut, String>),
+    ModifyProbedPointsResult(Result<LoadedDut, LoadError>),
     SimulationResult(Result<&'a SimulationResult, String>),
+    ExportVcdResult(Result<(), String>),
+    SaveCheckpointResult(Result<(), String>),
+    RestoreCheckpointResult(Result<u64, String>),
+    AddClockResult(Result<(), String>),
+    AddResetResult(Result<(), String>),
+    ListCacheResult(Result<Vec<CacheEntry>, String>),
+    PruneCacheResult(Result<usize, String>),
+    ClearCacheResult(Result<usize, String>),
 }
 
-pub use oombak_rs::parser::{InstanceNode, Signal, SignalType};
+pub use oombak_gen::cache::CacheEntry;
+pub use oombak_gen::diagnostic::{Diagnostic, Severity};
+pub use oombak_gen::source::SourceConfig;
+pub use oombak_rs::dut::{Logic, LogicVec};
+pub use oombak_rs::parser::{
+    Direction, EnumValue, Field, InstanceNode, Signal, SignalType, SourceLocation, TypeInfo,
+};
 
 pub struct ProbePointsModification {
     pub to_add: Vec<String>,
     pub to_remove: Vec<String>,
+    /// Top-level ports to remove. Kept apart from `to_remove` so ports are never removed by
+    /// accident.
+    pub top_level_ports_to_remove: Vec<String>,
+}
+
+/// Where a `RunUntil` request stopped. `condition` is the term which held, or `None` if the
+/// maximum duration was reached first.
+pub struct Breakpoint {
+    pub time: u64,
+    pub condition: Option<String>,
+}
+
+/// Failed `Load` or `ModifyProbedPoints` request. `diagnostics` holds the locations reported by
+/// the DUT build, if any.
+#[derive(Debug)]
+pub struct LoadError {
+    pub message: String,
+    pub diagnostics: Vec<Diagnostic>,
 }
 
 pub struct LoadedDut {
     pub root_node: InstanceNode,
     pub probed_points: Vec<String>,
+    /// Warnings and notes reported while parsing the design.
+    pub diagnostics: Vec<Diagnostic>,
 }
 
 impl Simulator {
@@ -79,15 +157,36 @@ impl Simulator {
             loop {
                 match request_rx.recv().map_err(|e| e.to_string())? {
                     Request::Run(duration) => server.serve_run(duration),
+                    Request::RunBack(duration) => server.serve_run_back(duration),
+                    Request::RunUntil(condition, max_duration) => {
+                        server.serve_run_until(&condition, max_duration)
+                    }
+                    Request::RunCycles(cycles) => server.serve_run_cycles(cycles),
                     Request::SetSignal(signal_name, value) => {
                         server.serve_set_signal(&signal_name, &value)
                     }
-                    Request::Load(sv_path) => server.serve_load(&sv_path),
+                    Request::Force(signal_name, value) => server.serve_force(&signal_name, &value),
+                    Request::Release(signal_name) => server.serve_release(&signal_name),
+                    Request::Deposit(signal_name, value) => {
+                        server.serve_deposit(&signal_name, &value)
+                    }
+                    Request::Load(source_config) => server.serve_load(&source_config),
+                    Request::LoadWaveform(dump_path) => server.serve_load_waveform(&dump_path),
                     Request::ModifyProbedPoints(probe_points_modification) => {
                         server.serve_modify_probe_points(&probe_points_modification)
                     }
                     Request::GetSimulationResult => server.serve_simulation_result(),
-                    Request::Terminate => break (Ok(())),
+                    Request::ExportVcd(vcd_path) => server.serve_export_vcd(&vcd_path),
+                    Request::SaveCheckpoint(path) => server.serve_save_checkpoint(&path),
+                    Request::RestoreCheckpoint(path) => server.serve_restore_checkpoint(&path),
+                    Request::AddClock(clock) => server.serve_add_clock(clock),
+                    Request::AddReset(signal_name, active_value, cycles) => {
+                        server.serve_add_reset(&signal_name, &active_value, cycles)
+                    }
+                    Request::ListCache => server.serve_list_cache(),
+                    Request::PruneCache(max_age) => server.serve_prune_cache(max_age),
+                    Request::ClearCache => server.serve_clear_cache(),
+                    Request::Terminate => break Ok(()),
                 }
             }
         });
@@ -97,21 +196,62 @@ impl Simulator {
 struct RequestServer {
     dut: Option<Dut>,
     probe: Option<Probe>,
-    sv_path: Option<PathBuf>,
+    source_config: Option<SourceConfig>,
+    /// Build cache key of the library of `dut`, recorded in checkpoints.
+    build_key: Option<String>,
     listeners: Arc<RwLock<Listeners>>,
     simulation_time: u64,
     simulation_result: SimulationResult,
+    generators: Generators,
+    history: StimulusHistory,
+    /// One handle per wave, in the same order.
+    wave_handles: SignalHandles,
+    /// In time order, the first one taken when the DUT was loaded.
+    snapshots: Vec<SimSnapshot>,
+    snapshot_interval: u64,
+}
+
+/// State of the request server when a run reached `time`, taken every `snapshot_interval` so
+/// that the simulation can run back without replaying from time 0.
+#[derive(Clone)]
+struct SimSnapshot {
+    time: u64,
+    dut: Snapshot,
+    generators: Generators,
+    forced_signals: Vec<String>,
+    history_len: usize,
+}
+
+/// State of the request server before running back, returned to if the replay fails.
+struct RunBackRollback {
+    dut: Snapshot,
+    simulation_time: u64,
+    simulation_result: SimulationResult,
+    generators: Generators,
+    history: StimulusHistory,
+    snapshots: Vec<SimSnapshot>,
+    snapshot_interval: u64,
 }
 
+const SNAPSHOT_INTERVAL: u64 = 1000;
+/// Past this, every other snapshot is dropped and the interval doubled.
+const MAX_SNAPSHOTS: usize = 64;
+
 impl RequestServer {
     fn new(listeners: Arc<RwLock<Listeners>>) -> Self {
         Self {
             dut: None,
-            sv_path: None,
+            source_config: None,
             probe: None,
+            build_key: None,
             listeners,
             simulation_time: 0,
             simulation_result: SimulationResult::default(),
+            generators: Generators::default(),
+            history: StimulusHistory::default(),
+            wave_handles: SignalHandles::default(),
+            snapshots: vec![],
+            snapshot_interval: SNAPSHOT_INTERVAL,
         }
     }
 
@@ -123,18 +263,74 @@ impl RequestServer {
         self.notify_listeners(response);
     }
 
-    fn serve_set_signal(&self, signal_name: &str, value: &BitVec<u32>) {
-        let response = match self.set_signal(signal_name, value) {
+    fn serve_run_back(&mut self, duration: u64) {
+        let response = match self.run_back(duration) {
+            Ok(curr_time) => Response::RunResult(Ok(curr_time)),
+            Err(e) => Response::RunResult(Err(e.to_string())),
+        };
+        self.notify_listeners(response);
+    }
+
+    fn serve_run_until(&mut self, condition: &Condition, max_duration: u64) {
+        let response = match self.run_until(condition, max_duration) {
+            Ok(breakpoint) => Response::RunUntilResult(Ok(breakpoint)),
+            Err(e) => Response::RunUntilResult(Err(e.to_string())),
+        };
+        self.notify_listeners(response);
+    }
+
+    fn serve_run_cycles(&mut self, cycles: u64) {
+        let response = match self.run_cycles(cycles) {
+            Ok(curr_time) => Response::RunResult(Ok(curr_time)),
+            Err(e) => Response::RunResult(Err(e.to_string())),
+        };
+        self.notify_listeners(response);
+    }
+
+    fn serve_set_signal(&mut self, signal_name: &str, value: &Literal) {
+        let response = match self.set_signal_to_literal(signal_name, value) {
             Ok(_) => Response::SetSignalResult(Ok(())),
             Err(e) => Response::SetSignalResult(Err(e.to_string())),
         };
         self.notify_listeners(response);
     }
 
-    fn serve_load(&mut self, sv_path: &Path) {
-        let response = match self.load_dut(sv_path) {
+    fn serve_force(&mut self, signal_name: &str, value: &Literal) {
+        let response = match self.force_to_literal(signal_name, value) {
+            Ok(_) => Response::ForceResult(Ok(())),
+            Err(e) => Response::ForceResult(Err(e.to_string())),
+        };
+        self.notify_listeners(response);
+    }
+
+    fn serve_release(&mut self, signal_name: &str) {
+        let response = match self.release(signal_name) {
+            Ok(_) => Response::ReleaseResult(Ok(())),
+            Err(e) => Response::ReleaseResult(Err(e.to_string())),
+        };
+        self.notify_listeners(response);
+    }
+
+    fn serve_deposit(&mut self, signal_name: &str, value: &Literal) {
+        let response = match self.deposit_literal(signal_name, value) {
+            Ok(_) => Response::DepositResult(Ok(())),
+            Err(e) => Response::DepositResult(Err(e.to_string())),
+        };
+        self.notify_listeners(response);
+    }
+
+    fn serve_load(&mut self, source_config: &SourceConfig) {
+        let response = match self.load_dut(source_config) {
             Ok(loaded_dut) => Response::LoadResult(Ok(loaded_dut)),
-            Err(e) => Response::LoadResult(Err(e.to_string())),
+            Err(e) => Response::LoadResult(Err(LoadError::from(e))),
+        };
+        self.notify_listeners(response);
+    }
+
+    fn serve_load_waveform(&mut self, dump_path: &Path) {
+        let response = match self.load_waveform(dump_path) {
+            Ok(loaded_dut) => Response::LoadWaveformResult(Ok(loaded_dut)),
+            Err(e) => Response::LoadWaveformResult(Err(e.to_string())),
         };
         self.notify_listeners(response);
     }
@@ -142,7 +338,7 @@ impl RequestServer {
     fn serve_modify_probe_points(&mut self, probe_points_modification: &ProbePointsModification) {
         let response = match self.modify_probe_points(probe_points_modification) {
             Ok(loaded_dut) => Response::ModifyProbedPointsResult(Ok(loaded_dut)),
-            Err(e) => Response::ModifyProbedPointsResult(Err(e.to_string())),
+            Err(e) => Response::ModifyProbedPointsResult(Err(LoadError::from(e))),
         };
         self.notify_listeners(response);
     }
@@ -152,14 +348,97 @@ impl RequestServer {
         self.notify_listeners(response);
     }
 
-    fn load_dut(&mut self, sv_path: &Path) -> OombakSimResult<LoadedDut> {
-        let (temp_gen_dir, probe) = oombak_gen::build(sv_path)?;
+    fn serve_export_vcd(&self, vcd_path: &Path) {
+        let response = match self.export_vcd(vcd_path) {
+            Ok(_) => Response::ExportVcdResult(Ok(())),
+            Err(e) => Response::ExportVcdResult(Err(e.to_string())),
+        };
+        self.notify_listeners(response);
+    }
+
+    fn serve_save_checkpoint(&self, checkpoint_path: &Path) {
+        let response = match self.save_checkpoint(checkpoint_path) {
+            Ok(_) => Response::SaveCheckpointResult(Ok(())),
+            Err(e) => Response::SaveCheckpointResult(Err(e.to_string())),
+        };
+        self.notify_listeners(response);
+    }
+
+    fn serve_restore_checkpoint(&mut self, checkpoint_path: &Path) {
+        let response = match self.restore_checkpoint(checkpoint_path) {
+            Ok(time) => Response::RestoreCheckpointResult(Ok(time)),
+            Err(e) => Response::RestoreCheckpointResult(Err(e.to_string())),
+        };
+        self.notify_listeners(response);
+    }
+
+    fn serve_add_clock(&mut self, clock: ClockSource) {
+        let response = match self.add_clock(clock) {
+            Ok(_) => Response::AddClockResult(Ok(())),
+            Err(e) => Response::AddClockResult(Err(e.to_string())),
+        };
+        self.notify_listeners(response);
+    }
+
+    fn serve_add_reset(&mut self, signal_name: &str, active_value: &Literal, cycles: u64) {
+        let response = match self.add_reset_literal(signal_name, active_value, cycles) {
+            Ok(_) => Response::AddResetResult(Ok(())),
+            Err(e) => Response::AddResetResult(Err(e.to_string())),
+        };
+        self.notify_listeners(response);
+    }
+
+    fn serve_list_cache(&self) {
+        let response = match Self::build_cache().and_then(|c| Ok(c.list()?)) {
+            Ok(entries) => Response::ListCacheResult(Ok(entries)),
+            Err(e) => Response::ListCacheResult(Err(e.to_string())),
+        };
+        self.notify_listeners(response);
+    }
+
+    fn serve_prune_cache(&self, max_age: Duration) {
+        let response = match Self::build_cache().and_then(|c| Ok(c.prune(max_age)?)) {
+            Ok(removed) => Response::PruneCacheResult(Ok(removed)),
+            Err(e) => Response::PruneCacheResult(Err(e.to_string())),
+        };
+        self.notify_listeners(response);
+    }
+
+    fn serve_clear_cache(&self) {
+        let response = match Self::build_cache().and_then(|c| Ok(c.clear()?)) {
+            Ok(removed) => Response::ClearCacheResult(Ok(removed)),
+            Err(e) => Response::ClearCacheResult(Err(e.to_string())),
+        };
+        self.notify_listeners(response);
+    }
+
+    fn load_dut(&mut self, source_config: &SourceConfig) -> OombakSimResult<LoadedDut> {
+        let (temp_gen_dir, probe) = oombak_gen::build(source_config)?;
         let loaded_dut = LoadedDut::from(&probe);
         let lib_path = temp_gen_dir.lib_path();
         self.dut = Some(Dut::new(lib_path.to_string_lossy().as_ref())?);
-        self.sv_path = Some(sv_path.to_path_buf());
+        self.build_key = Some(temp_gen_dir.key().to_string());
+        self.source_config = Some(source_config.clone());
+        self.history.clear();
         self.probe = Some(probe);
         self.reload_simulation_result()?;
+        self.reset_snapshots(0)?;
+        Ok(loaded_dut)
+    }
+
+    fn load_waveform(&mut self, dump_path: &Path) -> OombakSimResult<LoadedDut> {
+        let dump = dump::read(dump_path)?;
+        let loaded_dut = LoadedDut::from(&dump);
+        self.dut = None;
+        self.snapshots.clear();
+        self.wave_handles = SignalHandles::default();
+        self.probe = None;
+        self.source_config = None;
+        self.build_key = None;
+        self.generators.clear();
+        self.history.clear();
+        self.simulation_time = dump.simulation_result.total_time as u64;
+        self.simulation_result = dump.simulation_result;
         Ok(loaded_dut)
     }
 
@@ -167,57 +446,501 @@ impl RequestServer {
         &mut self,
         probe_points_modification: &ProbePointsModification,
     ) -> OombakSimResult<LoadedDut> {
-        self.modify_probe(probe_points_modification)?;
-        let temp_gen_dir = self.rebuild_sv_path()?;
+        let probe = self.modified_probe(probe_points_modification)?;
+        let temp_gen_dir = self.rebuild(&probe)?;
         let lib_path = temp_gen_dir.lib_path();
-        self.dut = Some(Dut::new(lib_path.to_string_lossy().as_ref())?);
+        let dut = Dut::new(lib_path.to_string_lossy().as_ref())?;
+        self.dut = Some(dut);
+        self.build_key = Some(temp_gen_dir.key().to_string());
+        self.probe = Some(probe);
+        for path in probe_points_modification.top_level_ports_to_remove.iter() {
+            self.generators.remove_signal(path);
+        }
         self.reload_simulation_result()?;
+        self.reset_snapshots(0)?;
+        self.replay_history()?;
         Ok(LoadedDut::from(
             self.probe.as_ref().ok_or(OombakSimError::DutNotLoaded)?,
         ))
     }
 
-    fn modify_probe(
-        &mut self,
+    /// Copy of the probe with the modification applied, so that the current one is kept if the
+    /// rebuild fails.
+    fn modified_probe(
+        &self,
         probe_points_modification: &ProbePointsModification,
-    ) -> OombakSimResult<()> {
-        let probe = self.probe.as_mut().ok_or(OombakSimError::DutNotLoaded)?;
+    ) -> OombakSimResult<Probe> {
+        let mut probe = self.probe.clone().ok_or(OombakSimError::DutNotLoaded)?;
         for path in probe_points_modification.to_add.iter() {
             probe.add_signal_to_probe(path)?;
         }
+        for path in probe_points_modification.to_remove.iter() {
+            probe.remove_signal_from_probe(path)?;
+        }
+        for path in probe_points_modification.top_level_ports_to_remove.iter() {
+            probe.remove_top_level_port_from_probe(path)?;
+        }
+        Ok(probe)
+    }
+
+    fn rebuild(&self, probe: &Probe) -> OombakSimResult<TempGenDir> {
+        let source_config = self
+            .source_config
+            .as_ref()
+            .ok_or(OombakSimError::DutNotLoaded)?;
+        Ok(oombak_gen::build_with_probe(source_config, probe)?)
+    }
+
+    fn export_vcd(&self, vcd_path: &Path) -> OombakSimResult<()> {
+        let top_scope_name = match &self.probe {
+            Some(probe) => probe.top_level_module_name(),
+            None => "TOP",
+        };
+        let mut writer = BufWriter::new(File::create(vcd_path)?);
+        vcd::write(&mut writer, &self.simulation_result, top_scope_name)?;
+        Ok(writer.flush()?)
+    }
+
+    fn save_checkpoint(&self, checkpoint_path: &Path) -> OombakSimResult<()> {
+        self.check_no_timing("checkpoints are")?;
+        let dut = self.dut()?;
+        let build_key = self.build_key()?;
+        fs::create_dir_all(checkpoint_path)?;
+        dut.save(&checkpoint::model_path(checkpoint_path))?;
+        let mut writer = BufWriter::new(File::create(checkpoint::state_path(checkpoint_path))?);
+        checkpoint::write(
+            &mut writer,
+            build_key,
+            self.simulation_time,
+            &self.simulation_result,
+            self.history.stimuli(),
+        )?;
+        Ok(writer.flush()?)
+    }
+
+    /// Memories are sampled again from the restored time on, since their past contents are not
+    /// part of a checkpoint. Everything is checked before the DUT is restored, so that a
+    /// checkpoint which does not fit leaves the simulation as it was.
+    fn restore_checkpoint(&mut self, checkpoint_path: &Path) -> OombakSimResult<u64> {
+        self.check_no_timing("checkpoints are")?;
+        let reader = BufReader::new(File::open(checkpoint::state_path(checkpoint_path))?);
+        let checkpoint = checkpoint::read(reader)?;
+        self.check_checkpoint(&checkpoint)?;
+        let (generators, forced_signals) = self.generators_from(&checkpoint.stimuli)?;
+        self.dut()?
+            .restore(&checkpoint::model_path(checkpoint_path))?;
+        self.reload_simulation_result()?;
+        for (wave, (_, values)) in self
+            .simulation_result
+            .waves
+            .iter_mut()
+            .zip(checkpoint.waves)
+        {
+            wave.values = values;
+        }
+        self.simulation_result.total_time = checkpoint.total_time;
+        self.simulation_result.time_step_ps = checkpoint.time_step_ps;
+        self.simulation_time = checkpoint.time;
+        self.generators = generators;
+        self.simulation_result.forced_signals = forced_signals;
+        self.history.clear();
+        for stimulus in checkpoint.stimuli {
+            self.history.record(stimulus);
+        }
+        self.record_memory_contents_at(self.simulation_time)?;
+        self.reset_snapshots(self.history.stimuli().len())?;
+        Ok(self.simulation_time)
+    }
+
+    fn check_checkpoint(&self, checkpoint: &Checkpoint) -> OombakSimResult<()> {
+        // The model of another library cannot be restored, and aborts the process when tried
+        if checkpoint.build_key != self.build_key()? {
+            return Err(OombakSimError::InvalidCheckpoint(
+                "saved from a different build of the DUT".to_string(),
+            ));
+        }
+        let is_same_probe = checkpoint.waves.len() == self.simulation_result.waves.len()
+            && checkpoint
+                .waves
+                .iter()
+                .zip(self.simulation_result.waves.iter())
+                .all(|((name, _), wave)| *name == wave.signal_name);
+        if !is_same_probe {
+            return Err(OombakSimError::InvalidCheckpoint(
+                "probed signals differ from the loaded DUT".to_string(),
+            ));
+        }
         Ok(())
     }
 
-    fn rebuild_sv_path(&self) -> OombakSimResult<TempGenDir> {
-        match (&self.sv_path, &self.probe) {
-            (Some(sv_path), Some(probe)) => Ok(oombak_gen::build_with_probe(sv_path, probe)?),
-            _ => Err(OombakSimError::DutNotLoaded),
+    /// Generators and forced signals as `stimuli`, applied from time 0, leave them.
+    fn generators_from(&self, stimuli: &[Stimulus]) -> OombakSimResult<(Generators, Vec<String>)> {
+        let mut generators = Generators::default();
+        let mut forced = SimulationResult::default();
+        let mut time = 0;
+        for stimulus in stimuli {
+            match stimulus {
+                Stimulus::AddClock(clock) => generators.add_clock(clock.clone())?,
+                Stimulus::AddReset(reset) => {
+                    let width = self.width_of_signal(&reset.signal_name)?;
+                    generators.add_reset(reset.clone(), width, time)?
+                }
+                Stimulus::Force(signal_name, _) => forced.set_forced(signal_name, true),
+                Stimulus::Release(signal_name) => forced.set_forced(signal_name, false),
+                Stimulus::RunTo(run_to) => time = *run_to,
+                Stimulus::SetSignal(..) | Stimulus::Deposit(..) => (),
+            }
         }
+        Ok((generators, forced.forced_signals))
+    }
+
+    /// Verilator does not save the state of its timing scheduler, so the state of a design with
+    /// timing constructs cannot be saved and restored.
+    fn check_no_timing(&self, feature: &str) -> OombakSimResult<()> {
+        if self.uses_timing() {
+            return Err(OombakSimError::UsesTiming(feature.to_string()));
+        }
+        Ok(())
+    }
+
+    fn uses_timing(&self) -> bool {
+        self.probe
+            .as_ref()
+            .is_some_and(|probe| probe.root_node().tree_uses_timing())
+    }
+
+    fn build_key(&self) -> OombakSimResult<&str> {
+        self.build_key
+            .as_deref()
+            .ok_or(OombakSimError::DutNotLoaded)
+    }
+
+    fn build_cache() -> OombakSimResult<BuildCache> {
+        BuildCache::open_default().ok_or(OombakSimError::CacheUnavailable)
     }
 
     fn reload_simulation_result(&mut self) -> OombakSimResult<()> {
-        self.simulation_result = SimulationResult::default();
+        self.simulation_result = SimulationResult {
+            time_step_ps: 1,
+            ..SimulationResult::default()
+        };
         self.simulation_time = 0;
+        self.generators.clear();
         self.load_signal_names_to_simulation_result()?;
         Ok(())
     }
 
     fn load_signal_names_to_simulation_result(&mut self) -> OombakSimResult<()> {
-        let waves: Vec<Wave> = self.dut()?.query()?.into_iter().map(Wave::from).collect();
+        let signals = self.dut()?.query()?;
+        let memories = signals
+            .iter()
+            .filter(|s| s.dimensions.len() == 1)
+            .map(|s| {
+                let element_width = s.width as usize / s.dimensions[0].size();
+                MemoryTrace::new(&s.name, element_width, s.dimensions[0])
+            })
+            .collect();
+        let waves: Vec<Wave> = signals
+            .into_iter()
+            .filter(|s| s.get)
+            .map(|s| {
+                let mut wave = Wave::from(s);
+                wave.type_info = self.type_info_of(&wave.signal_name);
+                wave
+            })
+            .collect();
+        let dut = self.dut()?;
+        let handles = waves
+            .iter()
+            .map(|w| dut.handle_of(&w.signal_name))
+            .collect::<OombakResult<Vec<SignalHandle>>>()?;
+        self.wave_handles = SignalHandles::new(handles);
         self.simulation_result.waves = waves;
+        self.simulation_result.memories = memories;
         Ok(())
     }
 
+    fn type_info_of(&self, path: &str) -> TypeInfo {
+        self.probe
+            .iter()
+            .flat_map(|probe| probe.get_probed_points().iter())
+            .find(|p| p.path() == path)
+            .map(|p| p.type_info().clone())
+            .unwrap_or_default()
+    }
+
     fn run(&mut self, duration: u64) -> OombakSimResult<u64> {
         let target_time = self.simulation_time + duration;
-        while self.simulation_time != target_time {
-            let curr_time = self.dut()?.run(duration)?;
-            self.append_new_values_to_simulation_result_until(curr_time)?;
-            self.simulation_time = curr_time;
+        while self.simulation_time < target_time {
+            self.run_timeslot(target_time)?;
         }
         Ok(self.simulation_time)
     }
 
+    fn run_until(
+        &mut self,
+        condition: &Condition,
+        max_duration: u64,
+    ) -> OombakSimResult<Breakpoint> {
+        self.check_signals_are_probed(condition)?;
+        let target_time = self.simulation_time + max_duration;
+        let mut previous_values = self.last_values();
+        let start_time = self.simulation_time;
+        while self.simulation_time < target_time {
+            // Checked both once the generators have driven the timeslot and once it has run, so
+            // that a clock edge stops the run at the time it happens. A run resumed from a
+            // breakpoint only stops there again if the generators changed a value.
+            self.start_timeslot()?;
+            let is_resumed =
+                self.simulation_time == start_time && self.last_values() == previous_values;
+            if !is_resumed {
+                if let Some(breakpoint) = self.check_breakpoint(condition, &mut previous_values)? {
+                    return Ok(breakpoint);
+                }
+            }
+            self.finish_timeslot(target_time)?;
+            if let Some(breakpoint) = self.check_breakpoint(condition, &mut previous_values)? {
+                self.record_memory_contents_at(self.simulation_time)?;
+                return Ok(breakpoint);
+            }
+        }
+        Ok(Breakpoint {
+            time: self.simulation_time,
+            condition: None,
+        })
+    }
+
+    /// Evaluates `condition` on the latest values against `previous_values`, which are then
+    /// replaced by the latest values.
+    fn check_breakpoint(
+        &self,
+        condition: &Condition,
+        previous_values: &mut Vec<Option<LogicVec>>,
+    ) -> OombakSimResult<Option<Breakpoint>> {
+        let current_values = self.last_values();
+        let values = TimeslotValues {
+            waves: &self.simulation_result.waves,
+            previous: previous_values,
+            current: &current_values,
+        };
+        let fired_term = condition.fired_term(&values)?;
+        *previous_values = current_values;
+        Ok(fired_term.map(|term| Breakpoint {
+            time: self.simulation_time,
+            condition: Some(term),
+        }))
+    }
+
+    fn run_cycles(&mut self, cycles: u64) -> OombakSimResult<u64> {
+        let period = self
+            .generators
+            .primary_period()
+            .ok_or(OombakSimError::InvalidGenerator(
+                "running cycles requires a clock source".to_string(),
+            ))?;
+        self.run(cycles * period)
+    }
+
+    /// Samples once the stimulus at the current time has settled, and again at the next
+    /// timeslot, which the DUT stops at if it comes before `target_time`. Every change is thus
+    /// recorded at the time it happens.
+    fn run_timeslot(&mut self, target_time: u64) -> OombakSimResult<()> {
+        self.start_timeslot()?;
+        self.finish_timeslot(target_time)
+    }
+
+    /// Memories are sampled here and at the end of a run only, since the next timeslot starts
+    /// at the time the previous one ended.
+    fn start_timeslot(&mut self) -> OombakSimResult<()> {
+        self.apply_generators()?;
+        self.record_values_at(self.simulation_time)?;
+        self.record_memory_contents_at(self.simulation_time)
+    }
+
+    fn finish_timeslot(&mut self, target_time: u64) -> OombakSimResult<()> {
+        let end_time = match self.generators.next_edge_after(self.simulation_time) {
+            Some(edge_time) => u64::min(edge_time, target_time),
+            None => target_time,
+        };
+        let curr_time = self.dut()?.run(end_time - self.simulation_time)?;
+        self.simulation_result.total_time += (curr_time - self.simulation_time) as usize;
+        for wave in self.simulation_result.waves.iter_mut() {
+            wave.extend_to(curr_time as usize);
+        }
+        self.simulation_time = curr_time;
+        self.record_values_at(curr_time)?;
+        if curr_time >= target_time {
+            self.record_memory_contents_at(curr_time)?;
+        }
+        self.history.record(Stimulus::RunTo(curr_time));
+        if self
+            .snapshots
+            .last()
+            .is_some_and(|s| curr_time >= s.time + self.snapshot_interval)
+        {
+            self.take_snapshot(self.history.stimuli().len())?;
+        }
+        Ok(())
+    }
+
+    /// Restores the latest snapshot taken before the target time, then replays the stimulus
+    /// recorded from there until the target time. Stimulus applied later is forgotten. If the
+    /// replay fails, the simulation is left as it was before running back.
+    fn run_back(&mut self, duration: u64) -> OombakSimResult<u64> {
+        self.check_no_timing("running back is")?;
+        let target_time = self.simulation_time.saturating_sub(duration);
+        let idx = self
+            .snapshots
+            .iter()
+            .rposition(|s| s.time <= target_time)
+            .ok_or(OombakSimError::NoSnapshot(target_time))?;
+        let rollback = RunBackRollback {
+            dut: self.dut()?.snapshot()?,
+            simulation_time: self.simulation_time,
+            simulation_result: self.simulation_result.clone(),
+            generators: self.generators.clone(),
+            history: self.history.clone(),
+            snapshots: self.snapshots.clone(),
+            snapshot_interval: self.snapshot_interval,
+        };
+        if let Err(e) = self.rewind(idx, target_time) {
+            self.simulation_time = rollback.simulation_time;
+            self.simulation_result = rollback.simulation_result;
+            self.generators = rollback.generators;
+            self.history = rollback.history;
+            self.snapshots = rollback.snapshots;
+            self.snapshot_interval = rollback.snapshot_interval;
+            self.dut()?.restore_snapshot(&rollback.dut)?;
+            return Err(e);
+        }
+        Ok(self.simulation_time)
+    }
+
+    fn rewind(&mut self, idx: usize, target_time: u64) -> OombakSimResult<()> {
+        self.snapshots.truncate(idx + 1);
+        let snapshot = &self.snapshots[idx];
+        self.dut()?.restore_snapshot(&snapshot.dut)?;
+        self.simulation_time = snapshot.time;
+        self.generators = snapshot.generators.clone();
+        self.simulation_result.forced_signals = snapshot.forced_signals.clone();
+        let history_len = snapshot.history_len;
+
+        let time = self.simulation_time as usize;
+        self.simulation_result.total_time = time;
+        for wave in self.simulation_result.waves.iter_mut() {
+            wave.truncate(time);
+        }
+        for memory in self.simulation_result.memories.iter_mut() {
+            memory.truncate(time);
+        }
+        self.record_values_at(self.simulation_time)?;
+
+        let stimuli = split_off_replay(&mut self.history, history_len, target_time);
+        self.replay(stimuli.stimuli())
+    }
+
+    fn take_snapshot(&mut self, history_len: usize) -> OombakSimResult<()> {
+        let snapshot = SimSnapshot {
+            time: self.simulation_time,
+            dut: self.dut()?.snapshot()?,
+            generators: self.generators.clone(),
+            forced_signals: self.simulation_result.forced_signals.clone(),
+            history_len,
+        };
+        self.snapshots.push(snapshot);
+        if self.snapshots.len() > MAX_SNAPSHOTS {
+            let mut idx = 0;
+            self.snapshots.retain(|_| {
+                idx += 1;
+                idx % 2 == 1
+            });
+            self.snapshot_interval *= 2;
+        }
+        Ok(())
+    }
+
+    /// Starts over from a single snapshot of the current state, reached by the first
+    /// `history_len` stimuli. No snapshot is taken of designs with timing constructs, see
+    /// `check_no_timing`.
+    fn reset_snapshots(&mut self, history_len: usize) -> OombakSimResult<()> {
+        self.snapshots.clear();
+        self.snapshot_interval = SNAPSHOT_INTERVAL;
+        if self.uses_timing() {
+            return Ok(());
+        }
+        self.take_snapshot(history_len)
+    }
+
+    /// Re-applies the recorded stimulus to a freshly loaded DUT, so that waves are rebuilt from
+    /// time 0. The whole history is kept if the replay fails part way.
+    fn replay_history(&mut self) -> OombakSimResult<()> {
+        let history = std::mem::take(&mut self.history);
+        if let Err(e) = self.replay(history.stimuli()) {
+            self.history = history;
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Applies `stimuli` from the current time, recording them again. Stimulus on signals which
+    /// are no longer probed is kept in the history but skipped.
+    fn replay(&mut self, stimuli: &[Stimulus]) -> OombakSimResult<()> {
+        let signal_names: Vec<String> = self.dut()?.query()?.into_iter().map(|s| s.name).collect();
+        let is_probed = |signal_name: &String| signal_names.contains(signal_name);
+        for stimulus in stimuli {
+            match stimulus {
+                Stimulus::SetSignal(signal_name, value) if is_probed(signal_name) => {
+                    self.set_signal(signal_name, value)?
+                }
+                Stimulus::Force(signal_name, value) if is_probed(signal_name) => {
+                    self.force(signal_name, value)?
+                }
+                Stimulus::Release(signal_name) if is_probed(signal_name) => {
+                    self.release(signal_name)?
+                }
+                Stimulus::Deposit(signal_name, value) if is_probed(signal_name) => {
+                    self.deposit(signal_name, value)?
+                }
+                Stimulus::AddClock(clock) if is_probed(&clock.signal_name) => {
+                    self.add_clock(clock.clone())?
+                }
+                Stimulus::AddReset(reset) if is_probed(&reset.signal_name) => {
+                    self.add_reset(reset.clone())?
+                }
+                Stimulus::RunTo(time) => {
+                    while self.simulation_time < *time {
+                        self.run_timeslot(*time)?;
+                    }
+                    self.history.record(Stimulus::RunTo(*time));
+                }
+                stimulus => self.history.record(stimulus.clone()),
+            }
+        }
+        Ok(())
+    }
+
+    fn check_signals_are_probed(&self, condition: &Condition) -> OombakSimResult<()> {
+        for signal_name in condition.signal_names() {
+            if !self
+                .simulation_result
+                .waves
+                .iter()
+                .any(|w| w.signal_name == signal_name)
+            {
+                return Err(OombakSimError::UnknownSignal(signal_name.to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    fn last_values(&self) -> Vec<Option<LogicVec>> {
+        self.simulation_result
+            .waves
+            .iter()
+            .map(|w| w.values.last().map(|(value, _, _)| value.clone()))
+            .collect()
+    }
+
     fn dut(&self) -> OombakSimResult<&Dut> {
         match &self.dut {
             Some(dut) => Ok(dut),
@@ -225,45 +948,117 @@ impl RequestServer {
         }
     }
 
-    fn append_new_values_to_simulation_result_until(
+    fn record_values_at(&mut self, time: u64) -> OombakSimResult<()> {
+        let dut = self.dut.as_ref().ok_or(OombakSimError::DutNotLoaded)?;
+        let new_values = dut.get_all(&mut self.wave_handles)?;
+        for (wave, new_value) in self.simulation_result.waves.iter_mut().zip(new_values) {
+            wave.record(time as usize, new_value);
+        }
+        Ok(())
+    }
+
+    fn record_memory_contents_at(&mut self, time: u64) -> OombakSimResult<()> {
+        let dut = self.dut.as_ref().ok_or(OombakSimError::DutNotLoaded)?;
+        for memory in self.simulation_result.memories.iter_mut() {
+            memory.sample(time as usize, |signal_name, words| {
+                dut.get_memory(signal_name, words)
+            })?;
+        }
+        Ok(())
+    }
+
+    fn add_clock(&mut self, clock: ClockSource) -> OombakSimResult<()> {
+        self.width_of_signal(&clock.signal_name)?;
+        self.generators.add_clock(clock.clone())?;
+        self.history.record(Stimulus::AddClock(clock));
+        self.apply_generators()
+    }
+
+    fn add_reset_literal(
         &mut self,
-        end_time: u64,
+        signal_name: &str,
+        active_value: &Literal,
+        cycles: u64,
     ) -> OombakSimResult<()> {
-        let new_values = self.query_new_values()?;
-        let duration = (end_time - self.simulation_time) as usize;
-        self.simulation_result.total_time += duration;
-        for (wave, new_value) in self
-            .simulation_result
-            .waves
-            .iter_mut()
-            .zip(new_values.into_iter())
-        {
-            if let Some((value, _start, count)) = wave.values.last_mut() {
-                if *value == new_value {
-                    *count += duration;
-                } else {
-                    wave.values
-                        .push((new_value, self.simulation_time as usize, duration));
-                }
-            } else {
-                wave.values
-                    .push((new_value, self.simulation_time as usize, duration));
-            }
+        let width = self.width_of_signal(signal_name)?;
+        self.add_reset(ResetSequence {
+            signal_name: signal_name.to_string(),
+            active_value: active_value.to_bitvec(width)?,
+            cycles,
+        })
+    }
+
+    fn add_reset(&mut self, reset: ResetSequence) -> OombakSimResult<()> {
+        let width = self.width_of_signal(&reset.signal_name)?;
+        self.generators
+            .add_reset(reset.clone(), width, self.simulation_time)?;
+        self.history.record(Stimulus::AddReset(reset));
+        self.apply_generators()
+    }
+
+    fn apply_generators(&self) -> OombakSimResult<()> {
+        for (signal_name, value) in self.generators.values_at(self.simulation_time) {
+            self.dut()?.set(signal_name, &value)?;
         }
         Ok(())
     }
 
-    fn query_new_values(&self) -> OombakSimResult<Vec<BitVec<u32>>> {
-        let mut new_values = vec![];
-        for signal_name in self.simulation_result.waves.iter().map(|w| &w.signal_name) {
-            let new_value = self.dut()?.get(signal_name)?;
-            new_values.push(new_value);
-        }
-        Ok(new_values)
+    fn width_of_signal(&self, signal_name: &str) -> OombakSimResult<usize> {
+        self.dut()?
+            .query()?
+            .into_iter()
+            .find(|s| s.name == signal_name)
+            .map(|s| s.width as usize)
+            .ok_or(OombakSimError::UnknownSignal(signal_name.to_string()))
+    }
+
+    fn set_signal_to_literal(&mut self, signal_name: &str, value: &Literal) -> OombakSimResult<()> {
+        let width = self.width_of_signal(signal_name)?;
+        self.set_signal(signal_name, &value.to_bitvec(width)?)
+    }
+
+    fn set_signal(&mut self, signal_name: &str, value: &BitVec<u32>) -> OombakSimResult<()> {
+        self.dut()?.set(signal_name, value)?;
+        self.history
+            .record(Stimulus::SetSignal(signal_name.to_string(), value.clone()));
+        Ok(())
+    }
+
+    fn force_to_literal(&mut self, signal_name: &str, value: &Literal) -> OombakSimResult<()> {
+        let width = self.width_of_signal(signal_name)?;
+        self.force(signal_name, &value.to_bitvec(width)?)
     }
 
-    fn set_signal(&self, signal_name: &str, value: &BitVec<u32>) -> OombakSimResult<()> {
-        Ok(self.dut()?.set(signal_name, value)?)
+    fn force(&mut self, signal_name: &str, value: &BitVec<u32>) -> OombakSimResult<()> {
+        self.dut()?.force(signal_name, value)?;
+        self.history
+            .record(Stimulus::Force(signal_name.to_string(), value.clone()));
+        self.simulation_result.set_forced(signal_name, true);
+        Ok(())
+    }
+
+    fn release(&mut self, signal_name: &str) -> OombakSimResult<()> {
+        self.dut()?.release(signal_name)?;
+        self.history
+            .record(Stimulus::Release(signal_name.to_string()));
+        self.simulation_result.set_forced(signal_name, false);
+        Ok(())
+    }
+
+    fn deposit_literal(&mut self, signal_name: &str, value: &Literal) -> OombakSimResult<()> {
+        let width = self.width_of_signal(signal_name)?;
+        self.deposit(signal_name, &value.to_bitvec(width)?)
+    }
+
+    /// Depositing on a forced signal would release it, so it is refused.
+    fn deposit(&mut self, signal_name: &str, value: &BitVec<u32>) -> OombakSimResult<()> {
+        if self.simulation_result.is_forced(signal_name) {
+            return Err(OombakSimError::SignalForced(signal_name.to_string()));
+        }
+        self.dut()?.deposit(signal_name, value)?;
+        self.history
+            .record(Stimulus::Deposit(signal_name.to_string(), value.clone()));
+        Ok(())
     }
 
     fn notify_listeners(&self, message: Response) {
@@ -281,25 +1076,133 @@ impl From<&Probe> for LoadedDut {
             .map(|p| p.path().to_string())
             .collect();
         let root_node = probe.root_node().clone();
+        let diagnostics = probe.diagnostics().to_vec();
         LoadedDut {
             probed_points,
             root_node,
+            diagnostics,
+        }
+    }
+}
+
+impl From<OombakSimError> for LoadError {
+    fn from(error: OombakSimError) -> Self {
+        let diagnostics = match &error {
+            OombakSimError::OombakGen(OombakGenError::Build(build_error)) => {
+                build_error.diagnostics.clone()
+            }
+            OombakSimError::OombakGen(OombakGenError::Oombak(OombakError::Parser(
+                parser::Error::Compilation(diagnostics),
+            ))) => diagnostics.clone(),
+            _ => vec![],
+        };
+        LoadError {
+            message: error.to_string(),
+            diagnostics,
+        }
+    }
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl From<&dump::Dump> for LoadedDut {
+    fn from(dump: &dump::Dump) -> Self {
+        let probed_points = dump
+            .simulation_result
+            .waves
+            .iter()
+            .map(|w| w.signal_name.clone())
+            .collect();
+        LoadedDut {
+            probed_points,
+            root_node: dump.root_node.clone(),
+            diagnostics: vec![],
         }
     }
 }
 
+struct TimeslotValues<'a> {
+    waves: &'a [Wave],
+    previous: &'a [Option<LogicVec>],
+    current: &'a [Option<LogicVec>],
+}
+
+impl TimeslotValues<'_> {
+    fn value_of<'a>(
+        &self,
+        values: &'a [Option<LogicVec>],
+        signal_name: &str,
+    ) -> Option<&'a LogicVec> {
+        let idx = self
+            .waves
+            .iter()
+            .position(|w| w.signal_name == signal_name)?;
+        values.get(idx)?.as_ref()
+    }
+}
+
+impl SignalValues for TimeslotValues<'_> {
+    fn current(&self, signal_name: &str) -> Option<&LogicVec> {
+        self.value_of(self.current, signal_name)
+    }
+
+    fn previous(&self, signal_name: &str) -> Option<&LogicVec> {
+        self.value_of(self.previous, signal_name)
+    }
+}
+
+/// Splits off the stimuli of `history` to replay from a snapshot reached by its first
+/// `history_len` stimuli, up to `target_time`. Stimuli applied later are dropped.
+fn split_off_replay(
+    history: &mut StimulusHistory,
+    history_len: usize,
+    target_time: u64,
+) -> StimulusHistory {
+    // The run which reached the snapshot is recorded last and may have gone on past it
+    let replay_from = match history_len.checked_sub(1) {
+        Some(i) if matches!(history.stimuli()[i], Stimulus::RunTo(_)) => i,
+        _ => history_len,
+    };
+    let mut stimuli = history.split_off(replay_from);
+    stimuli.truncate_at(target_time);
+    stimuli
+}
+
 #[derive(Clone, Default)]
 pub struct SimulationResult {
     pub waves: Vec<Wave>,
     pub time_step_ps: usize,
     pub total_time: usize,
+    /// Signals currently held by a `Force` request.
+    pub forced_signals: Vec<String>,
+    pub memories: Vec<MemoryTrace>,
+}
+
+impl SimulationResult {
+    pub fn is_forced(&self, signal_name: &str) -> bool {
+        self.forced_signals.iter().any(|s| s == signal_name)
+    }
+
+    fn set_forced(&mut self, signal_name: &str, forced: bool) {
+        if !forced {
+            self.forced_signals.retain(|s| s != signal_name);
+        } else if !self.is_forced(signal_name) {
+            self.forced_signals.push(signal_name.to_string());
+        }
+    }
 }
 
 #[derive(Clone)]
 pub struct Wave {
     pub signal_name: String,
     pub width: usize,
-    pub values: Vec<(BitVec<u32>, usize, usize)>,
+    pub values: Vec<(LogicVec, usize, usize)>,
+    /// `TypeInfo::Logic` for waves read from a dump, which do not describe types.
+    pub type_info: TypeInfo,
 }
 
 impl From<oombak_rs::dut::Signal> for Wave {
@@ -308,11 +1211,67 @@ impl From<oombak_rs::dut::Signal> for Wave {
             signal_name: signal.name,
             width: signal.width as usize,
             values: vec![],
+            type_info: TypeInfo::Logic,
         }
     }
 }
 
 impl Wave {
+    /// Wave of `field`, named `<signal_name>.<field name>`. Consecutive values which are equal
+    /// in the field are merged.
+    pub fn field(&self, field: &Field) -> Wave {
+        let mut values: Vec<(LogicVec, usize, usize)> = vec![];
+        for (value, start, duration) in self.values.iter() {
+            let mut field_value = LogicVec::default();
+            for logic in value.iter().skip(field.lsb).take(field.width) {
+                field_value.push(logic);
+            }
+            match values.last_mut() {
+                Some((last, last_start, last_duration))
+                    if *last == field_value && *last_start + *last_duration == *start =>
+                {
+                    *last_duration += duration;
+                }
+                _ => values.push((field_value, *start, *duration)),
+            }
+        }
+        Wave {
+            signal_name: format!("{}.{}", self.signal_name, field.name),
+            width: field.width,
+            values,
+            type_info: field.type_info.clone(),
+        }
+    }
+
+    /// Holds `value` from `time` on, replacing a value recorded at the same time, which an
+    /// earlier sample of the same timeslot left. A value is held for no time until the wave is
+    /// extended past it.
+    pub fn record(&mut self, time: usize, value: &LogicVec) {
+        if matches!(self.values.last(), Some((_, start, 0)) if *start == time) {
+            self.values.pop();
+        }
+        match self.values.last_mut() {
+            Some((last, start, count)) if last == value => *count = time - *start,
+            _ => self.values.push((value.clone(), time, 0)),
+        }
+    }
+
+    /// Holds the last value until `time`.
+    pub fn extend_to(&mut self, time: usize) {
+        if let Some((_, start, count)) = self.values.last_mut() {
+            *count = time - *start;
+        }
+    }
+
+    /// Forgets the values after `time`, leaving the value at `time` held for no time.
+    pub fn truncate(&mut self, time: usize) {
+        let end = self.values.partition_point(|(_, start, _)| *start <= time);
+        self.values.truncate(end);
+        if let Some((_, start, count)) = self.values.last_mut() {
+            *count = (*count).min(time - *start);
+        }
+    }
+
     pub fn value_idx_at(&self, time: usize) -> Option<(usize, usize)> {
         match self.values.binary_search_by(|v| (v.1).cmp(&time)) {
             Ok(idx) => Some((idx, 0)),
@@ -328,3 +1287,335 @@ impl Wave {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::{
+        path::{Path, PathBuf},
+        process::Command,
+        sync::{Arc, OnceLock, RwLock},
+    };
+
+    use oombak_rs::{
+        dut::{Dut, LogicVec},
+        parser::InstanceNode,
+        probe::Probe,
+    };
+
+    use bitvec::vec::BitVec;
+
+    use crate::{
+        checkpoint::Checkpoint,
+        clock::ClockSource,
+        condition::Condition,
+        error::OombakSimError,
+        history::{Stimulus, StimulusHistory},
+    };
+
+    use super::{split_off_replay, Field, RequestServer, SimulationResult, TypeInfo, Wave};
+
+    fn value(bits: &str) -> LogicVec {
+        LogicVec::from_ascii(bits.as_bytes()).unwrap()
+    }
+
+    /// Request server with a fresh instance of `tests/fixtures/fake_dut.c` loaded, which is
+    /// compiled once per test run.
+    fn server_with_fake_dut() -> RequestServer {
+        static LIB_PATH: OnceLock<PathBuf> = OnceLock::new();
+        let lib_path = LIB_PATH.get_or_init(|| {
+            let source_path =
+                Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/fake_dut.c");
+            let lib_path =
+                std::env::temp_dir().join(format!("oombak_fake_dut_{}.so", std::process::id()));
+            let status = Command::new("cc")
+                .args(["-shared", "-fPIC", "-o"])
+                .arg(&lib_path)
+                .arg(&source_path)
+                .status()
+                .unwrap();
+            assert!(status.success());
+            lib_path
+        });
+        let mut server = RequestServer::new(Arc::new(RwLock::new(vec![])));
+        server.dut = Some(Dut::new(lib_path.to_string_lossy().as_ref()).unwrap());
+        server.reload_simulation_result().unwrap();
+        server
+    }
+
+    #[test]
+    fn test_field() {
+        let wave = Wave {
+            signal_name: "entry".to_string(),
+            width: 4,
+            values: vec![
+                (value("0110"), 0, 2),
+                (value("1110"), 2, 1),
+                (value("1x01"), 3, 1),
+            ],
+            type_info: TypeInfo::Logic,
+        };
+        let field = Field {
+            name: "state".to_string(),
+            lsb: 1,
+            width: 2,
+            type_info: TypeInfo::Logic,
+        };
+        let field_wave = wave.field(&field);
+        assert_eq!(field_wave.signal_name, "entry.state");
+        assert_eq!(field_wave.width, 2);
+        assert_eq!(
+            field_wave.values,
+            vec![(value("11"), 0, 3), (value("x0"), 3, 1)]
+        );
+    }
+
+    #[test]
+    fn test_record() {
+        let mut wave = Wave {
+            signal_name: "out".to_string(),
+            width: 1,
+            values: vec![],
+            type_info: TypeInfo::Logic,
+        };
+        wave.record(0, &value("0"));
+        wave.extend_to(4);
+        wave.record(4, &value("1"));
+        assert_eq!(wave.values, vec![(value("0"), 0, 4), (value("1"), 4, 0)]);
+        assert_eq!(wave.value_idx_at(4), Some((1, 0)));
+
+        // A glitch back within the same timeslot leaves no trace
+        wave.record(4, &value("0"));
+        assert_eq!(wave.values, vec![(value("0"), 0, 4)]);
+
+        wave.record(4, &value("1"));
+        wave.extend_to(5);
+        wave.record(5, &value("1"));
+        wave.extend_to(7);
+        wave.record(7, &value("0"));
+        wave.extend_to(9);
+        assert_eq!(
+            wave.values,
+            vec![(value("0"), 0, 4), (value("1"), 4, 3), (value("0"), 7, 2)]
+        );
+    }
+
+    #[test]
+    fn test_truncate() {
+        let mut wave = Wave {
+            signal_name: "out".to_string(),
+            width: 1,
+            values: vec![(value("0"), 0, 4), (value("1"), 4, 3), (value("0"), 7, 2)],
+            type_info: TypeInfo::Logic,
+        };
+        wave.truncate(7);
+        assert_eq!(
+            wave.values,
+            vec![(value("0"), 0, 4), (value("1"), 4, 3), (value("0"), 7, 0)]
+        );
+        wave.truncate(6);
+        assert_eq!(wave.values, vec![(value("0"), 0, 4), (value("1"), 4, 2)]);
+        wave.truncate(5);
+        assert_eq!(wave.values, vec![(value("0"), 0, 4), (value("1"), 4, 1)]);
+        wave.truncate(4);
+        assert_eq!(wave.values, vec![(value("0"), 0, 4), (value("1"), 4, 0)]);
+    }
+
+    #[test]
+    fn test_set_forced() {
+        let mut simulation_result = SimulationResult::default();
+        simulation_result.set_forced("sample.c", true);
+        simulation_result.set_forced("sample.c", true);
+        simulation_result.set_forced("sample.d", true);
+        assert_eq!(
+            simulation_result.forced_signals,
+            vec!["sample.c", "sample.d"]
+        );
+        assert!(simulation_result.is_forced("sample.c"));
+
+        simulation_result.set_forced("sample.c", false);
+        assert_eq!(simulation_result.forced_signals, vec!["sample.d"]);
+        assert!(!simulation_result.is_forced("sample.c"));
+        simulation_result.set_forced("sample.c", false);
+        assert_eq!(simulation_result.forced_signals, vec!["sample.d"]);
+    }
+
+    fn checkpoint(build_key: &str, signal_names: &[&str]) -> Checkpoint {
+        Checkpoint {
+            build_key: build_key.to_string(),
+            time: 0,
+            total_time: 0,
+            time_step_ps: 1,
+            waves: signal_names
+                .iter()
+                .map(|name| (name.to_string(), vec![]))
+                .collect(),
+            stimuli: vec![],
+        }
+    }
+
+    #[test]
+    fn test_check_checkpoint() {
+        let mut server = RequestServer::new(Arc::new(RwLock::new(vec![])));
+        server.build_key = Some("abc".to_string());
+        server.simulation_result.waves = vec![Wave {
+            signal_name: "out".to_string(),
+            width: 1,
+            values: vec![],
+            type_info: TypeInfo::Logic,
+        }];
+        assert!(server
+            .check_checkpoint(&checkpoint("abc", &["out"]))
+            .is_ok());
+        assert!(matches!(
+            server.check_checkpoint(&checkpoint("abd", &["out"])),
+            Err(OombakSimError::InvalidCheckpoint(_))
+        ));
+        assert!(matches!(
+            server.check_checkpoint(&checkpoint("abc", &["in"])),
+            Err(OombakSimError::InvalidCheckpoint(_))
+        ));
+    }
+
+    #[test]
+    fn test_split_off_replay() {
+        let one = BitVec::<u32>::repeat(true, 1);
+        let zero = BitVec::<u32>::repeat(false, 1);
+        let mut history = StimulusHistory::default();
+        history.record(Stimulus::SetSignal("in".to_string(), one.clone()));
+        history.record(Stimulus::RunTo(1000));
+        // Snapshot taken at 1000, reached by the first 2 stimuli
+        history.record(Stimulus::RunTo(1500));
+        history.record(Stimulus::SetSignal("in".to_string(), zero.clone()));
+        history.record(Stimulus::RunTo(2000));
+
+        let mut kept = history.clone();
+        let stimuli = split_off_replay(&mut kept, 2, 1200);
+        assert_eq!(
+            kept.stimuli(),
+            &[Stimulus::SetSignal("in".to_string(), one.clone())]
+        );
+        assert_eq!(stimuli.stimuli(), &[Stimulus::RunTo(1200)]);
+
+        let mut kept = history.clone();
+        let stimuli = split_off_replay(&mut kept, 2, 1800);
+        assert_eq!(
+            kept.stimuli(),
+            &[Stimulus::SetSignal("in".to_string(), one.clone())]
+        );
+        assert_eq!(
+            stimuli.stimuli(),
+            &[
+                Stimulus::RunTo(1500),
+                Stimulus::SetSignal("in".to_string(), zero),
+                Stimulus::RunTo(1800),
+            ]
+        );
+
+        // Snapshot taken right after a stimulus, before any run
+        let mut kept = history.clone();
+        let stimuli = split_off_replay(&mut kept, 1, 0);
+        assert_eq!(
+            kept.stimuli(),
+            &[Stimulus::SetSignal("in".to_string(), one)]
+        );
+        assert!(stimuli.stimuli().is_empty());
+    }
+
+    #[test]
+    fn test_timing_designs() {
+        let mut root_node = InstanceNode {
+            name: "sample".to_string(),
+            module_name: "sample".to_string(),
+            children: vec![InstanceNode::default()],
+            ..Default::default()
+        };
+        root_node.children[0].uses_timing = true;
+        let mut server = RequestServer::new(Arc::new(RwLock::new(vec![])));
+        server.probe = Some(Probe::from_root_node(root_node).unwrap());
+        assert!(matches!(
+            server.save_checkpoint(Path::new("checkpoint")),
+            Err(OombakSimError::UsesTiming(_))
+        ));
+        assert!(matches!(
+            server.restore_checkpoint(Path::new("checkpoint")),
+            Err(OombakSimError::UsesTiming(_))
+        ));
+        assert!(matches!(
+            server.run_back(10),
+            Err(OombakSimError::UsesTiming(_))
+        ));
+        server.reset_snapshots(0).unwrap();
+        assert!(server.snapshots.is_empty());
+    }
+
+    #[test]
+    fn test_run_until_clock_edge() {
+        let mut server = server_with_fake_dut();
+        server
+            .add_clock(ClockSource {
+                phase: 3,
+                ..ClockSource::new("clk", 10)
+            })
+            .unwrap();
+        let rising = Condition::parse("rising(clk)").unwrap();
+        let falling = Condition::parse("falling(clk)").unwrap();
+        let breakpoint = server.run_until(&rising, 100).unwrap();
+        assert_eq!(breakpoint.time, 3);
+        assert_eq!(breakpoint.condition, Some("rising(clk)".to_string()));
+        assert_eq!(server.run_until(&falling, 100).unwrap().time, 8);
+        assert_eq!(server.run_until(&rising, 100).unwrap().time, 13);
+
+        // Counted on the edge at 23, so the register is read at the time it changes
+        let count = Condition::parse("count == 2").unwrap();
+        assert_eq!(server.run_until(&count, 100).unwrap().time, 23);
+
+        // Resumed past the breakpoint, where the condition still holds
+        assert_eq!(server.run_until(&count, 100).unwrap().time, 28);
+        let breakpoint = server.run_until(&rising, 4).unwrap();
+        assert_eq!(breakpoint.time, 32);
+        assert_eq!(breakpoint.condition, None);
+    }
+
+    #[test]
+    fn test_run_until_unknown_value() {
+        let mut server = server_with_fake_dut();
+        server
+            .add_clock(ClockSource {
+                phase: 10,
+                ..ClockSource::new("clk", 10)
+            })
+            .unwrap();
+        // The counter is X until the clock first rises at 10
+        server.run(5).unwrap();
+        let count = Condition::parse("count == 0").unwrap();
+        assert_eq!(server.run_until(&count, 100).unwrap().time, 10);
+    }
+
+    #[test]
+    fn test_record_memory_contents() {
+        let mut server = server_with_fake_dut();
+        server
+            .add_clock(ClockSource {
+                phase: 10,
+                ..ClockSource::new("clk", 10)
+            })
+            .unwrap();
+        server.run(25).unwrap();
+        let memory = &server.simulation_result.memories[0];
+        assert_eq!(memory.signal_name, "mem");
+        assert_eq!(memory.contents_at(9), vec![value("xxxxxxxx"); 4]);
+        // Written on the clock edges at 10 and 20
+        assert_eq!(memory.changed_at(10), vec![true, false, false, false]);
+        assert_eq!(memory.changed_at(15), vec![false; 4]);
+        assert_eq!(memory.changed_at(20), vec![false, true, false, false]);
+        assert_eq!(
+            memory.contents_at(25),
+            vec![
+                value("00000000"),
+                value("00000001"),
+                value("xxxxxxxx"),
+                value("xxxxxxxx"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,466 @@
+use std::{
+    collections::BTreeMap,
+    io::{BufRead, Write},
+};
+
+use oombak_rs::dut::{Logic, LogicVec};
+
+use crate::{
+    dump::{Dump, DumpBuilder},
+    error::{OombakSimError, OombakSimResult},
+    sim::{SimulationResult, Wave},
+};
+
+const TIME_UNITS: [(&str, usize); 5] = [
+    ("s", 1_000_000_000_000),
+    ("ms", 1_000_000_000),
+    ("us", 1_000_000),
+    ("ns", 1_000),
+    ("ps", 1),
+];
+
+/// Writes `simulation_result` as an IEEE 1364 Value Change Dump. Waves whose name contains no
+/// hierarchy separator (top level ports) are placed inside the `top_scope_name` scope.
+pub fn write<W: Write>(
+    writer: &mut W,
+    simulation_result: &SimulationResult,
+    top_scope_name: &str,
+) -> OombakSimResult<()> {
+    let (timescale, time_multiplier) = timescale_of(simulation_result.time_step_ps);
+    let identifiers: Vec<String> = (0..simulation_result.waves.len())
+        .map(identifier_of)
+        .collect();
+
+    writeln!(writer, "$version oombak $end")?;
+    writeln!(writer, "$timescale {timescale} $end")?;
+    let scope = Scope::new(&simulation_result.waves, &identifiers, top_scope_name);
+    scope.write_children(writer)?;
+    writeln!(writer, "$enddefinitions $end")?;
+
+    let changes = collect_changes(&simulation_result.waves);
+    for (time, indices) in changes.iter() {
+        writeln!(writer, "#{}", time * time_multiplier)?;
+        if *time == 0 {
+            writeln!(writer, "$dumpvars")?;
+        }
+        for (wave_idx, value_idx) in indices {
+            let wave = &simulation_result.waves[*wave_idx];
+            let value = &wave.values[*value_idx].0;
+            write_value_change(writer, value, wave.width, &identifiers[*wave_idx])?;
+        }
+        if *time == 0 {
+            writeln!(writer, "$end")?;
+        }
+    }
+    if simulation_result.total_time > 0 {
+        writeln!(
+            writer,
+            "#{}",
+            simulation_result.total_time * time_multiplier
+        )?;
+    }
+    Ok(())
+}
+
+/// Reads an IEEE 1364 Value Change Dump. Real valued variables are skipped.
+pub fn read<R: BufRead>(mut reader: R) -> OombakSimResult<Dump> {
+    let mut content = String::new();
+    reader.read_to_string(&mut content)?;
+    let mut tokens = content.split_whitespace();
+    let mut builder = DumpBuilder::new();
+    while let Some(token) = tokens.next() {
+        match token {
+            "$timescale" => {
+                let timescale = collect_until_end(&mut tokens)?.concat();
+                builder.set_time_step_ps(parse_timescale(&timescale)?);
+            }
+            "$scope" => {
+                let args = collect_until_end(&mut tokens)?;
+                let name = args.get(1).ok_or(invalid_command("$scope"))?;
+                builder.push_scope(name, name);
+            }
+            "$upscope" => {
+                collect_until_end(&mut tokens)?;
+                builder.pop_scope();
+            }
+            "$var" => {
+                let args = collect_until_end(&mut tokens)?;
+                if args.len() < 4 {
+                    return Err(invalid_command("$var"));
+                }
+                if args[0] != "real" {
+                    let width = args[1].parse().map_err(|_| invalid_command("$var"))?;
+                    builder.add_var(args[2], args[3], width, None);
+                }
+            }
+            "$comment" | "$date" | "$version" | "$enddefinitions" => {
+                collect_until_end(&mut tokens)?;
+            }
+            "$dumpvars" | "$dumpall" | "$dumpon" | "$dumpoff" | "$end" => (),
+            _ => read_simulation_token(&mut builder, token, &mut tokens)?,
+        }
+    }
+    Ok(builder.finish())
+}
+
+fn read_simulation_token<'a>(
+    builder: &mut DumpBuilder,
+    token: &'a str,
+    tokens: &mut impl Iterator<Item = &'a str>,
+) -> OombakSimResult<()> {
+    let head_len = token.chars().next().map_or(0, char::len_utf8);
+    let (head, tail) = token.split_at(head_len);
+    match head {
+        "#" => {
+            let time = tail.parse().map_err(|_| invalid_command(token))?;
+            builder.set_time(time)
+        }
+        "0" | "1" | "x" | "X" | "z" | "Z" => builder.change(tail, head.as_bytes()),
+        "b" | "B" => {
+            let id = tokens.next().ok_or(invalid_command(token))?;
+            builder.change(id, tail.as_bytes())
+        }
+        "r" | "R" => {
+            tokens.next().ok_or(invalid_command(token))?;
+            Ok(())
+        }
+        _ => Err(invalid_command(token)),
+    }
+}
+
+fn collect_until_end<'a>(
+    tokens: &mut impl Iterator<Item = &'a str>,
+) -> OombakSimResult<Vec<&'a str>> {
+    let mut args = vec![];
+    for token in tokens.by_ref() {
+        if token == "$end" {
+            return Ok(args);
+        }
+        args.push(token);
+    }
+    Err(OombakSimError::InvalidDump(
+        "unexpected end of file, expecting $end".to_string(),
+    ))
+}
+
+fn parse_timescale(timescale: &str) -> OombakSimResult<usize> {
+    let unit_start = timescale
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or(invalid_command("$timescale"))?;
+    let (number, unit) = timescale.split_at(unit_start);
+    let number: usize = number.parse().map_err(|_| invalid_command("$timescale"))?;
+    TIME_UNITS
+        .iter()
+        .find(|(u, _)| *u == unit)
+        .map(|(_, unit_ps)| number * unit_ps)
+        .ok_or(OombakSimError::InvalidDump(format!(
+            "unsupported timescale {timescale}"
+        )))
+}
+
+fn invalid_command(command: &str) -> OombakSimError {
+    OombakSimError::InvalidDump(format!("invalid {command} command"))
+}
+
+struct Scope<'a> {
+    name: &'a str,
+    vars: Vec<(&'a str, usize, &'a str)>,
+    children: Vec<Scope<'a>>,
+}
+
+impl<'a> Scope<'a> {
+    fn new(waves: &'a [Wave], identifiers: &'a [String], top_scope_name: &'a str) -> Self {
+        let mut root = Scope::with_name("");
+        for (wave, identifier) in waves.iter().zip(identifiers.iter()) {
+            let mut path: Vec<&str> = wave.signal_name.split('.').collect();
+            let var_name = path.pop().expect("split must yield at least one element");
+            if path.is_empty() {
+                path.push(top_scope_name);
+            }
+            let scope = path
+                .into_iter()
+                .fold(&mut root, |scope, name| scope.child_mut(name));
+            scope.vars.push((var_name, wave.width, identifier));
+        }
+        root
+    }
+
+    fn with_name(name: &'a str) -> Self {
+        Scope {
+            name,
+            vars: vec![],
+            children: vec![],
+        }
+    }
+
+    fn child_mut(&mut self, name: &'a str) -> &mut Scope<'a> {
+        let idx = match self.children.iter().position(|c| c.name == name) {
+            Some(idx) => idx,
+            None => {
+                self.children.push(Scope::with_name(name));
+                self.children.len() - 1
+            }
+        };
+        &mut self.children[idx]
+    }
+
+    fn write<W: Write>(&self, writer: &mut W) -> OombakSimResult<()> {
+        writeln!(writer, "$scope module {} $end", self.name)?;
+        for (name, width, identifier) in self.vars.iter() {
+            if *width > 1 {
+                writeln!(
+                    writer,
+                    "$var wire {width} {identifier} {name} [{}:0] $end",
+                    width - 1
+                )?;
+            } else {
+                writeln!(writer, "$var wire {width} {identifier} {name} $end")?;
+            }
+        }
+        self.write_children(writer)?;
+        writeln!(writer, "$upscope $end")?;
+        Ok(())
+    }
+
+    fn write_children<W: Write>(&self, writer: &mut W) -> OombakSimResult<()> {
+        for child in self.children.iter() {
+            child.write(writer)?;
+        }
+        Ok(())
+    }
+}
+
+fn collect_changes(waves: &[Wave]) -> BTreeMap<usize, Vec<(usize, usize)>> {
+    let mut changes: BTreeMap<usize, Vec<(usize, usize)>> = BTreeMap::new();
+    for (wave_idx, wave) in waves.iter().enumerate() {
+        for (value_idx, (_, start, _)) in wave.values.iter().enumerate() {
+            changes
+                .entry(*start)
+                .or_default()
+                .push((wave_idx, value_idx));
+        }
+    }
+    changes
+}
+
+fn write_value_change<W: Write>(
+    writer: &mut W,
+    value: &LogicVec,
+    width: usize,
+    identifier: &str,
+) -> OombakSimResult<()> {
+    let bits = String::from_iter((0..width).rev().map(|i| match value.get(i) {
+        Some(Logic::One) => '1',
+        Some(Logic::X) => 'x',
+        Some(Logic::Z) => 'z',
+        _ => '0',
+    }));
+    if width == 1 {
+        writeln!(writer, "{bits}{identifier}")?;
+    } else {
+        writeln!(writer, "b{bits} {identifier}")?;
+    }
+    Ok(())
+}
+
+fn timescale_of(time_step_ps: usize) -> (String, usize) {
+    let time_step_ps = usize::max(time_step_ps, 1);
+    for (unit, unit_ps) in TIME_UNITS {
+        if time_step_ps.is_multiple_of(unit_ps) && matches!(time_step_ps / unit_ps, 1 | 10 | 100) {
+            return (format!("{}{unit}", time_step_ps / unit_ps), 1);
+        }
+    }
+    ("1ps".to_string(), time_step_ps)
+}
+
+fn identifier_of(idx: usize) -> String {
+    const FIRST: u8 = b'!';
+    const RADIX: usize = (b'~' - b'!' + 1) as usize;
+    let mut idx = idx;
+    let mut identifier = String::new();
+    loop {
+        identifier.push((FIRST + (idx % RADIX) as u8) as char);
+        idx /= RADIX;
+        if idx == 0 {
+            break identifier;
+        }
+        idx -= 1;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bitvec::{bitvec, order::Lsb0};
+    use oombak_rs::dut::LogicVec;
+
+    use crate::sim::{SimulationResult, TypeInfo, Wave};
+
+    use super::{identifier_of, read, timescale_of, write};
+
+    #[test]
+    fn test_write() {
+        let simulation_result = SimulationResult {
+            waves: vec![
+                Wave {
+                    signal_name: "clk".to_string(),
+                    width: 1,
+                    values: vec![
+                        (LogicVec::from(bitvec![u32, Lsb0; 0]), 0, 1),
+                        (LogicVec::from(bitvec![u32, Lsb0; 1]), 1, 1),
+                    ],
+                    type_info: TypeInfo::Logic,
+                },
+                Wave {
+                    signal_name: "sample.adder_inst.c".to_string(),
+                    width: 3,
+                    values: vec![(LogicVec::from(bitvec![u32, Lsb0; 1, 1, 0]), 0, 2)],
+                    type_info: TypeInfo::Logic,
+                },
+            ],
+            time_step_ps: 1000,
+            total_time: 2,
+            forced_signals: vec![],
+            memories: vec![],
+        };
+
+        let mut output = vec![];
+        write(&mut output, &simulation_result, "sample").unwrap();
+
+        let expected = concat!(
+            "$version oombak $end\n",
+            "$timescale 1ns $end\n",
+            "$scope module sample $end\n",
+            "$var wire 1 ! clk $end\n",
+            "$scope module adder_inst $end\n",
+            "$var wire 3 \" c [2:0] $end\n",
+            "$upscope $end\n",
+            "$upscope $end\n",
+            "$enddefinitions $end\n",
+            "#0\n",
+            "$dumpvars\n",
+            "0!\n",
+            "b011 \"\n",
+            "$end\n",
+            "#1\n",
+            "1!\n",
+            "#2\n",
+        );
+        assert_eq!(String::from_utf8(output).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_read() {
+        let vcd = concat!(
+            "$timescale 10 ns $end\n",
+            "$scope module top $end\n",
+            "$var wire 1 ! clk $end\n",
+            "$scope module sub $end\n",
+            "$var wire 4 \" data [3:0] $end\n",
+            "$upscope $end\n",
+            "$upscope $end\n",
+            "$enddefinitions $end\n",
+            "#0\n",
+            "$dumpvars\n",
+            "0!\n",
+            "b1 \"\n",
+            "$end\n",
+            "#5\n",
+            "1!\n",
+            "#7\n",
+            "b1100 \"\n",
+            "#10\n",
+            "x!\n",
+            "bz1 \"\n",
+            "#12\n",
+        );
+
+        let dump = read(vcd.as_bytes()).unwrap();
+
+        assert_eq!(dump.root_node.name, "top");
+        assert_eq!(dump.root_node.children[0].name, "sub");
+        let simulation_result = dump.simulation_result;
+        assert_eq!(simulation_result.time_step_ps, 10_000);
+        assert_eq!(simulation_result.total_time, 12);
+        let clk = &simulation_result.waves[0];
+        assert_eq!(clk.signal_name, "top.clk");
+        assert_eq!(
+            clk.values,
+            vec![
+                (LogicVec::from(bitvec![u32, Lsb0; 0]), 0, 5),
+                (LogicVec::from(bitvec![u32, Lsb0; 1]), 5, 5),
+                (LogicVec::from_ascii(b"x").unwrap(), 10, 2)
+            ]
+        );
+        let data = &simulation_result.waves[1];
+        assert_eq!(data.signal_name, "top.sub.data");
+        assert_eq!(data.width, 4);
+        assert_eq!(
+            data.values,
+            vec![
+                (LogicVec::from(bitvec![u32, Lsb0; 1, 0, 0, 0]), 0, 7),
+                (LogicVec::from(bitvec![u32, Lsb0; 0, 0, 1, 1]), 7, 3),
+                (LogicVec::from_ascii(b"zzz1").unwrap(), 10, 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_invalid_token() {
+        let vcd = concat!(
+            "$scope module top $end\n",
+            "$var wire 1 ! clk $end\n",
+            "$upscope $end\n",
+            "$enddefinitions $end\n",
+            "#0\n",
+            "\u{e9}!\n",
+        );
+        assert!(read(vcd.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_write_read_roundtrip() {
+        let simulation_result = SimulationResult {
+            waves: vec![Wave {
+                signal_name: "in".to_string(),
+                width: 2,
+                values: vec![
+                    (LogicVec::from(bitvec![u32, Lsb0; 0, 1]), 0, 3),
+                    (LogicVec::from(bitvec![u32, Lsb0; 1, 1]), 3, 1),
+                    (LogicVec::from_ascii(b"xz").unwrap(), 4, 2),
+                ],
+                type_info: TypeInfo::Logic,
+            }],
+            time_step_ps: 1,
+            total_time: 6,
+            forced_signals: vec![],
+            memories: vec![],
+        };
+
+        let mut output = vec![];
+        write(&mut output, &simulation_result, "sample").unwrap();
+        let dump = read(output.as_slice()).unwrap();
+
+        assert_eq!(dump.simulation_result.total_time, 6);
+        assert_eq!(dump.simulation_result.waves[0].signal_name, "sample.in");
+        assert_eq!(
+            dump.simulation_result.waves[0].values,
+            simulation_result.waves[0].values
+        );
+    }
+
+    #[test]
+    fn test_timescale() {
+        assert_eq!(timescale_of(0), ("1ps".to_string(), 1));
+        assert_eq!(timescale_of(100), ("100ps".to_string(), 1));
+        assert_eq!(timescale_of(10_000), ("10ns".to_string(), 1));
+        assert_eq!(timescale_of(2_000), ("1ps".to_string(), 2_000));
+    }
+
+    #[test]
+    fn test_identifier() {
+        assert_eq!(identifier_of(0), "!");
+        assert_eq!(identifier_of(93), "~");
+        assert_eq!(identifier_of(94), "!!");
+        assert_eq!(identifier_of(95), "\"!");
+    }
+}
@@ -0,0 +1,119 @@
+// Stand-in for a generated DUT library, built by the request server tests so
+// that they run without Verilator. It has a clock input `clk` and a counter
+// `count` of its rising edges, which is unknown until the first one. Each edge
+// also writes the count into `mem[count % 4]`. Like the generated library, a
+// set is evaluated right away.
+
+#include <stdint.h>
+#include <stdlib.h>
+#include <string.h>
+
+typedef struct {
+  int64_t left;
+  int64_t right;
+} dim_t;
+
+typedef struct {
+  const char *name;
+  uint64_t width;
+  uint8_t get;
+  uint8_t set;
+  uint8_t force;
+  uint8_t set_element;
+  const dim_t *dimensions;
+  uint64_t dimensions_len;
+} signal_t;
+
+typedef struct {
+  uint64_t time;
+  uint32_t clk;
+  uint32_t count;
+  int is_count_known;
+  uint32_t mem[4];
+  int is_mem_known[4];
+} dut_ctx_t;
+
+enum { CLK, COUNT, MEM, NUM_OF_SIGNALS };
+
+static dim_t mem_dimensions[] = {{0, 3}};
+
+static signal_t signals[NUM_OF_SIGNALS] = {
+    {"clk", 1, 1, 1, 0, 0, NULL, 0},
+    {"count", 8, 1, 0, 0, 0, NULL, 0},
+    {"mem", 8 * 4, 0, 0, 0, 0, mem_dimensions, 1},
+};
+
+dut_ctx_t *create() { return calloc(1, sizeof(dut_ctx_t)); }
+
+void destroy(dut_ctx_t *ctx) { free(ctx); }
+
+signal_t *query(dut_ctx_t *ctx, uint64_t *num_of_signals) {
+  *num_of_signals = NUM_OF_SIGNALS;
+  return signals;
+}
+
+int set(dut_ctx_t *ctx, char *sig_name, uint32_t *words,
+        uint64_t num_of_words) {
+  if (strcmp(sig_name, "clk") != 0 || num_of_words < 1) {
+    return -1;
+  }
+  uint32_t clk = words[0] & 1;
+  if (clk && !ctx->clk) {
+    ctx->count = ctx->is_count_known ? (ctx->count + 1) & 0xff : 0;
+    ctx->is_count_known = 1;
+    ctx->mem[ctx->count % 4] = ctx->count;
+    ctx->is_mem_known[ctx->count % 4] = 1;
+  }
+  ctx->clk = clk;
+  return 0;
+}
+
+int64_t handle_of(dut_ctx_t *ctx, char *sig_name, uint64_t *n_bits) {
+  for (int64_t i = 0; i < NUM_OF_SIGNALS; i++) {
+    if (strcmp(sig_name, signals[i].name) == 0 && signals[i].get) {
+      *n_bits = signals[i].width;
+      return i;
+    }
+  }
+  return -1;
+}
+
+int get_all(dut_ctx_t *ctx, uint64_t *handles, uint64_t num_of_handles,
+            uint32_t *words, uint64_t num_of_words) {
+  if (num_of_words < 2 * num_of_handles) {
+    return -1;
+  }
+  for (uint64_t i = 0; i < num_of_handles; i++) {
+    switch (handles[i]) {
+    case CLK:
+      words[2 * i] = ctx->clk;
+      words[2 * i + 1] = 0;
+      break;
+    case COUNT:
+      words[2 * i] = ctx->is_count_known ? ctx->count : 0xff;
+      words[2 * i + 1] = ctx->is_count_known ? 0 : 0xff;
+      break;
+    default:
+      return -1;
+    }
+  }
+  return 0;
+}
+
+int get_memory(dut_ctx_t *ctx, char *sig_name, uint32_t *words,
+               uint64_t num_of_words) {
+  if (strcmp(sig_name, "mem") != 0 || num_of_words < 2 * 4) {
+    return -1;
+  }
+  for (int i = 0; i < 4; i++) {
+    words[2 * i] = ctx->is_mem_known[i] ? ctx->mem[i] : 0xff;
+    words[2 * i + 1] = ctx->is_mem_known[i] ? 0 : 0xff;
+  }
+  return 0;
+}
+
+int run(dut_ctx_t *ctx, uint64_t duration, uint64_t *current_time) {
+  ctx->time += duration;
+  *current_time = ctx->time;
+  return 0;
+}
@@ -1,13 +1,38 @@
-use std::path::PathBuf;
+use std::{path::PathBuf, time::Duration};
 
-use bitvec::vec::BitVec;
+use bitvec::field::BitField;
+
+use oombak_sim::{clock::ClockSource, condition::Condition, literal::Literal, sim::SourceConfig};
 
 use crate::utils::bitvec_str;
 
 pub enum Command {
     Run(u64),
-    Load(PathBuf),
-    Set(String, BitVec<u32>),
+    RunBack(u64),
+    RunUntil(Condition, u64),
+    RunCycles(u64),
+    Load(SourceConfig),
+    Open(PathBuf),
+    Set(String, Literal),
+    Force(String, Literal),
+    Release(String),
+    Deposit(String, Literal),
+    ExportVcd(PathBuf),
+    SaveCheckpoint(PathBuf),
+    RestoreCheckpoint(PathBuf),
+    Clock(ClockSource),
+    /// Signal name, active value and number of cycles.
+    Reset(String, Literal, u64),
+    ListCache,
+    PruneCache(Duration),
+    ClearCache,
+    /// Signal name, format, and whether values are signed if the format sets it.
+    Format(String, bitvec_str::Format, Option<bool>),
+    /// Memory to show in the memory view.
+    Memory(String),
+    Goto(i64),
+    MemoryAddressFormat(bitvec_str::Format),
+    MemoryDataFormat(bitvec_str::Format, Option<bool>),
     Noop,
 }
 
@@ -20,12 +45,34 @@ pub fn interpret(command_string: &str) -> Result<Command, String> {
     match command[0] {
         "run" => parse_run(args),
         "load" => parse_load(args),
+        "open" => parse_open(args),
         "set" => parse_set(args),
+        "force" => parse_force(args),
+        "release" => parse_release(args),
+        "deposit" => parse_deposit(args),
+        "export" => parse_export(args),
+        "checkpoint" => parse_checkpoint(args),
+        "clock" => parse_clock(args),
+        "reset" => parse_reset(args),
+        "cache" => parse_cache(args),
+        "format" => parse_format(args),
+        "memory" => parse_memory(args),
+        "goto" => parse_goto(args),
+        "memfmt" => parse_memfmt(args),
         _ => Err(format!("unknown command \"{}\"", command[0])),
     }
 }
 
 fn parse_run(args: &[&str]) -> Result<Command, String> {
+    if args.len() > 2 && args[1] == "until" {
+        return parse_run_until(args);
+    }
+    if args.len() == 2 && args[0] == "back" {
+        return parse_u64(args[1]).map(Command::RunBack);
+    }
+    if args.len() == 2 && args[1] == "cycles" {
+        return parse_u64(args[0]).map(Command::RunCycles);
+    }
     if args.len() != 1 {
         return Err("expected 1 argument (duration: u64)".to_string());
     }
@@ -36,11 +83,34 @@ fn parse_run(args: &[&str]) -> Result<Command, String> {
     }
 }
 
+fn parse_run_until(args: &[&str]) -> Result<Command, String> {
+    let Ok(max_duration) = args[0].parse() else {
+        return Err(format!("cannot parse {} as u64", args[0]));
+    };
+    match Condition::parse(&args[2..].join(" ")) {
+        Ok(condition) => Ok(Command::RunUntil(condition, max_duration)),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
 fn parse_load(args: &[&str]) -> Result<Command, String> {
+    if args.is_empty() {
+        return Err(
+            "expected source files, optionally with -top <name>, -f <filelist>, -y <dir> or -G<name>=<value>"
+                .to_string(),
+        );
+    }
+    match SourceConfig::from_args(args) {
+        Ok(source_config) => Ok(Command::Load(source_config)),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+fn parse_open(args: &[&str]) -> Result<Command, String> {
     if args.len() != 1 {
-        Err("expected 1 argument (sv_path: String)".to_string())
+        Err("expected 1 argument (dump_path: String)".to_string())
     } else {
-        Ok(Command::Load(PathBuf::from(args[0])))
+        Ok(Command::Open(PathBuf::from(args[0])))
     }
 }
 
@@ -48,8 +118,207 @@ fn parse_set(args: &[&str]) -> Result<Command, String> {
     if args.len() != 2 {
         return Err("expected 2 argument (sigal_name: String, value: String)".to_string());
     }
-    match bitvec_str::parse(args[1]) {
+    match Literal::parse(args[1]) {
         Ok(value) => Ok(Command::Set(args[0].to_string(), value)),
-        Err(e) => Err(e),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+fn parse_force(args: &[&str]) -> Result<Command, String> {
+    if args.len() != 2 {
+        return Err("expected 2 arguments (signal_name: String, value: String)".to_string());
+    }
+    match Literal::parse(args[1]) {
+        Ok(value) => Ok(Command::Force(args[0].to_string(), value)),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+fn parse_release(args: &[&str]) -> Result<Command, String> {
+    if args.len() != 1 {
+        return Err("expected 1 argument (signal_name: String)".to_string());
+    }
+    Ok(Command::Release(args[0].to_string()))
+}
+
+fn parse_deposit(args: &[&str]) -> Result<Command, String> {
+    if args.len() != 2 {
+        return Err("expected 2 arguments (signal_name: String, value: String)".to_string());
+    }
+    match Literal::parse(args[1]) {
+        Ok(value) => Ok(Command::Deposit(args[0].to_string(), value)),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+fn parse_export(args: &[&str]) -> Result<Command, String> {
+    if args.len() != 2 {
+        return Err("expected 2 argument (format: String, file_path: String)".to_string());
+    }
+    match args[0] {
+        "vcd" => Ok(Command::ExportVcd(PathBuf::from(args[1]))),
+        format => Err(format!("unknown export format \"{format}\"")),
+    }
+}
+
+fn parse_checkpoint(args: &[&str]) -> Result<Command, String> {
+    match args {
+        ["save", path] => Ok(Command::SaveCheckpoint(PathBuf::from(path))),
+        ["restore", path] => Ok(Command::RestoreCheckpoint(PathBuf::from(path))),
+        _ => Err("expected \"save <path>\" or \"restore <path>\"".to_string()),
+    }
+}
+
+fn parse_format(args: &[&str]) -> Result<Command, String> {
+    if args.len() != 2 {
+        return Err("expected 2 arguments (signal_name: String, format: String)".to_string());
+    }
+    let (format, signed) = parse_format_name(args[1])?;
+    Ok(Command::Format(args[0].to_string(), format, signed))
+}
+
+fn parse_format_name(name: &str) -> Result<(bitvec_str::Format, Option<bool>), String> {
+    match name {
+        "bin" => Ok((bitvec_str::Format::Binary, None)),
+        "oct" => Ok((bitvec_str::Format::Octal, None)),
+        "hex" => Ok((bitvec_str::Format::Hexadecimal, None)),
+        "dec" => Ok((bitvec_str::Format::Decimal, None)),
+        "udec" => Ok((bitvec_str::Format::Decimal, Some(false))),
+        "sdec" => Ok((bitvec_str::Format::Decimal, Some(true))),
+        "ascii" => Ok((bitvec_str::Format::Ascii, None)),
+        format => Err(format!(
+            "unknown format \"{format}\", expected one of bin, oct, hex, dec, udec, sdec, ascii"
+        )),
+    }
+}
+
+fn parse_memory(args: &[&str]) -> Result<Command, String> {
+    if args.len() != 1 {
+        return Err("expected 1 argument (signal_name: String)".to_string());
+    }
+    Ok(Command::Memory(args[0].to_string()))
+}
+
+fn parse_goto(args: &[&str]) -> Result<Command, String> {
+    if args.len() != 1 {
+        return Err("expected 1 argument (address: String)".to_string());
+    }
+    let address = Literal::parse(args[0])
+        .and_then(|literal| literal.to_bitvec(64))
+        .map_err(|e| e.to_string())?;
+    Ok(Command::Goto(address.load_le::<u64>() as i64))
+}
+
+fn parse_memfmt(args: &[&str]) -> Result<Command, String> {
+    match args {
+        ["addr", format] => match parse_format_name(format)? {
+            (format, None | Some(false)) => Ok(Command::MemoryAddressFormat(format)),
+            (_, Some(true)) => Err("addresses cannot be signed".to_string()),
+        },
+        ["data", format] => {
+            let (format, signed) = parse_format_name(format)?;
+            Ok(Command::MemoryDataFormat(format, signed))
+        }
+        _ => Err("expected \"addr <format>\" or \"data <format>\"".to_string()),
+    }
+}
+
+fn parse_clock(args: &[&str]) -> Result<Command, String> {
+    if args.len() < 2 || args.len() > 4 {
+        return Err(
+            "expected 2 to 4 arguments (signal_name: String, period: u64, duty_cycle: u64, phase: u64)"
+                .to_string(),
+        );
+    }
+    let mut clock = ClockSource::new(args[0], parse_u64(args[1])?);
+    if let Some(duty_cycle) = args.get(2) {
+        clock.duty_cycle = parse_u64(duty_cycle)?;
+    }
+    if let Some(phase) = args.get(3) {
+        clock.phase = parse_u64(phase)?;
+    }
+    Ok(Command::Clock(clock))
+}
+
+fn parse_reset(args: &[&str]) -> Result<Command, String> {
+    if args.len() != 3 {
+        return Err(
+            "expected 3 arguments (signal_name: String, active_value: String, cycles: u64)"
+                .to_string(),
+        );
+    }
+    let active_value = Literal::parse(args[1]).map_err(|e| e.to_string())?;
+    Ok(Command::Reset(
+        args[0].to_string(),
+        active_value,
+        parse_u64(args[2])?,
+    ))
+}
+
+fn parse_cache(args: &[&str]) -> Result<Command, String> {
+    match args {
+        ["list"] => Ok(Command::ListCache),
+        ["prune", days] => {
+            let secs = parse_u64(days)?
+                .checked_mul(24 * 60 * 60)
+                .ok_or_else(|| format!("{days} days is too long"))?;
+            Ok(Command::PruneCache(Duration::from_secs(secs)))
+        }
+        ["clear"] => Ok(Command::ClearCache),
+        _ => Err("expected \"list\", \"prune <days: u64>\" or \"clear\"".to_string()),
+    }
+}
+
+fn parse_u64(arg: &str) -> Result<u64, String> {
+    arg.parse()
+        .map_err(|_| format!("cannot parse {arg} as u64"))
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use oombak_sim::literal::Literal;
+
+    use super::{interpret, Command};
+
+    #[test]
+    fn test_interpret_force() {
+        let expected = Literal::parse("8'hff").unwrap();
+        assert!(matches!(
+            interpret("force sample.c 8'hff"),
+            Ok(Command::Force(name, value)) if name == "sample.c" && value == expected
+        ));
+        assert!(interpret("force sample.c").is_err());
+        assert!(interpret("force sample.c 8'hzz").is_err());
+    }
+
+    #[test]
+    fn test_interpret_release() {
+        assert!(matches!(
+            interpret("release sample.c"),
+            Ok(Command::Release(name)) if name == "sample.c"
+        ));
+        assert!(interpret("release").is_err());
+        assert!(interpret("release sample.c sample.d").is_err());
+    }
+
+    #[test]
+    fn test_interpret_deposit() {
+        let expected = Literal::parse("3").unwrap();
+        assert!(matches!(
+            interpret("deposit sample.c 3"),
+            Ok(Command::Deposit(name, value)) if name == "sample.c" && value == expected
+        ));
+        assert!(interpret("deposit sample.c").is_err());
+    }
+
+    #[test]
+    fn test_interpret_cache_prune() {
+        assert!(matches!(
+            interpret("cache prune 2"),
+            Ok(Command::PruneCache(age)) if age == Duration::from_secs(2 * 24 * 60 * 60)
+        ));
+        assert!(interpret("cache prune 213503982334602").is_err());
     }
 }
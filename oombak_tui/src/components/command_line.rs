@@ -7,6 +7,7 @@ use crate::{
     backend::interpreter,
     component::{Component, HandleResult},
     render::Message,
+    utils::bitvec_str,
 };
 
 use oombak_sim::sim;
@@ -17,6 +18,23 @@ pub struct CommandLine {
     text: String,
     result_history: Vec<Result<String, String>>,
     state: State,
+    format_changes: Vec<FormatChange>,
+    memory_view_changes: Vec<MemoryViewChange>,
+}
+
+/// Display format requested for a wave, applied by the view holding the waves.
+pub struct FormatChange {
+    pub signal_name: String,
+    pub format: bitvec_str::Format,
+    pub signed: Option<bool>,
+}
+
+/// Change requested for the memory view, applied by the view holding it.
+pub enum MemoryViewChange {
+    Select(String),
+    Goto(i64),
+    AddressFormat(bitvec_str::Format),
+    DataFormat(bitvec_str::Format, Option<bool>),
 }
 
 #[derive(PartialEq)]
@@ -33,8 +51,23 @@ impl CommandLine {
             text: "".to_string(),
             result_history: vec![],
             state: State::NotActive,
+            format_changes: vec![],
+            memory_view_changes: vec![],
         }
     }
+
+    pub fn take_format_changes(&mut self) -> Vec<FormatChange> {
+        std::mem::take(&mut self.format_changes)
+    }
+
+    pub fn take_memory_view_changes(&mut self) -> Vec<MemoryViewChange> {
+        std::mem::take(&mut self.memory_view_changes)
+    }
+
+    pub fn push_result(&mut self, result: Result<String, String>) {
+        self.result_history.push(result);
+        self.notify_render();
+    }
 }
 
 impl Component for CommandLine {
@@ -98,10 +131,60 @@ impl CommandLine {
             Ok(command) => {
                 match command {
                     interpreter::Command::Run(x) => self.request(sim::Request::Run(x)),
+                    interpreter::Command::RunBack(x) => self.request(sim::Request::RunBack(x)),
+                    interpreter::Command::RunUntil(condition, max_duration) => {
+                        self.request(sim::Request::RunUntil(condition, max_duration))
+                    }
+                    interpreter::Command::RunCycles(x) => self.request(sim::Request::RunCycles(x)),
                     interpreter::Command::Load(x) => self.request(sim::Request::Load(x)),
+                    interpreter::Command::Open(x) => self.request(sim::Request::LoadWaveform(x)),
                     interpreter::Command::Set(sig_name, value) => {
                         self.request(sim::Request::SetSignal(sig_name, value))
                     }
+                    interpreter::Command::Force(sig_name, value) => {
+                        self.request(sim::Request::Force(sig_name, value))
+                    }
+                    interpreter::Command::Release(sig_name) => {
+                        self.request(sim::Request::Release(sig_name))
+                    }
+                    interpreter::Command::Deposit(sig_name, value) => {
+                        self.request(sim::Request::Deposit(sig_name, value))
+                    }
+                    interpreter::Command::ExportVcd(x) => self.request(sim::Request::ExportVcd(x)),
+                    interpreter::Command::SaveCheckpoint(x) => {
+                        self.request(sim::Request::SaveCheckpoint(x))
+                    }
+                    interpreter::Command::RestoreCheckpoint(x) => {
+                        self.request(sim::Request::RestoreCheckpoint(x))
+                    }
+                    interpreter::Command::Clock(x) => self.request(sim::Request::AddClock(x)),
+                    interpreter::Command::Reset(sig_name, active_value, cycles) => {
+                        self.request(sim::Request::AddReset(sig_name, active_value, cycles))
+                    }
+                    interpreter::Command::ListCache => self.request(sim::Request::ListCache),
+                    interpreter::Command::PruneCache(x) => {
+                        self.request(sim::Request::PruneCache(x))
+                    }
+                    interpreter::Command::ClearCache => self.request(sim::Request::ClearCache),
+                    interpreter::Command::Format(signal_name, format, signed) => {
+                        self.format_changes.push(FormatChange {
+                            signal_name,
+                            format,
+                            signed,
+                        })
+                    }
+                    interpreter::Command::Memory(signal_name) => self
+                        .memory_view_changes
+                        .push(MemoryViewChange::Select(signal_name)),
+                    interpreter::Command::Goto(address) => self
+                        .memory_view_changes
+                        .push(MemoryViewChange::Goto(address)),
+                    interpreter::Command::MemoryAddressFormat(format) => self
+                        .memory_view_changes
+                        .push(MemoryViewChange::AddressFormat(format)),
+                    interpreter::Command::MemoryDataFormat(format, signed) => self
+                        .memory_view_changes
+                        .push(MemoryViewChange::DataFormat(format, signed)),
                     interpreter::Command::Noop => return,
                 }
                 self.result_history
@@ -111,6 +194,18 @@ impl CommandLine {
         }
     }
 
+    fn cache_summary(entries: &[sim::CacheEntry]) -> String {
+        let total_size: u64 = entries.iter().map(|e| e.size).sum();
+        let top_module_names: Vec<&str> =
+            entries.iter().map(|e| e.top_module_name.as_str()).collect();
+        format!(
+            "cache: {} entries, {:.1} MiB ({})",
+            entries.len(),
+            total_size as f64 / (1024.0 * 1024.0),
+            top_module_names.join(", ")
+        )
+    }
+
     fn request(&self, request: sim::Request) {
         self.request_tx.send(request).unwrap();
     }
@@ -126,11 +221,48 @@ impl sim::Listener for CommandLine {
             sim::Response::RunResult(Ok(curr_time)) => {
                 Ok(format!("run: current time = {curr_time}"))
             }
+            sim::Response::RunUntilResult(Ok(sim::Breakpoint {
+                time,
+                condition: Some(condition),
+            })) => Ok(format!("run: {condition} holds at time = {time}")),
+            sim::Response::RunUntilResult(Ok(sim::Breakpoint {
+                time,
+                condition: None,
+            })) => Ok(format!("run: condition not met, current time = {time}")),
             sim::Response::SetSignalResult(Ok(())) => Ok("set: success".to_string()),
+            sim::Response::ForceResult(Ok(())) => Ok("force: success".to_string()),
+            sim::Response::ReleaseResult(Ok(())) => Ok("release: success".to_string()),
+            sim::Response::DepositResult(Ok(())) => Ok("deposit: success".to_string()),
             sim::Response::LoadResult(Ok(_)) => Ok("load: success".to_string()),
+            sim::Response::LoadWaveformResult(Ok(_)) => Ok("open: success".to_string()),
+            sim::Response::ExportVcdResult(Ok(())) => Ok("export: success".to_string()),
+            sim::Response::SaveCheckpointResult(Ok(())) => Ok("checkpoint: saved".to_string()),
+            sim::Response::RestoreCheckpointResult(Ok(time)) => {
+                Ok(format!("checkpoint: restored, current time = {time}"))
+            }
+            sim::Response::AddClockResult(Ok(())) => Ok("clock: success".to_string()),
+            sim::Response::AddResetResult(Ok(())) => Ok("reset: success".to_string()),
+            sim::Response::ListCacheResult(Ok(entries)) => Ok(Self::cache_summary(entries)),
+            sim::Response::PruneCacheResult(Ok(removed))
+            | sim::Response::ClearCacheResult(Ok(removed)) => {
+                Ok(format!("cache: removed {removed} entries"))
+            }
             sim::Response::RunResult(Err(e)) => Err(format!("run: {e}")),
+            sim::Response::RunUntilResult(Err(e)) => Err(format!("run: {e}")),
             sim::Response::SetSignalResult(Err(e)) => Err(format!("set: {e}")),
+            sim::Response::ForceResult(Err(e)) => Err(format!("force: {e}")),
+            sim::Response::ReleaseResult(Err(e)) => Err(format!("release: {e}")),
+            sim::Response::DepositResult(Err(e)) => Err(format!("deposit: {e}")),
             sim::Response::LoadResult(Err(e)) => Err(format!("load: {e}")),
+            sim::Response::LoadWaveformResult(Err(e)) => Err(format!("open: {e}")),
+            sim::Response::ExportVcdResult(Err(e)) => Err(format!("export: {e}")),
+            sim::Response::SaveCheckpointResult(Err(e))
+            | sim::Response::RestoreCheckpointResult(Err(e)) => Err(format!("checkpoint: {e}")),
+            sim::Response::AddClockResult(Err(e)) => Err(format!("clock: {e}")),
+            sim::Response::AddResetResult(Err(e)) => Err(format!("reset: {e}")),
+            sim::Response::ListCacheResult(Err(e))
+            | sim::Response::PruneCacheResult(Err(e))
+            | sim::Response::ClearCacheResult(Err(e)) => Err(format!("cache: {e}")),
             sim::Response::ModifyProbedPointsResult(Err(e)) => {
                 Err(format!("modify probe points: {e}"))
             }
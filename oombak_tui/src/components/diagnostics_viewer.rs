@@ -0,0 +1,170 @@
+use std::sync::mpsc::Sender;
+
+use crossterm::event::KeyCode;
+use oombak_sim::sim::{Diagnostic, Severity};
+use ratatui::{
+    layout::{Alignment, Constraint, Layout},
+    style::{palette::tailwind::SLATE, Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+};
+
+use crate::{
+    component::{Component, HandleResult},
+    render::Message,
+};
+
+use super::SourceViewer;
+
+const SELECTED_STYLE: Style = Style::new().bg(SLATE.c800).add_modifier(Modifier::BOLD);
+
+pub struct DiagnosticsViewer {
+    message_tx: Sender<Message>,
+    diagnostics: Vec<Diagnostic>,
+    list_state: ListState,
+    source_viewer: SourceViewer,
+    focus: Focus,
+}
+
+#[derive(PartialEq)]
+enum Focus {
+    List,
+    Source,
+}
+
+impl DiagnosticsViewer {
+    pub fn new(message_tx: Sender<Message>) -> Self {
+        Self {
+            message_tx,
+            diagnostics: vec![],
+            list_state: ListState::default(),
+            source_viewer: SourceViewer::default(),
+            focus: Focus::List,
+        }
+    }
+
+    pub fn set_diagnostics(&mut self, diagnostics: &[Diagnostic]) {
+        self.diagnostics = diagnostics.to_vec();
+        self.focus = Focus::List;
+        self.list_state = ListState::default();
+        if !self.diagnostics.is_empty() {
+            self.list_state.select_first();
+            self.open_selected_source();
+        }
+    }
+
+    pub fn has_diagnostics(&self) -> bool {
+        !self.diagnostics.is_empty()
+    }
+}
+
+impl Component for DiagnosticsViewer {
+    fn render_mut(&mut self, f: &mut ratatui::Frame, rect: ratatui::prelude::Rect) {
+        if self.diagnostics.is_empty() {
+            let rect = Layout::vertical(vec![
+                Constraint::Percentage(50),
+                Constraint::Length(1),
+                Constraint::Percentage(50),
+            ])
+            .split(rect)[1];
+            let message = Paragraph::new("No diagnostics").alignment(Alignment::Center);
+            f.render_widget(message, rect);
+            return;
+        }
+        let chunks = Layout::vertical(vec![Constraint::Percentage(40), Constraint::Percentage(60)])
+            .split(rect);
+        let items: Vec<ListItem> = self.diagnostics.iter().map(Self::new_list_item).collect();
+        let list = List::new(items).highlight_style(SELECTED_STYLE);
+        f.render_stateful_widget(list, chunks[0], &mut self.list_state);
+
+        let title = self
+            .source_viewer
+            .path()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let block = Block::new().borders(Borders::TOP).title(title);
+        let block = if self.focus == Focus::Source {
+            block.border_style(Style::new().fg(Color::Yellow))
+        } else {
+            block
+        };
+        let inner = block.inner(chunks[1]);
+        f.render_widget(block, chunks[1]);
+        self.source_viewer.render_mut(f, inner);
+    }
+
+    fn handle_key_event(&mut self, key_event: &crossterm::event::KeyEvent) -> HandleResult {
+        match (&self.focus, key_event.code) {
+            (Focus::List, KeyCode::Char('q') | KeyCode::Esc) => return HandleResult::ReleaseFocus,
+            (Focus::List, KeyCode::Enter) if self.has_diagnostics() => self.focus = Focus::Source,
+            (Focus::List, KeyCode::Down | KeyCode::Char('j')) => {
+                self.list_state.select_next();
+                self.open_selected_source();
+            }
+            (Focus::List, KeyCode::Up | KeyCode::Char('k')) => {
+                self.list_state.select_previous();
+                self.open_selected_source();
+            }
+            (Focus::Source, KeyCode::Char('q') | KeyCode::Esc | KeyCode::Enter) => {
+                self.focus = Focus::List
+            }
+            (Focus::Source, KeyCode::Down | KeyCode::Char('j')) => self.source_viewer.scroll_down(),
+            (Focus::Source, KeyCode::Up | KeyCode::Char('k')) => self.source_viewer.scroll_up(),
+            _ => (),
+        }
+        self.notify_render();
+        HandleResult::Handled
+    }
+
+    fn handle_resize_event(&mut self, _columns: u16, _rows: u16) -> HandleResult {
+        self.notify_render();
+        HandleResult::Handled
+    }
+
+    fn try_propagate_event(&mut self, _event: &crossterm::event::Event) -> HandleResult {
+        HandleResult::NotHandled
+    }
+
+    fn set_focus_to_self(&mut self) {}
+
+    fn render(&self, _f: &mut ratatui::Frame, _rect: ratatui::prelude::Rect) {}
+}
+
+impl DiagnosticsViewer {
+    fn notify_render(&self) {
+        self.message_tx.send(Message::Render).unwrap();
+    }
+
+    fn open_selected_source(&mut self) {
+        let selected = self
+            .list_state
+            .selected()
+            .map(|idx| usize::min(idx, self.diagnostics.len().saturating_sub(1)));
+        if let Some(diagnostic) = selected.and_then(|idx| self.diagnostics.get(idx)) {
+            self.source_viewer.open(&diagnostic.file, diagnostic.line);
+        }
+    }
+
+    fn new_list_item<'a>(diagnostic: &Diagnostic) -> ListItem<'a> {
+        let severity_color = match diagnostic.severity {
+            Severity::Error => Color::Red,
+            Severity::Warning => Color::Yellow,
+            Severity::Note => Color::Blue,
+        };
+        let location = match (diagnostic.line, diagnostic.column) {
+            (0, _) => diagnostic.file.to_string_lossy().to_string(),
+            (line, Some(column)) => {
+                format!("{}:{line}:{column}", diagnostic.file.to_string_lossy())
+            }
+            (line, None) => format!("{}:{line}", diagnostic.file.to_string_lossy()),
+        };
+        let line = Line::from(vec![
+            Span::styled(
+                format!("{:<8}", diagnostic.severity.to_string()),
+                Style::new().fg(severity_color),
+            ),
+            Span::raw(format!("{location}: {}", diagnostic.message)),
+        ]);
+        ListItem::new(line)
+    }
+}
@@ -3,7 +3,9 @@ use std::sync::mpsc::Sender;
 use std::sync::{Arc, RwLock};
 
 use crossterm::event::KeyCode;
-use oombak_sim::sim::{InstanceNode, LoadedDut, ProbePointsModification, Request, Signal};
+use oombak_sim::sim::{
+    Direction, InstanceNode, LoadedDut, ProbePointsModification, Request, Signal, SourceLocation,
+};
 use ratatui::style::Color;
 use ratatui::{
     layout::{Alignment, Constraint, Layout},
@@ -24,6 +26,7 @@ const INSTANCE_ITEM_STYLE: Style = Style::new()
 const SIGNAL_ITEM_STYLE: Style = Style::new()
     .fg(Color::Yellow)
     .add_modifier(Modifier::ITALIC);
+const INOUT_ITEM_STYLE: Style = Style::new().fg(Color::Cyan).add_modifier(Modifier::ITALIC);
 
 pub struct InstanceHierViewer {
     message_tx: Sender<Message>,
@@ -35,6 +38,9 @@ pub struct InstanceHierViewer {
     selected_item_idx: Option<usize>,
     signals_marked_to_add: HashSet<String>,
     signals_marked_to_remove: HashSet<String>,
+    ports_marked_to_remove: HashSet<String>,
+    /// Path of the signal whose declaration was asked to be shown.
+    source_request: Option<String>,
 }
 
 struct InstanceHierNode {
@@ -50,6 +56,7 @@ struct InstanceHierLeaf {
     path: String,
     signal: Signal,
     is_added: bool,
+    is_top_level_port: bool,
     marker: Marker,
 }
 
@@ -58,6 +65,7 @@ enum Marker {
     NotMarked,
     MarkedForAdd,
     MarkedForRemove,
+    MarkedForForcedRemove,
 }
 
 enum HierItem {
@@ -77,6 +85,8 @@ impl InstanceHierViewer {
             probed_points: HashSet::default(),
             signals_marked_to_add: HashSet::default(),
             signals_marked_to_remove: HashSet::default(),
+            ports_marked_to_remove: HashSet::default(),
+            source_request: None,
         }
     }
 
@@ -90,6 +100,21 @@ impl InstanceHierViewer {
         self.selected_item_idx = Some(0);
         self.list_state.select_first();
     }
+
+    pub fn take_source_request(&mut self) -> Option<String> {
+        self.source_request.take()
+    }
+
+    /// Where the signal probed as `path` is declared.
+    pub fn location_of(&self, path: &str) -> Option<SourceLocation> {
+        let root_node = self.root_node.as_ref()?;
+        InstanceHierNode::find_leaf(root_node, path)?
+            .read()
+            .unwrap()
+            .signal
+            .location
+            .clone()
+    }
 }
 
 impl Component for InstanceHierViewer {
@@ -121,7 +146,11 @@ impl Component for InstanceHierViewer {
                 self.clear_marked_signals();
                 return HandleResult::ReleaseFocus;
             }
+            KeyCode::Char('o') if self.request_source_of_selected() => {
+                return HandleResult::ReleaseFocus;
+            }
             KeyCode::Enter => self.perform_action_on_selected(),
+            KeyCode::Char('D') => self.toggle_forced_removal_on_selected(),
             KeyCode::Down | KeyCode::Char('j') => self.scroll_down(),
             KeyCode::Up | KeyCode::Char('k') => self.scroll_up(),
             _ => (),
@@ -207,12 +236,20 @@ impl InstanceHierViewer {
             Marker::NotMarked => "",
             Marker::MarkedForAdd => " (+)",
             Marker::MarkedForRemove => " (-)",
+            Marker::MarkedForForcedRemove => " (-!)",
+        };
+        let (direction_symbol, style) = match leaf.signal.direction() {
+            Some(Direction::In) => (" <in>", SIGNAL_ITEM_STYLE),
+            Some(Direction::Out) => (" <out>", SIGNAL_ITEM_STYLE),
+            Some(Direction::InOut) => (" <inout>", INOUT_ITEM_STYLE),
+            Some(Direction::Ref) => (" <ref>", INOUT_ITEM_STYLE),
+            None => ("", SIGNAL_ITEM_STYLE),
         };
         let line = Line::raw(format!(
-            "{}{}{}{}",
-            indentation, leaf.signal.name, added_symbol, marker_symbol
+            "{}{}{}{}{}",
+            indentation, leaf.signal.name, direction_symbol, added_symbol, marker_symbol
         ))
-        .style(SIGNAL_ITEM_STYLE);
+        .style(style);
         ListItem::new(line)
     }
 
@@ -244,6 +281,7 @@ impl InstanceHierViewer {
                             signals_marked_to_remove.remove(&leaf.path);
                             Marker::NotMarked
                         }
+                        Marker::MarkedForForcedRemove => Marker::MarkedForForcedRemove,
                     };
                 }
             }
@@ -253,6 +291,38 @@ impl InstanceHierViewer {
         self.signals_marked_to_remove = signals_marked_to_remove;
     }
 
+    fn toggle_forced_removal_on_selected(&mut self) {
+        let mut signals_marked_to_remove = self.signals_marked_to_remove.clone();
+        let mut ports_marked_to_remove = self.ports_marked_to_remove.clone();
+        if let Some(HierItem::Signal(leaf)) = self.get_selected_item() {
+            let mut leaf = leaf.write().unwrap();
+            if leaf.is_added && leaf.is_top_level_port {
+                leaf.marker = match leaf.marker {
+                    Marker::MarkedForForcedRemove => {
+                        ports_marked_to_remove.remove(&leaf.path);
+                        Marker::NotMarked
+                    }
+                    _ => {
+                        signals_marked_to_remove.remove(&leaf.path);
+                        ports_marked_to_remove.insert(leaf.path.clone());
+                        Marker::MarkedForForcedRemove
+                    }
+                };
+            }
+        }
+        self.signals_marked_to_remove = signals_marked_to_remove;
+        self.ports_marked_to_remove = ports_marked_to_remove;
+    }
+
+    fn request_source_of_selected(&mut self) -> bool {
+        let path = match self.get_selected_item() {
+            Some(HierItem::Signal(leaf)) => leaf.read().unwrap().path.clone(),
+            _ => return false,
+        };
+        self.source_request = Some(path);
+        true
+    }
+
     fn get_selected_item(&self) -> Option<&HierItem> {
         if let Some(idx) = self.selected_item_idx {
             Some(&self.items_in_list[idx])
@@ -265,6 +335,7 @@ impl InstanceHierViewer {
         let probe_points_modification = ProbePointsModification {
             to_add: self.signals_marked_to_add.clone().into_iter().collect(),
             to_remove: self.signals_marked_to_remove.clone().into_iter().collect(),
+            top_level_ports_to_remove: self.ports_marked_to_remove.clone().into_iter().collect(),
         };
         self.request_tx
             .send(Request::ModifyProbedPoints(probe_points_modification))
@@ -274,6 +345,7 @@ impl InstanceHierViewer {
     fn clear_marked_signals(&mut self) {
         self.signals_marked_to_add.clear();
         self.signals_marked_to_remove.clear();
+        self.ports_marked_to_remove.clear();
     }
 
     fn scroll_down(&mut self) {
@@ -313,7 +385,7 @@ impl InstanceHierNode {
         let leafs = instance_node
             .signals
             .iter()
-            .map(|s| InstanceHierLeaf::new(s, &path, probed_points))
+            .map(|s| InstanceHierLeaf::new(s, &path, parent_path.is_empty(), probed_points))
             .map(RwLock::new)
             .map(Arc::new)
             .collect();
@@ -327,9 +399,31 @@ impl InstanceHierNode {
     }
 }
 
+impl InstanceHierNode {
+    fn find_leaf(
+        node: &Arc<RwLock<InstanceHierNode>>,
+        path: &str,
+    ) -> Option<Arc<RwLock<InstanceHierLeaf>>> {
+        let node = node.read().unwrap();
+        node.leafs
+            .iter()
+            .find(|l| l.read().unwrap().path == path)
+            .cloned()
+            .or_else(|| node.children.iter().find_map(|c| Self::find_leaf(c, path)))
+    }
+}
+
 impl InstanceHierLeaf {
-    fn new(signal: &Signal, parent_path: &str, probed_points: &HashSet<String>) -> Self {
-        let path = if parent_path.is_empty() {
+    /// Ports of the root instance are probed by their bare name, other signals by their
+    /// hierarchical path.
+    fn new(
+        signal: &Signal,
+        parent_path: &str,
+        is_in_root: bool,
+        probed_points: &HashSet<String>,
+    ) -> Self {
+        let is_top_level_port = is_in_root && signal.is_port();
+        let path = if parent_path.is_empty() || is_top_level_port {
             signal.name.to_string()
         } else {
             format!("{parent_path}.{}", signal.name)
@@ -339,6 +433,7 @@ impl InstanceHierLeaf {
             path,
             signal: signal.clone(),
             is_added,
+            is_top_level_port,
             marker: Marker::NotMarked,
         }
     }
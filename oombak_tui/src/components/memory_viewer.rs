@@ -0,0 +1,283 @@
+use bitvec::{field::BitField, vec::BitVec};
+use oombak_sim::{memory::MemoryTrace, sim::LogicVec};
+use ratatui::{
+    layout::Rect,
+    style::{palette::tailwind::SLATE, Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+
+use crate::utils::bitvec_str::{self, Format};
+
+const TITLE_STYLE: Style = Style::new().add_modifier(Modifier::BOLD);
+const ADDRESS_STYLE: Style = Style::new().fg(Color::DarkGray);
+const CHANGED_STYLE: Style = Style::new().fg(Color::Black).bg(Color::LightYellow);
+const UNKNOWN_STYLE: Style = Style::new().fg(Color::Red);
+const GOTO_STYLE: Style = Style::new().bg(SLATE.c800).add_modifier(Modifier::BOLD);
+
+/// Hex-dump style table of a probed memory's contents at the highlighted time.
+pub struct MemoryViewer {
+    memories: Vec<MemoryTrace>,
+    selected_idx: usize,
+    highlight_time: usize,
+    /// Offset from the lowest address of the first row shown.
+    top_offset: usize,
+    goto_offset: Option<usize>,
+    address_format: Format,
+    data_format: Format,
+    data_signed: bool,
+    /// Elements per row and rows shown at the last render, for scrolling.
+    columns: usize,
+    rows: usize,
+}
+
+impl Default for MemoryViewer {
+    fn default() -> Self {
+        Self {
+            memories: vec![],
+            selected_idx: 0,
+            highlight_time: 0,
+            top_offset: 0,
+            goto_offset: None,
+            address_format: Format::Hexadecimal,
+            data_format: Format::Hexadecimal,
+            data_signed: false,
+            columns: 1,
+            rows: 1,
+        }
+    }
+}
+
+impl MemoryViewer {
+    /// Keeps the selected memory if it is still probed.
+    pub fn set_memories(&mut self, memories: Vec<MemoryTrace>) {
+        let selected_name = self.selected().map(|m| m.signal_name.clone());
+        self.memories = memories;
+        match selected_name.and_then(|name| self.position(&name)) {
+            Some(idx) => self.selected_idx = idx,
+            None => self.select_idx(0),
+        }
+    }
+
+    pub fn set_highlight(&mut self, time: usize) {
+        self.highlight_time = time;
+    }
+
+    /// Returns false if no memory named `signal_name` is probed.
+    pub fn select(&mut self, signal_name: &str) -> bool {
+        match self.position(signal_name) {
+            Some(idx) => {
+                self.select_idx(idx);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn select_next(&mut self) {
+        if !self.memories.is_empty() {
+            self.select_idx((self.selected_idx + 1) % self.memories.len());
+        }
+    }
+
+    /// Scrolls to the row holding `address` and marks it.
+    pub fn goto(&mut self, address: i64) -> Result<(), String> {
+        let Some(memory) = self.selected() else {
+            return Err("no memory is probed".to_string());
+        };
+        if !memory.dimension.contains(address) {
+            return Err(format!(
+                "address {address} is outside {}[{}:{}]",
+                memory.signal_name, memory.dimension.left, memory.dimension.right
+            ));
+        }
+        let offset = (address - memory.lowest_address()) as usize;
+        self.goto_offset = Some(offset);
+        self.top_offset = offset - offset % self.columns;
+        Ok(())
+    }
+
+    pub fn set_address_format(&mut self, format: Format) {
+        self.address_format = format;
+    }
+
+    pub fn set_data_format(&mut self, format: Format, signed: Option<bool>) {
+        self.data_format = format;
+        self.data_signed = signed.unwrap_or(self.data_signed);
+    }
+
+    pub fn scroll_down(&mut self) {
+        let size = self.selected().map(|m| m.size()).unwrap_or(0);
+        let step = self.columns * self.rows.max(1);
+        if self.top_offset + step < size {
+            self.top_offset += step;
+        }
+    }
+
+    pub fn scroll_up(&mut self) {
+        let step = self.columns * self.rows.max(1);
+        self.top_offset = self.top_offset.saturating_sub(step);
+    }
+
+    pub fn render_mut(&mut self, f: &mut Frame, rect: Rect) {
+        let Some(memory) = self.selected() else {
+            f.render_widget(Paragraph::new("no probed memories"), rect);
+            return;
+        };
+        let title = Line::styled(
+            format!(
+                "{}[{}:{}] @ {} (addr: {}, data: {})",
+                memory.signal_name,
+                memory.dimension.left,
+                memory.dimension.right,
+                self.highlight_time,
+                self.address_format.name(),
+                self.data_format_name()
+            ),
+            TITLE_STYLE,
+        );
+        let contents = memory.contents_at(self.highlight_time);
+        if contents.is_empty() {
+            f.render_widget(
+                Paragraph::new(vec![title, Line::raw("not sampled yet")]),
+                rect,
+            );
+            return;
+        }
+        let changed = memory.changed_at(self.highlight_time);
+
+        let highest_offset = memory.size() - 1;
+        let address_width = self
+            .format_address(memory, highest_offset)
+            .len()
+            .max(self.format_address(memory, 0).len());
+        let cell_width = self.cell_width(memory.element_width);
+        let columns = Self::columns_fitting(rect.width as usize, address_width, cell_width);
+        let rows = (rect.height as usize).saturating_sub(1).max(1);
+
+        let top_offset = self.top_offset - self.top_offset % columns;
+        let mut lines = vec![title];
+        for row_offset in (top_offset..memory.size()).step_by(columns).take(rows) {
+            let mut spans = vec![Span::styled(
+                format!(
+                    "{:>address_width$}:",
+                    self.format_address(memory, row_offset)
+                ),
+                ADDRESS_STYLE,
+            )];
+            for offset in row_offset..usize::min(row_offset + columns, memory.size()) {
+                let value = &contents[offset];
+                let style = if Some(offset) == self.goto_offset {
+                    GOTO_STYLE
+                } else if changed[offset] {
+                    CHANGED_STYLE
+                } else if value.to_bitvec().is_none() {
+                    UNKNOWN_STYLE
+                } else {
+                    Style::default()
+                };
+                spans.push(Span::raw(" "));
+                spans.push(Span::styled(
+                    format!("{:>cell_width$}", self.format_data(memory, value)),
+                    style,
+                ));
+            }
+            lines.push(Line::from(spans));
+        }
+        f.render_widget(Paragraph::new(lines), rect);
+
+        self.top_offset = top_offset;
+        self.columns = columns;
+        self.rows = rows;
+    }
+}
+
+impl MemoryViewer {
+    fn selected(&self) -> Option<&MemoryTrace> {
+        self.memories.get(self.selected_idx)
+    }
+
+    fn position(&self, signal_name: &str) -> Option<usize> {
+        self.memories
+            .iter()
+            .position(|m| m.signal_name == signal_name)
+    }
+
+    fn select_idx(&mut self, idx: usize) {
+        self.selected_idx = idx;
+        self.top_offset = 0;
+        self.goto_offset = None;
+    }
+
+    fn data_format_name(&self) -> &'static str {
+        match (self.data_format, self.data_signed) {
+            (Format::Decimal, true) => "sdec",
+            (format, _) => format.name(),
+        }
+    }
+
+    fn format_address(&self, memory: &MemoryTrace, offset: usize) -> String {
+        let address = memory.lowest_address() + offset as i64;
+        let mut bits: BitVec<u32> = BitVec::repeat(false, 64);
+        bits.store_le(address as u64);
+        let width = usize::max(64 - (address as u64).leading_zeros() as usize, 1);
+        bitvec_str::from(
+            &LogicVec::from(bits),
+            &bitvec_str::Option {
+                format: self.address_format,
+                width,
+                twos_complement: false,
+            },
+        )
+    }
+
+    fn format_data(&self, memory: &MemoryTrace, value: &LogicVec) -> String {
+        bitvec_str::from(
+            value,
+            &bitvec_str::Option {
+                format: self.data_format,
+                width: memory.element_width,
+                twos_complement: self.data_signed,
+            },
+        )
+    }
+
+    /// Characters of the widest value of `width` bits in the data format.
+    fn cell_width(&self, width: usize) -> usize {
+        let width = width.max(1);
+        match self.data_format {
+            Format::Binary => width,
+            Format::Octal => width.div_ceil(3),
+            Format::Hexadecimal => width.div_ceil(4),
+            Format::Ascii => width.div_ceil(8),
+            Format::Decimal => {
+                let digits = (width as f64 * 2f64.log10()).floor() as usize + 1;
+                digits + self.data_signed as usize
+            }
+        }
+    }
+
+    /// The largest power of two, up to 16, of cells which fit beside the address column.
+    fn columns_fitting(total_width: usize, address_width: usize, cell_width: usize) -> usize {
+        let available = total_width.saturating_sub(address_width + 1);
+        let fitting = available / (cell_width + 1);
+        let mut columns = 1;
+        while columns * 2 <= usize::min(fitting, 16) {
+            columns *= 2;
+        }
+        columns
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::MemoryViewer;
+
+    #[test]
+    fn test_columns_fitting() {
+        assert_eq!(MemoryViewer::columns_fitting(80, 4, 2), 16);
+        assert_eq!(MemoryViewer::columns_fitting(40, 4, 8), 2);
+        assert_eq!(MemoryViewer::columns_fitting(10, 4, 8), 1);
+    }
+}
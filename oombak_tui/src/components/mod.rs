@@ -1,12 +1,18 @@
 mod command_line;
+mod diagnostics_viewer;
 mod instance_hier_viewer;
+mod memory_viewer;
 pub mod models;
 mod root;
 mod signals_viewer;
+mod source_viewer;
 mod wave_viewer;
 
 pub use command_line::CommandLine;
+pub use diagnostics_viewer::DiagnosticsViewer;
 pub use instance_hier_viewer::InstanceHierViewer;
+pub use memory_viewer::MemoryViewer;
 pub use root::Root;
 pub use signals_viewer::SignalsViewer;
+pub use source_viewer::SourceViewer;
 pub use wave_viewer::WaveViewer;
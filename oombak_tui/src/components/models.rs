@@ -1,6 +1,6 @@
 use crate::utils::bitvec_str;
 
-use oombak_sim::sim::{self, SimulationResult};
+use oombak_sim::sim::{self, LogicVec, SimulationResult};
 
 #[derive(Default, Clone)]
 pub struct SimulationSpec {
@@ -16,6 +16,15 @@ pub struct WaveSpec {
     pub height: u16,
     pub format: bitvec_str::Format,
     pub signed: bool,
+    pub is_forced: bool,
+    /// Values of enums are shown by name, and in `format` if no name matches.
+    pub show_enum_names: bool,
+    /// Whether the field waves of a struct or union are shown below it.
+    pub expanded: bool,
+    /// Number of structs this wave is nested in. Field waves follow the wave they are part of.
+    pub depth: usize,
+    /// The field of the parent wave this wave is sliced from.
+    pub field: Option<sim::Field>,
 }
 
 impl SimulationSpec {
@@ -30,26 +39,256 @@ impl SimulationSpec {
             .waves
             .iter()
             .map(|w| WaveSpec {
-                wave: w.clone(),
-                height: 1,
-                format: bitvec_str::Format::Binary,
-                signed: true,
+                is_forced: simulation_result.is_forced(&w.signal_name),
+                ..WaveSpec::new(w.clone())
             })
             .collect();
         spec
     }
 
+    /// Shows or hides the field waves of the struct or union wave at `idx`.
+    pub fn toggle_expand(&mut self, idx: usize) {
+        let Some(wave_spec) = self.wave_specs.get(idx) else {
+            return;
+        };
+        if wave_spec.expanded {
+            let depth = wave_spec.depth;
+            let end = self.wave_specs[idx + 1..]
+                .iter()
+                .position(|s| s.depth <= depth)
+                .map_or(self.wave_specs.len(), |p| idx + 1 + p);
+            self.wave_specs.drain(idx + 1..end);
+            self.wave_specs[idx].expanded = false;
+        } else {
+            let field_specs: Vec<WaveSpec> = wave_spec
+                .wave
+                .type_info
+                .fields()
+                .iter()
+                .map(|field| WaveSpec {
+                    is_forced: wave_spec.is_forced,
+                    depth: wave_spec.depth + 1,
+                    field: Some(field.clone()),
+                    ..WaveSpec::new(wave_spec.wave.field(field))
+                })
+                .collect();
+            if field_specs.is_empty() {
+                return;
+            }
+            self.wave_specs.splice(idx + 1..idx + 1, field_specs);
+            self.wave_specs[idx].expanded = true;
+        }
+    }
+
+    /// Name of the signal the wave at `idx` is part of, which is its own unless it is a field.
+    pub fn signal_name_of(&self, idx: usize) -> Option<&str> {
+        self.wave_specs
+            .get(..=idx)?
+            .iter()
+            .rev()
+            .find(|s| s.depth == 0)
+            .map(|s| s.wave.signal_name.as_str())
+    }
+
+    /// Keeps the display format of waves which are also in `previous`.
+    pub fn inherit_formats(&mut self, previous: &SimulationSpec) {
+        for wave_spec in self.wave_specs.iter_mut() {
+            if let Some(previous_spec) = previous
+                .wave_specs
+                .iter()
+                .find(|s| s.wave.signal_name == wave_spec.wave.signal_name)
+            {
+                wave_spec.format = previous_spec.format;
+                wave_spec.signed = previous_spec.signed;
+                wave_spec.show_enum_names = previous_spec.show_enum_names;
+            }
+        }
+    }
+
+    /// Returns false if there is no wave of `signal_name`.
+    pub fn set_format(
+        &mut self,
+        signal_name: &str,
+        format: bitvec_str::Format,
+        signed: Option<bool>,
+    ) -> bool {
+        match self
+            .wave_specs
+            .iter_mut()
+            .find(|s| s.wave.signal_name == signal_name)
+        {
+            Some(wave_spec) => {
+                wave_spec.format = format;
+                wave_spec.signed = signed.unwrap_or(wave_spec.signed);
+                true
+            }
+            None => false,
+        }
+    }
+
     pub fn update(&mut self, simulation_result: &SimulationResult) {
         self.time_step_ps = simulation_result.time_step_ps;
         self.total_time = simulation_result.total_time;
-        self.wave_specs = simulation_result
-            .waves
-            .iter()
-            .zip(self.wave_specs.iter_mut())
-            .map(|(w, s)| WaveSpec {
-                wave: w.clone(),
-                ..s.clone()
-            })
-            .collect();
+        let mut parent_idxs: Vec<usize> = vec![];
+        for idx in 0..self.wave_specs.len() {
+            parent_idxs.truncate(self.wave_specs[idx].depth);
+            let parent_idx = parent_idxs.last().copied();
+            let (wave, is_forced) = match (&self.wave_specs[idx].field, parent_idx) {
+                (Some(field), Some(parent_idx)) => {
+                    let parent = &self.wave_specs[parent_idx];
+                    (Some(parent.wave.field(field)), parent.is_forced)
+                }
+                _ => {
+                    let signal_name = &self.wave_specs[idx].wave.signal_name;
+                    (
+                        simulation_result
+                            .waves
+                            .iter()
+                            .find(|w| &w.signal_name == signal_name)
+                            .cloned(),
+                        simulation_result.is_forced(signal_name),
+                    )
+                }
+            };
+            let wave_spec = &mut self.wave_specs[idx];
+            if let Some(wave) = wave {
+                wave_spec.wave = wave;
+            }
+            wave_spec.is_forced = is_forced;
+            parent_idxs.push(idx);
+        }
+    }
+}
+
+impl WaveSpec {
+    pub fn new(wave: sim::Wave) -> Self {
+        let show_enum_names = matches!(wave.type_info, sim::TypeInfo::Enum(_));
+        Self {
+            wave,
+            height: 1,
+            format: bitvec_str::Format::Binary,
+            signed: true,
+            is_forced: false,
+            show_enum_names,
+            expanded: false,
+            depth: 0,
+            field: None,
+        }
+    }
+
+    pub fn is_expandable(&self) -> bool {
+        !self.wave.type_info.fields().is_empty()
+    }
+
+    pub fn format_value(&self, value: &LogicVec) -> String {
+        if self.show_enum_names {
+            if let Some(name) = self.wave.type_info.enum_name_of(value) {
+                return name.to_string();
+            }
+        }
+        bitvec_str::from(value, &bitvec_str::Option::from(self))
+    }
+
+    /// Value at `time` in the display format, or "x" if there is none.
+    pub fn format_value_at(&self, time: usize) -> String {
+        match self.wave.value_idx_at(time) {
+            Some((idx, _)) => self.format_value(&self.wave.values[idx].0),
+            None => "x".to_string(),
+        }
+    }
+
+    /// Steps through binary, hexadecimal, unsigned and signed decimal, octal and ASCII, with
+    /// enum names before binary for enum waves.
+    pub fn cycle_format(&mut self) {
+        use bitvec_str::Format;
+        if self.show_enum_names {
+            self.show_enum_names = false;
+            (self.format, self.signed) = (Format::Binary, false);
+            return;
+        }
+        if self.format == Format::Ascii && matches!(self.wave.type_info, sim::TypeInfo::Enum(_)) {
+            self.show_enum_names = true;
+        }
+        (self.format, self.signed) = match (self.format, self.signed) {
+            (Format::Binary, _) => (Format::Hexadecimal, false),
+            (Format::Hexadecimal, _) => (Format::Decimal, false),
+            (Format::Decimal, false) => (Format::Decimal, true),
+            (Format::Decimal, true) => (Format::Octal, false),
+            (Format::Octal, _) => (Format::Ascii, false),
+            (Format::Ascii, _) => (Format::Binary, false),
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use oombak_sim::sim::{EnumValue, Field, LogicVec, SimulationResult, TypeInfo, Wave};
+
+    use super::SimulationSpec;
+
+    fn value(bits: &str) -> LogicVec {
+        LogicVec::from_ascii(bits.as_bytes()).unwrap()
+    }
+
+    fn simulation_result(values: Vec<(LogicVec, usize, usize)>) -> SimulationResult {
+        let state = TypeInfo::Enum(vec![EnumValue {
+            name: "BUSY".to_string(),
+            value: value("01"),
+        }]);
+        let entry = TypeInfo::Struct(vec![
+            Field {
+                name: "tag".to_string(),
+                lsb: 2,
+                width: 2,
+                type_info: TypeInfo::Logic,
+            },
+            Field {
+                name: "state".to_string(),
+                lsb: 0,
+                width: 2,
+                type_info: state,
+            },
+        ]);
+        SimulationResult {
+            waves: vec![Wave {
+                signal_name: "entry".to_string(),
+                width: 4,
+                values,
+                type_info: entry,
+            }],
+            ..SimulationResult::default()
+        }
+    }
+
+    #[test]
+    fn test_toggle_expand() {
+        let mut spec = SimulationSpec::new(&simulation_result(vec![(value("1001"), 0, 1)]));
+        spec.toggle_expand(0);
+        assert_eq!(spec.wave_specs.len(), 3);
+        assert_eq!(spec.wave_specs[2].wave.signal_name, "entry.state");
+        assert_eq!(spec.wave_specs[2].depth, 1);
+        assert_eq!(spec.wave_specs[2].format_value(&value("01")), "BUSY");
+        assert_eq!(spec.wave_specs[2].format_value(&value("10")), "10");
+
+        spec.update(&simulation_result(vec![
+            (value("1001"), 0, 1),
+            (value("0110"), 1, 1),
+        ]));
+        assert_eq!(spec.wave_specs[1].wave.values.len(), 2);
+        assert_eq!(spec.wave_specs[1].wave.values[1].0, value("01"));
+
+        spec.toggle_expand(0);
+        assert_eq!(spec.wave_specs.len(), 1);
+    }
+
+    #[test]
+    fn test_signal_name_of() {
+        let mut spec = SimulationSpec::new(&simulation_result(vec![(value("1001"), 0, 1)]));
+        spec.toggle_expand(0);
+        assert_eq!(spec.signal_name_of(0), Some("entry"));
+        assert_eq!(spec.signal_name_of(2), Some("entry"));
+        assert_eq!(spec.signal_name_of(3), None);
+        assert_eq!(spec.wave_specs[2].format_value_at(0), "BUSY");
+        assert_eq!(spec.wave_specs[2].format_value_at(5), "x");
     }
 }
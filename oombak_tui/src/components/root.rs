@@ -11,8 +11,12 @@ use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::widgets::{Block, Borders, Clear};
 use ratatui::Frame;
 
+use super::command_line::MemoryViewChange;
 use super::models::SimulationSpec;
-use super::{CommandLine, InstanceHierViewer, SignalsViewer, WaveViewer};
+use super::{
+    CommandLine, DiagnosticsViewer, InstanceHierViewer, MemoryViewer, SignalsViewer, SourceViewer,
+    WaveViewer,
+};
 
 pub struct Root {
     message_tx: Sender<Message>,
@@ -20,6 +24,12 @@ pub struct Root {
     signals_viewer: SignalsViewer,
     wave_viewer: WaveViewer,
     instance_hier_viewer: Arc<RwLock<InstanceHierViewer>>,
+    diagnostics_viewer: DiagnosticsViewer,
+    memory_viewer: MemoryViewer,
+    show_memory_viewer: bool,
+    source_viewer: SourceViewer,
+    /// Path of the signal whose declaration is shown in the source viewer.
+    source_signal: Option<String>,
     command_line: Arc<RwLock<CommandLine>>,
     focused_child: Option<Child>,
     simulation_spec: SimulationSpec,
@@ -29,6 +39,7 @@ pub struct Root {
 enum Child {
     CommandLine,
     InstanceHierView,
+    DiagnosticsView,
 }
 
 impl Root {
@@ -47,6 +58,11 @@ impl Root {
                 message_tx.clone(),
                 request_tx.clone(),
             ))),
+            diagnostics_viewer: DiagnosticsViewer::new(message_tx.clone()),
+            memory_viewer: MemoryViewer::default(),
+            show_memory_viewer: false,
+            source_viewer: SourceViewer::default(),
+            source_signal: None,
             command_line,
             focused_child: None,
             simulation_spec,
@@ -69,15 +85,39 @@ impl Component for Root {
             .direction(Direction::Vertical)
             .constraints(vec![Constraint::Min(0), Constraint::Length(1)])
             .split(rect);
+        let waves_area = if self.source_signal.is_some() {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(vec![Constraint::Percentage(60), Constraint::Percentage(40)])
+                .split(main_layout_v[0]);
+            self.render_source_viewer(f, chunks[1]);
+            chunks[0]
+        } else {
+            main_layout_v[0]
+        };
+        let sub_layout_constraints = if self.show_memory_viewer {
+            vec![
+                Constraint::Percentage(25),
+                Constraint::Percentage(45),
+                Constraint::Percentage(30),
+            ]
+        } else {
+            vec![Constraint::Percentage(25), Constraint::Percentage(75)]
+        };
         let sub_layout_h = Layout::default()
             .direction(Direction::Horizontal)
-            .constraints(vec![Constraint::Percentage(25), Constraint::Percentage(75)])
-            .split(main_layout_v[0]);
+            .constraints(sub_layout_constraints)
+            .split(waves_area);
         self.render_signals_viewer(f, sub_layout_h[0]);
         self.render_wave_viewer(f, sub_layout_h[1]);
+        if self.show_memory_viewer {
+            self.render_memory_viewer(f, sub_layout_h[2]);
+        }
         self.render_command_line(f, main_layout_v[1]);
-        if matches!(self.focused_child, Some(Child::InstanceHierView)) {
-            self.render_instance_hier_viewer(f, rect);
+        match self.focused_child {
+            Some(Child::InstanceHierView) => self.render_instance_hier_viewer(f, rect),
+            Some(Child::DiagnosticsView) => self.render_diagnostics_viewer(f, rect),
+            _ => (),
         }
     }
 
@@ -120,6 +160,18 @@ impl Component for Root {
             KeyCode::Char('s') => {
                 self.focused_child = Some(Child::InstanceHierView);
             }
+            KeyCode::Char('e') => {
+                self.focused_child = Some(Child::DiagnosticsView);
+            }
+            KeyCode::Char('f') => self.cycle_selected_wave_format(),
+            KeyCode::Enter => self.toggle_selected_wave_expand(),
+            KeyCode::Char('m') => self.show_memory_viewer = !self.show_memory_viewer,
+            KeyCode::Char('n') if self.show_memory_viewer => self.memory_viewer.select_next(),
+            KeyCode::PageUp if self.show_memory_viewer => self.memory_viewer.scroll_up(),
+            KeyCode::PageDown if self.show_memory_viewer => self.memory_viewer.scroll_down(),
+            KeyCode::Char('o') => self.open_selected_wave_source(),
+            KeyCode::Char('c') => self.source_signal = None,
+            KeyCode::Char('b') => self.run_back_to_highlight(),
             _ => return HandleResult::NotHandled,
         }
         self.notify_render();
@@ -132,7 +184,24 @@ impl Component for Root {
     }
 
     fn set_focus_to_self(&mut self) {
+        if matches!(self.focused_child, Some(Child::CommandLine)) {
+            self.apply_format_changes();
+            self.apply_memory_view_changes();
+        }
         if matches!(self.focused_child, Some(Child::InstanceHierView)) {
+            let source_request = self
+                .instance_hier_viewer
+                .write()
+                .unwrap()
+                .take_source_request();
+            if let Some(path) = source_request {
+                self.open_source_of(&path);
+            }
+        }
+        if matches!(
+            self.focused_child,
+            Some(Child::InstanceHierView) | Some(Child::DiagnosticsView)
+        ) {
             self.notify_render();
         }
         self.focused_child = None;
@@ -147,6 +216,7 @@ impl Component for Root {
                     .write()
                     .unwrap()
                     .handle_event(event),
+                Child::DiagnosticsView => self.diagnostics_viewer.handle_event(event),
             }
         } else {
             HandleResult::NotHandled
@@ -168,6 +238,25 @@ impl Root {
         self.wave_viewer.render_mut(f, inner);
     }
 
+    fn render_memory_viewer(&mut self, f: &mut Frame, rect: Rect) {
+        let block = Block::new().borders(Borders::LEFT);
+        let inner = block.inner(rect);
+        f.render_widget(block, rect);
+        self.memory_viewer.render_mut(f, inner);
+    }
+
+    fn render_source_viewer(&mut self, f: &mut Frame, rect: Rect) {
+        let title = self
+            .source_viewer
+            .path()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let block = Block::new().borders(Borders::TOP).title(title);
+        let inner = block.inner(rect);
+        f.render_widget(block, rect);
+        self.source_viewer.render_mut(f, inner);
+    }
+
     fn render_instance_hier_viewer(&self, f: &mut Frame, rect: Rect) {
         let popup_area = Self::get_popup_area(rect);
         let block = Block::new().borders(Borders::ALL);
@@ -178,13 +267,154 @@ impl Root {
             .render_mut_with_block(f, popup_area, block);
     }
 
+    fn render_diagnostics_viewer(&mut self, f: &mut Frame, rect: Rect) {
+        let popup_area = Self::get_popup_area(rect);
+        let block = Block::new().borders(Borders::ALL).title("Diagnostics");
+        f.render_widget(Clear, popup_area);
+        self.diagnostics_viewer
+            .render_mut_with_block(f, popup_area, block);
+    }
+
     fn render_command_line(&self, f: &mut Frame, rect: Rect) {
         self.command_line.read().unwrap().render(f, rect);
     }
 
+    fn apply_format_changes(&mut self) {
+        let format_changes = self.command_line.write().unwrap().take_format_changes();
+        for change in format_changes {
+            if !self
+                .simulation_spec
+                .set_format(&change.signal_name, change.format, change.signed)
+            {
+                self.command_line.write().unwrap().push_result(Err(format!(
+                    "format: unknown signal {}",
+                    change.signal_name
+                )));
+            }
+        }
+        self.update_viewers_simulation();
+    }
+
+    fn apply_memory_view_changes(&mut self) {
+        let changes = self
+            .command_line
+            .write()
+            .unwrap()
+            .take_memory_view_changes();
+        for change in changes {
+            let result = match change {
+                MemoryViewChange::Select(signal_name) => {
+                    if self.memory_viewer.select(&signal_name) {
+                        Ok(())
+                    } else {
+                        Err(format!("memory: unknown memory {signal_name}"))
+                    }
+                }
+                MemoryViewChange::Goto(address) => self
+                    .memory_viewer
+                    .goto(address)
+                    .map_err(|e| format!("goto: {e}")),
+                MemoryViewChange::AddressFormat(format) => {
+                    self.memory_viewer.set_address_format(format);
+                    Ok(())
+                }
+                MemoryViewChange::DataFormat(format, signed) => {
+                    self.memory_viewer.set_data_format(format, signed);
+                    Ok(())
+                }
+            };
+            match result {
+                Ok(()) => self.show_memory_viewer = true,
+                Err(e) => self.command_line.write().unwrap().push_result(Err(e)),
+            }
+        }
+        self.notify_render();
+    }
+
+    fn cycle_selected_wave_format(&mut self) {
+        let selected = self.signals_viewer.selected_idx();
+        if let Some(wave_spec) =
+            selected.and_then(|idx| self.simulation_spec.wave_specs.get_mut(idx))
+        {
+            wave_spec.cycle_format();
+            self.update_viewers_simulation();
+        }
+    }
+
+    fn toggle_selected_wave_expand(&mut self) {
+        if let Some(idx) = self.signals_viewer.selected_idx() {
+            self.simulation_spec.toggle_expand(idx);
+            self.update_viewers_simulation();
+        }
+    }
+
+    fn run_back_to_highlight(&self) {
+        if let Some(duration) = self.wave_viewer.run_back_duration_to_highlight() {
+            self.request_tx
+                .send(sim::Request::RunBack(duration))
+                .unwrap();
+        }
+    }
+
+    fn open_selected_wave_source(&mut self) {
+        let signal_name = self
+            .signals_viewer
+            .selected_idx()
+            .and_then(|idx| self.simulation_spec.signal_name_of(idx))
+            .map(str::to_string);
+        if let Some(signal_name) = signal_name {
+            self.open_source_of(&signal_name);
+        }
+    }
+
+    fn open_source_of(&mut self, path: &str) {
+        let location = self.instance_hier_viewer.read().unwrap().location_of(path);
+        match location {
+            Some(location) => {
+                self.source_viewer.open(&location.file, location.line);
+                self.source_signal = Some(path.to_string());
+                self.update_source_annotation();
+            }
+            None => self
+                .command_line
+                .write()
+                .unwrap()
+                .push_result(Err(format!("source: no declaration of {path}"))),
+        }
+    }
+
+    /// Annotates the declaration with the highlighted value of the signal.
+    fn update_source_annotation(&mut self) {
+        let Some(path) = &self.source_signal else {
+            return;
+        };
+        let time = self.wave_viewer.get_highlighted_unit_time();
+        let value = self
+            .simulation_spec
+            .wave_specs
+            .iter()
+            .find(|s| s.depth == 0 && &s.wave.signal_name == path)
+            .map(|s| s.format_value_at(time));
+        let annotation = match value {
+            Some(value) => format!("{path} = {value} @ {time}"),
+            None => format!("{path} is not probed"),
+        };
+        self.source_viewer.set_annotation(Some(annotation));
+    }
+
+    fn update_viewers_simulation(&mut self) {
+        self.signals_viewer
+            .update_simulation(self.simulation_spec.clone());
+        self.wave_viewer
+            .update_simulation(self.simulation_spec.clone());
+        self.update_source_annotation();
+    }
+
     fn update_signal_viewer_highlight(&mut self) {
         let highlight_idx = self.wave_viewer.get_highlighted_unit_time();
         self.signals_viewer.set_highlight(highlight_idx);
+        self.memory_viewer.set_highlight(highlight_idx);
+        self.update_source_annotation();
     }
 
     fn get_popup_area(rect: Rect) -> Rect {
@@ -207,16 +437,33 @@ impl Root {
 impl sim::Listener for Root {
     fn on_receive_reponse(&mut self, response: &sim::Response) {
         match response {
-            sim::Response::RunResult(Ok(_)) => self.request_simulation_result(),
+            sim::Response::RunResult(Ok(_))
+            | sim::Response::RunUntilResult(Ok(_))
+            | sim::Response::ForceResult(Ok(_))
+            | sim::Response::ReleaseResult(Ok(_))
+            | sim::Response::DepositResult(Ok(_))
+            | sim::Response::RestoreCheckpointResult(Ok(_)) => self.request_simulation_result(),
             sim::Response::LoadResult(Ok(loaded_dut))
+            | sim::Response::LoadWaveformResult(Ok(loaded_dut))
             | sim::Response::ModifyProbedPointsResult(Ok(loaded_dut)) => {
                 self.instance_hier_viewer
                     .write()
                     .unwrap()
                     .set_loaded_dut(loaded_dut);
+                self.diagnostics_viewer
+                    .set_diagnostics(&loaded_dut.diagnostics);
                 self.reload_simulation = true;
                 self.request_simulation_result();
             }
+            sim::Response::LoadResult(Err(load_error))
+            | sim::Response::ModifyProbedPointsResult(Err(load_error)) => {
+                self.diagnostics_viewer
+                    .set_diagnostics(&load_error.diagnostics);
+                if self.diagnostics_viewer.has_diagnostics() {
+                    self.focused_child = Some(Child::DiagnosticsView);
+                    self.notify_render();
+                }
+            }
             sim::Response::SimulationResult(Ok(simulation_result)) => {
                 self.update_simulation_spec(simulation_result);
                 self.notify_render();
@@ -229,7 +476,9 @@ impl sim::Listener for Root {
 impl Root {
     fn update_simulation_spec(&mut self, simulation_result: &SimulationResult) {
         if self.reload_simulation {
-            self.simulation_spec = SimulationSpec::new(simulation_result);
+            let mut simulation_spec = SimulationSpec::new(simulation_result);
+            simulation_spec.inherit_formats(&self.simulation_spec);
+            self.simulation_spec = simulation_spec;
             self.reload_simulation = false;
         } else {
             self.simulation_spec.update(simulation_result);
@@ -238,6 +487,9 @@ impl Root {
             .set_simulation(self.simulation_spec.clone());
         self.wave_viewer
             .set_simulation(self.simulation_spec.clone());
+        self.memory_viewer
+            .set_memories(simulation_result.memories.clone());
+        self.update_source_annotation();
     }
 
     fn request_simulation_result(&self) {
@@ -1,15 +1,16 @@
 use ratatui::{
-    style::{palette::tailwind::SLATE, Modifier, Style},
+    style::{palette::tailwind::SLATE, Color, Modifier, Style},
     symbols,
     text::Line,
     widgets::{List, ListItem, ListState},
 };
 
-use crate::utils::{self, bitvec_str};
+use crate::utils::bitvec_str;
 
 use super::models::{SimulationSpec, WaveSpec};
 
 const SELECTED_STYLE: Style = Style::new().bg(SLATE.c800).add_modifier(Modifier::BOLD);
+const FORCED_STYLE: Style = Style::new().fg(Color::Magenta);
 
 #[derive(Default)]
 pub struct SignalsViewer {
@@ -39,6 +40,15 @@ impl SignalsViewer {
         }
     }
 
+    /// Replaces the simulation without moving the selection.
+    pub fn update_simulation(&mut self, simulation: SimulationSpec) {
+        self.simulation = simulation;
+    }
+
+    pub fn selected_idx(&self) -> Option<usize> {
+        self.selected_idx
+    }
+
     pub fn scroll_down(&mut self) {
         if let Some(idx) = self.selected_idx {
             self.list_state.select_next();
@@ -66,10 +76,15 @@ impl SignalsViewer {
             .iter()
             .enumerate()
             .map(|(i, spec)| {
+                let style = if spec.is_forced {
+                    FORCED_STYLE
+                } else {
+                    Style::default()
+                };
                 if Some(i) == self.selected_idx {
-                    self.new_list_item(spec, width, SELECTED_STYLE)
+                    self.new_list_item(spec, width, style.patch(SELECTED_STYLE))
                 } else {
-                    self.new_list_item(spec, width, Style::default())
+                    self.new_list_item(spec, width, style)
                 }
             })
             .collect()
@@ -87,24 +102,33 @@ impl SignalsViewer {
     }
 
     fn new_signal_description(&self, wave_spec: &WaveSpec) -> String {
+        let format = match (
+            wave_spec.show_enum_names,
+            wave_spec.format,
+            wave_spec.signed,
+        ) {
+            (true, _, _) => "enum",
+            (_, bitvec_str::Format::Decimal, true) => "sdec",
+            (_, format, _) => format.name(),
+        };
+        let forced = if wave_spec.is_forced { " [forced]" } else { "" };
+        let marker = match (wave_spec.is_expandable(), wave_spec.expanded) {
+            (true, true) => "\u{25be} ",
+            (true, false) => "\u{25b8} ",
+            (false, _) => "",
+        };
+        let name = match &wave_spec.field {
+            Some(field) => &field.name,
+            None => &wave_spec.wave.signal_name,
+        };
         format!(
-            "{} [{}:0] ({})",
-            wave_spec.wave.signal_name,
+            "{}{marker}{name} [{}:0] ({}) {format}{forced}",
+            "  ".repeat(wave_spec.depth),
             wave_spec.wave.width,
-            self.get_highlighted_value_of(wave_spec)
+            wave_spec.format_value_at(self.highlight_idx)
         )
     }
 
-    fn get_highlighted_value_of(&self, wave_spec: &WaveSpec) -> String {
-        if let Some((idx, _)) = wave_spec.wave.value_idx_at(self.highlight_idx) {
-            let value = &wave_spec.wave.values[idx].0;
-            let option = bitvec_str::Option::from(wave_spec);
-            utils::bitvec_str::from(value, &option)
-        } else {
-            "x".to_string()
-        }
-    }
-
     fn create_horizontal_line<'a>(width: u16) -> Line<'a> {
         symbols::line::HORIZONTAL.repeat(width as usize).into()
     }
@@ -0,0 +1,89 @@
+use std::path::{Path, PathBuf};
+
+use ratatui::{
+    layout::Rect,
+    style::{palette::tailwind::SLATE, Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+
+const HIGHLIGHTED_LINE_STYLE: Style = Style::new().bg(SLATE.c800).add_modifier(Modifier::BOLD);
+const LINE_NUMBER_STYLE: Style = Style::new().fg(Color::DarkGray);
+const ANNOTATION_STYLE: Style = Style::new()
+    .fg(Color::Yellow)
+    .add_modifier(Modifier::ITALIC);
+
+/// Read-only view of a source file, scrolled to and highlighting a single line.
+#[derive(Default)]
+pub struct SourceViewer {
+    path: Option<PathBuf>,
+    lines: Vec<String>,
+    highlighted_line: Option<usize>,
+    /// Shown at the end of the highlighted line.
+    annotation: Option<String>,
+    scroll: usize,
+    center_on_render: bool,
+}
+
+impl SourceViewer {
+    /// Opens `path` at the 1-based `line`.
+    pub fn open(&mut self, path: &Path, line: usize) {
+        if self.path.as_deref() != Some(path) {
+            self.lines = match std::fs::read_to_string(path) {
+                Ok(content) => content.lines().map(|l| l.replace('\t', "    ")).collect(),
+                Err(e) => vec![format!("cannot read {}: {e}", path.to_string_lossy())],
+            };
+            self.path = Some(path.to_path_buf());
+        }
+        self.highlighted_line = Some(line);
+        self.annotation = None;
+        self.center_on_render = true;
+    }
+
+    pub fn set_annotation(&mut self, annotation: Option<String>) {
+        self.annotation = annotation;
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    pub fn scroll_down(&mut self) {
+        self.scroll = usize::min(self.scroll + 1, self.lines.len().saturating_sub(1));
+    }
+
+    pub fn scroll_up(&mut self) {
+        self.scroll = self.scroll.saturating_sub(1);
+    }
+
+    pub fn render_mut(&mut self, f: &mut Frame, rect: Rect) {
+        if self.center_on_render {
+            let line_idx = self.highlighted_line.unwrap_or(1).saturating_sub(1);
+            self.scroll = line_idx.saturating_sub(rect.height as usize / 2);
+            self.center_on_render = false;
+        }
+        let number_width = self.lines.len().to_string().len();
+        let lines: Vec<Line> = self
+            .lines
+            .iter()
+            .enumerate()
+            .skip(self.scroll)
+            .take(rect.height as usize)
+            .map(|(i, text)| {
+                let mut spans = vec![
+                    Span::styled(format!("{:>number_width$} ", i + 1), LINE_NUMBER_STYLE),
+                    Span::raw(text.clone()),
+                ];
+                if Some(i + 1) != self.highlighted_line {
+                    return Line::from(spans);
+                }
+                if let Some(annotation) = &self.annotation {
+                    spans.push(Span::styled(format!("  // {annotation}"), ANNOTATION_STYLE));
+                }
+                Line::from(spans).style(HIGHLIGHTED_LINE_STYLE)
+            })
+            .collect();
+        f.render_widget(Paragraph::new(lines), rect);
+    }
+}
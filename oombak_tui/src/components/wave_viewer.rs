@@ -39,6 +39,11 @@ impl WaveViewer {
         }
     }
 
+    /// Replaces the simulation without moving the selection or the scroll position.
+    pub fn update_simulation(&mut self, simulation: SimulationSpec) {
+        self.simulation = simulation;
+    }
+
     pub fn scroll_right(&mut self) {
         self.waveform_scroll_state.next();
         self.timebar_state.next();
@@ -85,6 +90,14 @@ impl WaveViewer {
             / (NUMBER_OF_CELLS_PER_UNIT_TIME * 2usize.pow(self.simulation.zoom as u32))
     }
 
+    /// How far to run back for the highlighted unit of time to be the last one simulated, or
+    /// `None` if it already is.
+    pub fn run_back_duration_to_highlight(&self) -> Option<u64> {
+        let end_time = self.get_highlighted_unit_time() + 1;
+        (end_time < self.simulation.total_time)
+            .then(|| (self.simulation.total_time - end_time) as u64)
+    }
+
     pub fn render_mut(&mut self, f: &mut ratatui::Frame, rect: ratatui::prelude::Rect) {
         let mut waveform_scroll_state = self.waveform_scroll_state.clone();
         let items = self.new_list_items(rect.width, &mut waveform_scroll_state);
@@ -1,6 +1,9 @@
 use oombak_sim::sim;
 use oombak_tui::{components, event, render, tui};
-use std::sync::{mpsc, Arc, RwLock};
+use std::{
+    path::PathBuf,
+    sync::{mpsc, Arc, RwLock},
+};
 
 fn main() {
     let terminal = tui::init_terminal().unwrap();
@@ -24,6 +27,13 @@ fn main() {
     let root_clone = Arc::clone(&root);
     event::register_event_listener(root);
 
+    if let Some(dump_path) = std::env::args().nth(1) {
+        simulator
+            .get_request_channel()
+            .send(sim::Request::LoadWaveform(PathBuf::from(dump_path)))
+            .unwrap();
+    }
+
     event::spawn_event_loop();
     render::spawn_renderer(root_clone, terminal, message_rx)
         .join()
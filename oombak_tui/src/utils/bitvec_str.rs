@@ -1,4 +1,5 @@
-use bitvec::{order::Lsb0, prelude::BitVec};
+use bitvec::prelude::BitVec;
+use oombak_sim::sim::{Logic, LogicVec};
 
 use crate::components::models;
 
@@ -9,26 +10,24 @@ pub struct Option {
     pub twos_complement: bool,
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum Format {
     Binary,
+    Octal,
+    Hexadecimal,
+    /// Signed if `Option::twos_complement` is set.
+    Decimal,
+    Ascii,
 }
 
-pub fn from(bit_vec: &BitVec<u32>, option: &Option) -> String {
+pub fn from(logic_vec: &LogicVec, option: &Option) -> String {
+    let logic_vec = get_resized_logic_vec(logic_vec, option.width, option.twos_complement);
     match option.format {
-        Format::Binary => binary(bit_vec, option.width, option.twos_complement),
-    }
-}
-
-pub fn parse(value: &str) -> Result<BitVec<u32>, String> {
-    let chars: Vec<char> = value.chars().collect();
-    if chars.is_empty() {
-        return Err("cannot parse empty value".to_string());
-    }
-    if chars[0] == '0' && chars.len() > 1 {
-        parse_non_decimal(&chars)
-    } else {
-        parse_decimal(&chars)
+        Format::Binary => logic_vec.to_string(),
+        Format::Octal => radix_power_of_2(&logic_vec, 3),
+        Format::Hexadecimal => radix_power_of_2(&logic_vec, 4),
+        Format::Decimal => decimal(&logic_vec, option.twos_complement),
+        Format::Ascii => ascii(&logic_vec),
     }
 }
 
@@ -52,41 +51,178 @@ impl From<&models::WaveSpec> for Option {
     }
 }
 
-fn binary(bit_vec: &BitVec<u32>, width: usize, twos_complement: bool) -> String {
-    let bit_vec = get_resized_bitvec(bit_vec, width, twos_complement);
-    String::from_iter(bit_vec.iter().rev().map(|b| if *b { "1" } else { "0" }))
+impl Format {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Format::Binary => "bin",
+            Format::Octal => "oct",
+            Format::Hexadecimal => "hex",
+            Format::Decimal => "dec",
+            Format::Ascii => "ascii",
+        }
+    }
 }
 
-fn get_resized_bitvec(bit_vec: &BitVec<u32>, width: usize, twos_complement: bool) -> BitVec<u32> {
-    let mut bit_vec = bit_vec.clone();
-    let fills = if twos_complement {
-        *bit_vec.first().as_deref().unwrap_or(&false)
-    } else {
-        false
+/// Digits which are entirely X or Z print as `x` or `z`, and digits which are only partly
+/// unknown print as `X` or `Z`.
+fn radix_power_of_2(logic_vec: &LogicVec, bits_per_digit: usize) -> String {
+    let logics: Vec<Logic> = logic_vec.iter().collect();
+    let mut digits = String::new();
+    for chunk in logics.chunks(bits_per_digit).rev() {
+        let digit = if chunk.iter().all(|l| *l == Logic::X) {
+            'x'
+        } else if chunk.iter().all(|l| *l == Logic::Z) {
+            'z'
+        } else if chunk.contains(&Logic::X) {
+            'X'
+        } else if chunk.contains(&Logic::Z) {
+            'Z'
+        } else {
+            let value = chunk
+                .iter()
+                .rev()
+                .fold(0, |acc, l| acc << 1 | (*l == Logic::One) as u32);
+            char::from_digit(value, 16).expect("digit must be below 16")
+        };
+        digits.push(digit);
+    }
+    digits
+}
+
+fn decimal(logic_vec: &LogicVec, twos_complement: bool) -> String {
+    if logic_vec.all(Logic::Z) {
+        return "z".to_string();
+    }
+    let Some(mut bit_vec) = logic_vec.to_bitvec() else {
+        return "x".to_string();
     };
-    bit_vec.resize(width, fills);
-    bit_vec
+    let is_negative = twos_complement && bit_vec.last().is_some_and(|b| *b);
+    if is_negative {
+        negate(&mut bit_vec);
+    }
+    let digits = unsigned_decimal(&bit_vec);
+    if is_negative {
+        format!("-{digits}")
+    } else {
+        digits
+    }
+}
+
+/// Two's complement negation within the width of `bit_vec`.
+fn negate(bit_vec: &mut BitVec<u32>) {
+    for mut bit in bit_vec.iter_mut() {
+        *bit = !*bit;
+    }
+    for mut bit in bit_vec.iter_mut() {
+        *bit = !*bit;
+        if *bit {
+            break;
+        }
+    }
 }
 
-fn parse_non_decimal(chars: &[char]) -> Result<BitVec<u32>, String> {
-    match chars[1] {
-        'b' => parse_binary(&chars[2..]),
-        c => Err(format!("unknown radix identifier '0{c}'")),
+/// Repeated long division by 10^9 over the 32-bit words of `bit_vec`.
+fn unsigned_decimal(bit_vec: &BitVec<u32>) -> String {
+    const CHUNK: u64 = 1_000_000_000;
+    let mut bit_vec = bit_vec.clone();
+    bit_vec.set_uninitialized(false);
+    let mut words: Vec<u32> = bit_vec.as_raw_slice().to_vec();
+    let mut chunks = vec![];
+    while words.iter().any(|w| *w != 0) {
+        let mut remainder = 0u64;
+        for word in words.iter_mut().rev() {
+            let dividend = remainder << 32 | *word as u64;
+            *word = (dividend / CHUNK) as u32;
+            remainder = dividend % CHUNK;
+        }
+        chunks.push(remainder);
+    }
+    match chunks.split_last() {
+        Some((most_significant, rest)) => rest
+            .iter()
+            .rev()
+            .fold(most_significant.to_string(), |acc, chunk| {
+                format!("{acc}{chunk:09}")
+            }),
+        None => "0".to_string(),
     }
 }
 
-fn parse_binary(chars: &[char]) -> Result<BitVec<u32>, String> {
-    let mut result = bitvec::bitvec![u32, Lsb0;];
-    for c in chars.iter().rev() {
-        match c {
-            '0' => result.push(false),
-            '1' => result.push(true),
-            _ => return Err("binary value can only contain 1's and 0's".to_string()),
+/// Bytes are aligned to the least significant bit and shown from the most significant one.
+/// Bytes with unknown bits or which are not printable show as `.`.
+fn ascii(logic_vec: &LogicVec) -> String {
+    let logics: Vec<Logic> = logic_vec.iter().collect();
+    let mut text = String::new();
+    for chunk in logics.chunks(8).rev() {
+        let byte = chunk.iter().rev().try_fold(0u8, |acc, l| match l {
+            Logic::Zero => Some(acc << 1),
+            Logic::One => Some(acc << 1 | 1),
+            _ => None,
+        });
+        match byte {
+            Some(byte) if byte.is_ascii_graphic() || byte == b' ' => text.push(byte as char),
+            _ => text.push('.'),
         }
     }
-    Ok(result)
+    text
+}
+
+/// X and Z extend as themselves, like unsized literals in SystemVerilog.
+fn get_resized_logic_vec(logic_vec: &LogicVec, width: usize, twos_complement: bool) -> LogicVec {
+    let mut logic_vec = logic_vec.clone();
+    let fill = match logic_vec.iter().next_back() {
+        Some(logic @ (Logic::X | Logic::Z)) => logic,
+        Some(Logic::One) if twos_complement => Logic::One,
+        _ => Logic::Zero,
+    };
+    logic_vec.resize(width, fill);
+    logic_vec
 }
 
-fn parse_decimal(_chars: &[char]) -> Result<BitVec<u32>, String> {
-    Err("parsing decimal value not yet supported".to_string())
+#[cfg(test)]
+mod test {
+    use oombak_sim::sim::LogicVec;
+
+    use super::{from, Format, Option};
+
+    fn format(value: &str, format: Format, width: usize, twos_complement: bool) -> String {
+        let option = Option {
+            format,
+            width,
+            twos_complement,
+        };
+        from(&LogicVec::from_ascii(value.as_bytes()).unwrap(), &option)
+    }
+
+    #[test]
+    fn test_radix_power_of_2() {
+        assert_eq!(format("1011111", Format::Hexadecimal, 7, false), "5f");
+        assert_eq!(format("1011111", Format::Octal, 7, false), "137");
+        assert_eq!(format("xxxx1z01", Format::Hexadecimal, 8, false), "xZ");
+        assert_eq!(format("zzzz0x00", Format::Hexadecimal, 8, false), "zX");
+        assert_eq!(format("1", Format::Hexadecimal, 8, true), "ff");
+    }
+
+    #[test]
+    fn test_decimal() {
+        assert_eq!(format("11111111", Format::Decimal, 8, false), "255");
+        assert_eq!(format("11111111", Format::Decimal, 8, true), "-1");
+        assert_eq!(format("10000000", Format::Decimal, 8, true), "-128");
+        assert_eq!(format("01111111", Format::Decimal, 8, true), "127");
+        assert_eq!(format("0", Format::Decimal, 1, false), "0");
+        assert_eq!(
+            format(&"1".repeat(80), Format::Decimal, 80, false),
+            "1208925819614629174706175"
+        );
+        assert_eq!(format("1x", Format::Decimal, 2, false), "x");
+        assert_eq!(format("zz", Format::Decimal, 2, false), "z");
+    }
+
+    #[test]
+    fn test_ascii() {
+        assert_eq!(format("0100111101001011", Format::Ascii, 16, false), "OK");
+        assert_eq!(format("1001000x00000001", Format::Ascii, 16, false), "..");
+        assert_eq!(format("1000001", Format::Ascii, 7, false), "A");
+        assert_eq!(format("010001000001", Format::Ascii, 12, false), ".A");
+    }
 }
@@ -1,14 +1,13 @@
-use bitvec::vec::BitVec;
-use oombak_sim::sim::Wave;
+use oombak_sim::sim::{Logic, LogicVec, Wave};
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
     prelude::BlockExt,
-    style::{Style, Stylize},
+    style::{Color, Style, Stylize},
     widgets::{Block, StatefulWidget, Widget},
 };
 
-use crate::{components::models::WaveSpec, utils::bitvec_str};
+use crate::components::models::WaveSpec;
 
 const NUMBER_OF_CELLS_PER_UNIT_TIME: usize = 3;
 
@@ -61,8 +60,10 @@ impl StatefulWidget for Waveform<'_> {
     {
         state.set_viewport_length(area.width as usize);
         let (value_count_pairs, start_skip) = self.trim_wave_values(&self.wave_spec.wave, state);
-        let lines = self.plot_values_as_lines(value_count_pairs, start_skip, state.viewport_length);
+        let (lines, regions) =
+            self.plot_values_as_lines(value_count_pairs, start_skip, state.viewport_length);
         self.render_lines(&lines, area, buf);
+        self.add_value_highlights(buf, area, &regions, start_skip, state.viewport_length);
         self.add_cursor_highlight(buf, area, state.selected_position, lines.len() as u16);
     }
 }
@@ -72,7 +73,7 @@ impl Waveform<'_> {
         &self,
         wave: &Wave,
         state: &WaveformScrollState,
-    ) -> (Vec<(BitVec<u32>, usize)>, usize) {
+    ) -> (Vec<(LogicVec, usize)>, usize) {
         let unit_size = NUMBER_OF_CELLS_PER_UNIT_TIME * 2usize.pow(self.zoom as u32);
         let start_time = state.start_position / unit_size;
         let mut start_cut = state.start_position % unit_size;
@@ -80,10 +81,19 @@ impl Waveform<'_> {
             (state.start_position + usize::saturating_sub(state.viewport_length, 1)) / unit_size;
         let mut result = vec![];
 
-        if let Some((start_idx, start_offset)) = wave.value_idx_at(start_time) {
-            let (end_idx, end_offset) = wave
-                .value_idx_at(end_time)
-                .unwrap_or((wave.values.len() - 1, wave.values.last().unwrap().2 - 1));
+        // The last value may have been recorded at the end of the run and hold no time yet
+        let held_idx_at = |time| {
+            wave.value_idx_at(time)
+                .filter(|(idx, _)| wave.values[*idx].2 > 0)
+        };
+        if let Some((start_idx, start_offset)) = held_idx_at(start_time) {
+            let last_idx = wave
+                .values
+                .iter()
+                .rposition(|v| v.2 > 0)
+                .unwrap_or(start_idx);
+            let (end_idx, end_offset) =
+                held_idx_at(end_time).unwrap_or((last_idx, wave.values[last_idx].2 - 1));
 
             let start_value = wave.values[start_idx].0.clone();
             let mut start_count = usize::min(
@@ -117,23 +127,35 @@ impl Waveform<'_> {
 
     fn plot_values_as_lines(
         &self,
-        value_count_pairs: Vec<(BitVec<u32>, usize)>,
+        value_count_pairs: Vec<(LogicVec, usize)>,
         skip_start: usize,
         viewport_length: usize,
-    ) -> Vec<String> {
+    ) -> (Vec<String>, Vec<ValueRegion>) {
         let height = self.wave_spec.height as usize;
         let mut lines = vec![String::new(); 2 * height + 1];
+        let mut regions = vec![];
+        let mut column = 0;
         for (c, (value, count)) in value_count_pairs.iter().enumerate() {
             let is_end_value = c == value_count_pairs.len() - 1;
-            let word = self.format(value, *count);
+            let kind = ValueKind::from(value);
+            let word = self.format(value, *count, kind);
             Self::draw_opening(&mut lines, &word, height);
-            Self::draw_body(&mut lines, &word, height);
+            Self::draw_body(&mut lines, &word, height, kind);
             Self::draw_tail(&mut lines, &word, height, is_end_value);
+            if kind != ValueKind::Known {
+                regions.push(ValueRegion {
+                    start: column + 1,
+                    end: column + word.len() - 1,
+                    kind,
+                });
+            }
+            column += word.len() - 1;
         }
-        lines
+        let lines = lines
             .iter()
             .map(|l| l.chars().skip(skip_start).take(viewport_length).collect())
-            .collect()
+            .collect();
+        (lines, regions)
     }
 
     fn render_lines(&self, lines: &[String], area: Rect, buf: &mut Buffer) {
@@ -163,9 +185,36 @@ impl Waveform<'_> {
         );
     }
 
-    fn format(&self, value: &BitVec<u32>, count: usize) -> Vec<char> {
-        let option = bitvec_str::Option::from(self.wave_spec);
-        let value = bitvec_str::from(value, &option);
+    /// Colors values with unknown bits, leaving the transitions around them as they are.
+    fn add_value_highlights(
+        &self,
+        buf: &mut Buffer,
+        area: Rect,
+        regions: &[ValueRegion],
+        skip_start: usize,
+        viewport_length: usize,
+    ) {
+        let area = self.block.inner_if_some(area);
+        let height = 2 * self.wave_spec.height + 1;
+        for region in regions {
+            let start = usize::max(region.start, skip_start) - skip_start;
+            let end = usize::min(region.end.saturating_sub(skip_start), viewport_length);
+            if start >= end {
+                continue;
+            }
+            let color = match region.kind {
+                ValueKind::HighImpedance => Color::Yellow,
+                _ => Color::Red,
+            };
+            buf.set_style(
+                Rect::new(area.x + start as u16, area.y, (end - start) as u16, height),
+                Style::default().fg(color),
+            );
+        }
+    }
+
+    fn format(&self, value: &LogicVec, count: usize, kind: ValueKind) -> Vec<char> {
+        let value = self.wave_spec.format_value(value);
         let str_width = NUMBER_OF_CELLS_PER_UNIT_TIME * 2usize.pow(self.zoom as u32) * count + 1;
         let res = if str_width - 2 >= value.len() {
             format!("{:^1$}", value, str_width)
@@ -174,7 +223,12 @@ impl Waveform<'_> {
             let snip = &value[0..snip_size];
             format!(" {snip}~ ")
         };
-        res.chars().take(str_width).collect()
+        let res = res.chars().take(str_width);
+        if kind == ValueKind::HighImpedance {
+            res.map(|c| if c == ' ' { '\u{2500}' } else { c }).collect()
+        } else {
+            res.collect()
+        }
     }
 
     fn draw_opening(lines: &mut [String], word: &[char], height: usize) {
@@ -221,13 +275,17 @@ impl Waveform<'_> {
         }
     }
 
-    fn draw_body(lines: &mut [String], word: &[char], height: usize) {
+    /// A high-impedance value is drawn as a line halfway between the rails.
+    fn draw_body(lines: &mut [String], word: &[char], height: usize, kind: ValueKind) {
         let head_length = height + 1;
         let body_length = word.len() - 2 * head_length;
+        let draws_rails = kind != ValueKind::HighImpedance;
         for c in word.iter().skip(head_length).take(body_length) {
             for (j, line) in lines.iter_mut().enumerate() {
                 if j == height {
                     *line += &format!("{}", c);
+                } else if !draws_rails {
+                    *line += " ";
                 } else if j == 0 {
                     *line += "\u{2594}";
                 } else if j == height * 2 {
@@ -240,6 +298,34 @@ impl Waveform<'_> {
     }
 }
 
+#[derive(Clone, Copy, PartialEq)]
+enum ValueKind {
+    Known,
+    /// At least one bit is X or Z.
+    Unknown,
+    /// Every bit is Z.
+    HighImpedance,
+}
+
+impl From<&LogicVec> for ValueKind {
+    fn from(value: &LogicVec) -> Self {
+        if value.all(Logic::Z) {
+            ValueKind::HighImpedance
+        } else if value.is_known() {
+            ValueKind::Known
+        } else {
+            ValueKind::Unknown
+        }
+    }
+}
+
+/// Columns `start..end` of the plotted lines, before they are scrolled.
+struct ValueRegion {
+    start: usize,
+    end: usize,
+    kind: ValueKind,
+}
+
 #[derive(Default, Clone)]
 pub struct WaveformScrollState {
     start_position: usize,